@@ -1,6 +1,12 @@
 use crossterm::event::{KeyCode, KeyEvent};
 
-use crate::{App, branch_input::BranchInput, git, regions::Region, ui::branches};
+use crate::{
+    App,
+    branch_input::{BranchInput, BranchInputPurpose},
+    git,
+    regions::Region,
+    ui::branches,
+};
 
 impl App {
     pub fn refresh_branches(&mut self) {
@@ -19,6 +25,7 @@ impl App {
             match key_event.code {
                 KeyCode::Esc => self.branch_input = None,
                 KeyCode::Enter => self.submit_branch_input(),
+                KeyCode::Tab => input.toggle_rename_upstream(),
                 code => input.handle_edit_key(code),
             }
         }
@@ -34,13 +41,51 @@ impl App {
                 self.start_branch_input();
                 None
             }
-            KeyCode::Up
-            | KeyCode::Down
-            | KeyCode::Enter
-            | KeyCode::Delete
-            | KeyCode::Char('x')
-            | KeyCode::Char('u')
-            | KeyCode::Char('p') => branches::handle_key(&mut self.selected_branch, code),
+            KeyCode::Char('u') => {
+                self.update_current_branch();
+                None
+            }
+            KeyCode::Char('p') => {
+                self.push_current_branch();
+                None
+            }
+            KeyCode::Char('m') => {
+                self.start_merge();
+                None
+            }
+            KeyCode::Char('r') => {
+                self.start_rebase_onto();
+                None
+            }
+            KeyCode::Char('t') => {
+                self.start_upstream_picker();
+                None
+            }
+            KeyCode::Char('B') => {
+                self.start_branch_from_hovered();
+                None
+            }
+            KeyCode::Char('n') => {
+                self.start_rename_branch();
+                None
+            }
+            KeyCode::Char('I') => {
+                self.toggle_integration_branch();
+                None
+            }
+            KeyCode::Delete | KeyCode::Char('x') => {
+                match branches::hovered_remote_delete(&self.selected_branch) {
+                    Some(Ok(delete)) => {
+                        self.delete_remote_branch(delete);
+                        None
+                    }
+                    Some(Err(message)) => Some(message),
+                    None => branches::handle_key(&mut self.selected_branch, code),
+                }
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::Enter => {
+                branches::handle_key(&mut self.selected_branch, code)
+            }
             _ => None,
         } {
             self.show_notification(message);
@@ -49,6 +94,95 @@ impl App {
         self.refresh_commits();
     }
 
+    /// Deletes a branch on its remote, and the local branch along with it. The
+    /// push runs in the background and can be cancelled.
+    fn delete_remote_branch(&mut self, delete: branches::RemoteDelete) {
+        let branches::RemoteDelete {
+            remote_ref,
+            local_name,
+            has_local,
+        } = delete;
+        self.spawn_operation(format!("Deleting {remote_ref}"), true, move |cancel| {
+            let remote_missing = match git::delete_remote_branch(&remote_ref, cancel) {
+                Ok(()) => false,
+                Err(err) if err.to_lowercase().contains("remote ref does not exist") => true,
+                Err(err) => return Err(format!("Delete failed: {err}")),
+            };
+            let _ = git::delete_remote_tracking_ref(&remote_ref);
+
+            if has_local || git::local_branch_exists(&local_name) {
+                git::delete_branch(&local_name).map_err(|err| {
+                    format!("Deleted {remote_ref}, but failed to delete {local_name}: {err}")
+                })?;
+                return Ok(if remote_missing {
+                    format!("Remote already gone; deleted {local_name}")
+                } else {
+                    format!("Deleted {remote_ref} and {local_name}")
+                });
+            }
+            Ok(if remote_missing {
+                "Remote already gone".to_string()
+            } else {
+                format!("Deleted {remote_ref}")
+            })
+        });
+    }
+
+    /// Makes the hovered branch the one every branch is compared against, or
+    /// goes back to main/master when it already is.
+    fn toggle_integration_branch(&mut self) {
+        let info = &self.selected_branch;
+        let Some(branch) = info.hovered.and_then(|idx| info.branches.get(idx)) else {
+            return;
+        };
+        let name = if branch.has_local {
+            branch.name.clone()
+        } else {
+            branch
+                .remote_ref
+                .clone()
+                .unwrap_or_else(|| branch.name.clone())
+        };
+        let unset = info.integration_branch.as_deref() == Some(name.as_str());
+        let result = git::set_integration_branch((!unset).then_some(name.as_str()));
+        self.refresh_branches();
+        match result {
+            Ok(()) => {
+                let message = match &self.selected_branch.integration_branch {
+                    Some(integration) => format!("Comparing branches against {integration}"),
+                    None => "No integration branch to compare against".to_string(),
+                };
+                self.show_notification(message);
+            }
+            Err(err) => self.show_notification(err),
+        }
+    }
+
+    fn update_current_branch(&mut self) {
+        let current = self.selected_branch.current.clone();
+        self.spawn_operation("Updating branches", true, move |cancel| {
+            git::fetch_remotes(cancel)?;
+            let Some(current) = current else {
+                return Ok("Fetched remote branches".to_string());
+            };
+            git::pull_current_branch(cancel)
+                .map(|()| format!("Updated {current}"))
+                .map_err(|err| format!("Update {current} failed: {err}"))
+        });
+    }
+
+    fn push_current_branch(&mut self) {
+        let Some(current) = self.selected_branch.current.clone() else {
+            self.show_notification("No current branch to push".to_string());
+            return;
+        };
+        self.spawn_operation(format!("Pushing {current}"), true, move |cancel| {
+            git::push_current_branch(cancel)
+                .map(|()| format!("Updated {current}"))
+                .map_err(|err| format!("Push {current} failed: {err}"))
+        });
+    }
+
     pub fn submit_branch_input(&mut self) {
         let Some(input) = self.branch_input.as_mut() else {
             return;
@@ -60,17 +194,89 @@ impl App {
             return;
         }
 
-        match git::create_branch(name) {
+        let result = match &input.purpose {
+            BranchInputPurpose::Create => git::create_branch(name),
+            BranchInputPurpose::CreateFrom(start) => git::create_branch_from(name, start),
+            BranchInputPurpose::FromStash(index) => git::branch_from_stash(name, *index),
+            BranchInputPurpose::Rename { branch, .. } => git::rename_branch(branch, name),
+        };
+
+        match result {
             Ok(()) => {
+                let name = name.to_string();
                 let mut previous = std::mem::take(&mut self.selected_branch);
-                previous.selected = Some(name.to_string());
-                previous.current = Some(name.to_string());
+                previous.selected = Some(name.clone());
+                previous.current = Some(name.clone());
                 self.selected_branch = branches::refresh(previous);
-                self.branch_input = None;
+                let input = self.branch_input.take();
+                self.refresh_after_change();
+                if let Some(input) = input {
+                    self.finish_branch_input(input, name);
+                }
             }
             Err(err) => {
                 input.error = Some(err);
             }
         }
     }
+
+    /// Reports a rename, and renames the upstream too when that was asked for.
+    fn finish_branch_input(&mut self, input: BranchInput, name: String) {
+        let BranchInputPurpose::Rename { branch, upstream } = input.purpose else {
+            return;
+        };
+        match upstream.filter(|_| input.rename_upstream) {
+            Some(upstream) => {
+                self.spawn_operation(format!("Renaming {upstream}"), true, move |cancel| {
+                    git::rename_upstream_branch(&name, &upstream, cancel)
+                        .map(|renamed| {
+                            format!("Renamed {branch} to {name}, now tracking {renamed}")
+                        })
+                        .map_err(|err| {
+                            format!("Renamed {branch} to {name}, but not {upstream}: {err}")
+                        })
+                });
+            }
+            None => self.show_notification(format!("Renamed {branch} to {name}")),
+        }
+    }
+
+    /// Opens the branch popup to name a branch starting at `start_point`.
+    pub fn start_branch_from(&mut self, start_point: String) {
+        let purpose = BranchInputPurpose::CreateFrom(start_point);
+        self.branch_input = Some(BranchInput::new(purpose));
+    }
+
+    fn start_rename_branch(&mut self) {
+        let info = &self.selected_branch;
+        let Some(branch) = info.hovered.and_then(|idx| info.branches.get(idx)) else {
+            return;
+        };
+        if !branch.has_local {
+            let message = format!(
+                "{} is only on the remote; check it out to rename it",
+                branch.name
+            );
+            self.show_notification(message);
+            return;
+        }
+        let input = BranchInput::rename(branch.name.clone(), branch.upstream.clone());
+        self.branch_input = Some(input);
+    }
+
+    fn start_branch_from_hovered(&mut self) {
+        let info = &self.selected_branch;
+        let Some(branch) = info.hovered.and_then(|idx| info.branches.get(idx)) else {
+            return;
+        };
+        let start = if branch.has_local {
+            branch.name.clone()
+        } else {
+            branch
+                .remote_ref
+                .clone()
+                .unwrap_or_else(|| branch.name.clone())
+        };
+        self.start_branch_from(start);
+    }
 }
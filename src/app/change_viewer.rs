@@ -0,0 +1,79 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App,
+    git::{self, PatchAction},
+    regions::Region,
+    ui::diff::DiffState,
+};
+
+impl App {
+    pub fn handle_change_viewer_key(&mut self, code: KeyCode) {
+        if self.popup_region != Region::ChangeViewer {
+            return;
+        }
+
+        match code {
+            KeyCode::Up => self.change_diff.move_up(),
+            KeyCode::Down => self.change_diff.move_down(),
+            KeyCode::PageUp => self.change_diff.page_up(),
+            KeyCode::PageDown => self.change_diff.page_down(),
+            KeyCode::Home => self.change_diff.move_to_top(),
+            KeyCode::End => self.change_diff.move_to_bottom(),
+            KeyCode::Char('n') => self.change_diff.next_hunk(),
+            KeyCode::Char('p') => self.change_diff.previous_hunk(),
+            KeyCode::Char(' ') => self.change_diff.toggle_anchor(),
+            KeyCode::Enter => {
+                let staged = self
+                    .change_diff
+                    .change
+                    .as_ref()
+                    .is_some_and(|change| change.staged);
+                if staged {
+                    self.apply_diff_selection(PatchAction::Unstage);
+                } else {
+                    self.apply_diff_selection(PatchAction::Stage);
+                }
+            }
+            KeyCode::Char('x') => self.apply_diff_selection(PatchAction::Discard),
+            _ => {}
+        }
+    }
+
+    pub fn refresh_change_diff(&mut self) {
+        if !self.show_changes_popup {
+            self.change_diff = DiffState::default();
+            return;
+        }
+
+        let change = self
+            .selected_change
+            .and_then(|idx| self.repo_status.changes.get(idx))
+            .cloned();
+        let previous = std::mem::take(&mut self.change_diff);
+        self.change_diff = DiffState::refresh(previous, change);
+    }
+
+    fn apply_diff_selection(&mut self, action: PatchAction) {
+        let (Some(change), Some(diff)) = (
+            self.change_diff.change.clone(),
+            self.change_diff.diff.as_ref(),
+        ) else {
+            return;
+        };
+
+        let selected = self.change_diff.selected_lines();
+        if selected.is_empty() {
+            self.show_notification("Move to a hunk or mark lines first".to_string());
+            return;
+        }
+
+        if let Err(err) = git::apply_partial_change(&change, diff, &selected, action) {
+            self.show_notification(err);
+            return;
+        }
+
+        self.change_diff.anchor = None;
+        self.refresh_after_change();
+    }
+}
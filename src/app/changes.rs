@@ -1,6 +1,10 @@
 use crossterm::event::KeyCode;
 
-use crate::{App, git, regions::Region};
+use crate::{
+    App,
+    git::{self, ChangeType},
+    regions::Region,
+};
 
 impl App {
     pub fn handle_changes_popup_key(&mut self, code: KeyCode) {
@@ -21,11 +25,13 @@ impl App {
         let len = self.repo_status.changes.len();
         if len == 0 {
             self.selected_change = None;
+            self.refresh_change_diff();
             return;
         }
 
         let current = self.selected_change.unwrap_or(0).min(len - 1);
         self.selected_change = Some(current);
+        self.refresh_change_diff();
     }
 
     fn move_change_selection(&mut self, delta: isize) {
@@ -38,6 +44,7 @@ impl App {
         let current = self.selected_change.unwrap_or(0).min(len - 1) as isize;
         let next = (current + delta).clamp(0, len.saturating_sub(1) as isize) as usize;
         self.selected_change = Some(next);
+        self.refresh_change_diff();
     }
 
     fn toggle_stage_selected_change(&mut self) {
@@ -49,6 +56,10 @@ impl App {
         };
 
         let path = change.path.clone();
+        if change.change == ChangeType::Unmerged {
+            self.open_conflicts(&path);
+            return;
+        }
         let result = if change.staged {
             git::unstage_change(&path)
         } else {
@@ -60,8 +71,7 @@ impl App {
             return;
         }
 
-        self.refresh_status();
-        self.reselect_change(Some(path));
+        self.refresh_after_change();
     }
 
     fn discard_selected_change(&mut self) {
@@ -78,25 +88,32 @@ impl App {
             return;
         }
 
-        self.refresh_status();
-        self.reselect_change(None);
+        self.refresh_after_change();
     }
 
-    pub fn reselect_change(&mut self, preferred_path: Option<String>) {
+    pub fn reselect_change(&mut self, preferred: Option<(String, bool)>) {
         let len = self.repo_status.changes.len();
         if len == 0 {
             self.selected_change = None;
+            self.refresh_change_diff();
             return;
         }
 
-        if let Some(path) = preferred_path {
-            if let Some(idx) = self.repo_status.changes.iter().position(|c| c.path == path) {
-                self.selected_change = Some(idx);
-                return;
-            }
+        if let Some((path, staged)) = preferred
+            && let Some(idx) = self
+                .repo_status
+                .changes
+                .iter()
+                .position(|c| c.path == path && c.staged == staged)
+                .or_else(|| self.repo_status.changes.iter().position(|c| c.path == path))
+        {
+            self.selected_change = Some(idx);
+            self.refresh_change_diff();
+            return;
         }
 
         let current = self.selected_change.unwrap_or(0).min(len - 1);
         self.selected_change = Some(current);
+        self.refresh_change_diff();
     }
 }
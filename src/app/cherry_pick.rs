@@ -0,0 +1,73 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App,
+    git::{self, OperationKind},
+    ui::cherry_pick::{PickAction, PickPrompt},
+};
+
+impl App {
+    /// Asks to cherry-pick or revert the marked commits, or the hovered one.
+    pub fn start_pick(&mut self, action: PickAction) {
+        if let Some(operation) = self.repo_status.operation {
+            self.show_notification(format!(
+                "Finish the {} in progress first",
+                operation.kind.as_str()
+            ));
+            return;
+        }
+        let Some(target) = self.selected_branch.current.clone() else {
+            self.show_notification("No current branch to apply commits to".to_string());
+            return;
+        };
+        let commits = self.commits.picked_commits();
+        if commits.is_empty() {
+            return;
+        }
+        self.pick_prompt = Some(PickPrompt {
+            action,
+            commits,
+            target,
+            record_origin: false,
+        });
+    }
+
+    pub fn handle_pick_prompt_key(&mut self, code: KeyCode) {
+        let Some(prompt) = self.pick_prompt.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Char('x') if prompt.action == PickAction::CherryPick => {
+                prompt.record_origin = !prompt.record_origin;
+            }
+            KeyCode::Enter => self.run_pick(),
+            KeyCode::Esc => self.pick_prompt = None,
+            _ => {}
+        }
+    }
+
+    fn run_pick(&mut self) {
+        let Some(prompt) = self.pick_prompt.take() else {
+            return;
+        };
+        let ids = prompt.ids();
+        let count = prompt.count_label();
+        match prompt.action {
+            PickAction::CherryPick => {
+                let finished = format!("Cherry-picked {count} onto {}", prompt.target);
+                let record_origin = prompt.record_origin;
+                let label = format!("Cherry-picking {count}");
+                self.spawn_operation_step(label, OperationKind::CherryPick, finished, move || {
+                    git::cherry_pick_commits(&ids, record_origin)
+                });
+            }
+            PickAction::Revert => {
+                let finished = format!("Reverted {count} on {}", prompt.target);
+                let label = format!("Reverting {count}");
+                self.spawn_operation_step(label, OperationKind::Revert, finished, move || {
+                    git::revert_commits(&ids)
+                });
+            }
+        }
+    }
+}
@@ -1,42 +1,93 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::{App, git, ui::input::TextInput};
+use crate::{
+    App,
+    app::jobs::JobOutput,
+    git,
+    ui::{message_editor::MessageEditor, popup::AmendState},
+};
 
 impl App {
-    pub fn handle_commit_message_key(&mut self, code: KeyCode) {
+    /// Edits the commit message; Enter starts a new line and Ctrl+S (or
+    /// Ctrl+Enter where the terminal reports it) commits.
+    pub fn handle_commit_message_key(&mut self, key_event: KeyEvent) {
         if !self.commit_message_editing {
             return;
         }
 
-        match code {
-            KeyCode::Enter => {
-                if self.commit_input.value.trim().is_empty() {
-                    self.show_notification("Commit message cannot be empty".to_string());
-                    return;
-                }
-                match git::commit_staged(&self.commit_input.value) {
-                    Ok(()) => {
-                        let summary = self.commit_input.value.clone();
-                        self.commit_input = TextInput::default();
-                        self.refresh_status();
-                        self.refresh_commits();
-                        self.show_notification(format!("Committed: {summary}"));
-                        self.commit_message_editing = false;
-                        self.show_changes_popup = false;
-                    }
-                    Err(err) => self.show_notification(err),
-                }
-            }
-            KeyCode::Backspace => {
-                self.commit_input.handle_key(KeyCode::Backspace);
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        match key_event.code {
+            KeyCode::Char('s') | KeyCode::Enter if ctrl => self.commit_message(),
+            code if !ctrl => self.commit_input.handle_key(code),
+            _ => {}
+        }
+    }
+
+    fn commit_message(&mut self) {
+        if self.commit_input.is_blank() {
+            self.show_notification("Commit message cannot be empty".to_string());
+            return;
+        }
+        if self.commit_job.is_some_and(|id| self.jobs.is_running(id)) {
+            return;
+        }
+        let message = self.commit_input.value.trim_end().to_string();
+        let amend = self.commit_amend.is_some();
+        let label = if amend { "Amending" } else { "Committing" };
+        let id = self.jobs.spawn(label, false, move |_| {
+            let result = if amend {
+                git::amend_head(&message)
+            } else {
+                git::commit_staged(&message)
+            };
+            JobOutput::Commit {
+                amend,
+                result: result.map(|()| message),
             }
-            KeyCode::Char(c) if !c.is_control() => {
-                self.commit_input.handle_key(KeyCode::Char(c));
+        });
+        self.commit_job = Some(id);
+    }
+
+    /// Switches the commit message slot between a new commit and amending HEAD,
+    /// which starts from HEAD's message and keeps the draft for switching back.
+    pub fn toggle_amend(&mut self) {
+        if let Some(amend) = self.commit_amend.take() {
+            self.commit_input = amend.draft;
+            return;
+        }
+        let message = match git::fetch_head_message() {
+            Ok(message) => message,
+            Err(err) => {
+                self.show_notification(err);
+                return;
             }
-            KeyCode::Delete | KeyCode::Left | KeyCode::Right | KeyCode::Home | KeyCode::End => {
-                self.commit_input.handle_key(code);
+        };
+        let draft = std::mem::replace(&mut self.commit_input, MessageEditor::new(message));
+        let pushed_to = git::head_pushed_to();
+        if let Some(upstream) = &pushed_to {
+            self.show_notification(format!(
+                "HEAD is already on {upstream}; amending rewrites pushed history"
+            ));
+        }
+        self.commit_amend = Some(AmendState { draft, pushed_to });
+    }
+
+    pub fn finish_commit(&mut self, amend: bool, result: Result<String, String>) {
+        match result {
+            Ok(message) => {
+                let summary = message.lines().next().unwrap_or_default();
+                self.commit_input = MessageEditor::default();
+                self.commit_amend = None;
+                self.refresh_after_change();
+                if amend {
+                    self.show_notification(format!("Amended HEAD: {summary}"));
+                } else {
+                    self.show_notification(format!("Committed: {summary}"));
+                }
+                self.commit_message_editing = false;
+                self.show_changes_popup = false;
             }
-            _ => {}
+            Err(err) => self.show_notification(err),
         }
     }
 }
@@ -1,11 +1,109 @@
 use crossterm::event::KeyCode;
 
-use crate::{App, regions::Region, ui::commits};
+use crate::{
+    App,
+    regions::Region,
+    ui::{cherry_pick::PickAction, commit_search::CommitQuery, commits},
+};
 
 impl App {
     pub fn refresh_commits(&mut self) {
-        self.commits = commits::CommitsState::refresh(self.hovered_commit_id.as_deref());
+        self.commits_generation += 1;
+        let fetched = commits::CommitsFetch::load(
+            self.commits.ref_tips.as_deref(),
+            self.commits.limit,
+            &self.commits.query.filter,
+        );
+        self.apply_commits(fetched);
+    }
+
+    /// Re-reads the log with `limit` commits even if no ref moved.
+    fn reload_commits(&mut self, limit: usize) {
+        self.commits_generation += 1;
+        let fetched = commits::CommitsFetch::load(None, limit, &self.commits.query.filter);
+        self.apply_commits(fetched);
+    }
+
+    /// Appends the next page of the log to the list.
+    fn load_commit_page(&mut self) {
+        self.commits_generation += 1;
+        let fetched = commits::CommitsFetch::next_page(&self.commits);
+        self.apply_commits(fetched);
+    }
+
+    fn load_next_commit_page(&mut self) {
+        if self.commits.wants_next_page() {
+            self.load_commit_page();
+        }
+    }
+
+    pub fn apply_commits(&mut self, fetched: commits::CommitsFetch) {
+        let previous = std::mem::take(&mut self.commits);
+        self.commits =
+            commits::CommitsState::apply(previous, fetched, self.hovered_commit_id.as_deref());
         self.hovered_commit_id = self.commits.hovered_commit_id().map(|id| id.to_string());
+        self.refresh_details();
+    }
+
+    pub fn handle_commit_search_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.commits.search_input = None,
+            KeyCode::Enter => {
+                let input = self.commits.search_input.take().unwrap_or_default();
+                self.apply_commit_query(CommitQuery::parse(&input.value));
+            }
+            code => {
+                if let Some(input) = self.commits.search_input.as_mut() {
+                    input.handle_key(code);
+                }
+            }
+        }
+    }
+
+    fn apply_commit_query(&mut self, query: CommitQuery) {
+        let filter_changed = query.filter != self.commits.query.filter;
+        let has_text = query.text.is_some();
+        self.commits.query = query;
+        if filter_changed {
+            self.reload_commits(commits::PAGE_SIZE);
+        }
+        if has_text {
+            self.jump_to_next_match(self.commits.hovered.unwrap_or(0));
+        }
+    }
+
+    /// Moves to the next match at or after `start`. Matches may sit past the
+    /// loaded pages, so up to [`commits::SEARCH_PAGES`] more are loaded before
+    /// stopping at the last commit; pressing `n` there keeps searching.
+    fn jump_to_next_match(&mut self, start: usize) {
+        let mut pages = 0;
+        while self.commits.match_from(start).is_none() && self.commits.has_more() {
+            if pages == commits::SEARCH_PAGES {
+                let searched = self.commits.commits.len().saturating_sub(start);
+                self.commits.move_to_bottom();
+                self.show_notification(format!(
+                    "No match in the next {searched} commits: [n] keeps searching"
+                ));
+                return;
+            }
+            self.load_commit_page();
+            pages += 1;
+        }
+        let found = self
+            .commits
+            .match_from(start)
+            .or_else(|| self.commits.match_from(0));
+        self.hover_match(found);
+    }
+
+    fn hover_match(&mut self, found: Option<usize>) {
+        match found {
+            Some(idx) => self.commits.hovered = Some(idx),
+            None => {
+                let text = self.commits.query.text.clone().unwrap_or_default();
+                self.show_notification(format!("No commits match \"{text}\""));
+            }
+        }
     }
 
     pub fn handle_commits_region_keys(&mut self, code: KeyCode) {
@@ -16,9 +114,37 @@ impl App {
         match code {
             KeyCode::Up => self.commits.move_hover_up(),
             KeyCode::Down => self.commits.move_hover_down(),
+            KeyCode::PageUp => self.commits.page_up(),
+            KeyCode::PageDown => self.commits.page_down(),
+            KeyCode::Home => self.commits.move_to_top(),
+            KeyCode::End => self.commits.move_to_bottom(),
+            KeyCode::Char('/') => self.commits.start_search(),
+            KeyCode::Char('i') => self.open_rebase_plan(),
+            KeyCode::Char(' ') => self.commits.toggle_mark(),
+            KeyCode::Char('p') => self.start_pick(PickAction::CherryPick),
+            KeyCode::Char('v') => self.start_pick(PickAction::Revert),
+            KeyCode::Char('X') => self.start_reset(),
+            KeyCode::Char('t') => self.start_tag(),
+            KeyCode::Char('B') => {
+                if let Some(id) = self.commits.hovered_commit_id().map(str::to_string) {
+                    self.start_branch_from(id);
+                }
+            }
+            KeyCode::Char('n') if self.commits.query.text.is_some() => {
+                self.jump_to_next_match(self.commits.hovered.map_or(0, |idx| idx + 1));
+            }
+            KeyCode::Char('N') if self.commits.query.text.is_some() => {
+                self.hover_match(self.commits.previous_match());
+            }
+            KeyCode::Esc if !self.commits.marked.is_empty() => self.commits.marked.clear(),
+            KeyCode::Esc if !self.commits.query.is_empty() => {
+                self.apply_commit_query(CommitQuery::default());
+            }
             _ => {}
         }
 
+        self.load_next_commit_page();
         self.hovered_commit_id = self.commits.hovered_commit_id().map(|id| id.to_string());
+        self.refresh_details();
     }
 }
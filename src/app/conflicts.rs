@@ -0,0 +1,82 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App,
+    git::{self, ConflictSide},
+    ui::conflicts::ConflictsState,
+};
+
+impl App {
+    /// Opens the conflict view on `file`, listing every conflicted file.
+    pub fn open_conflicts(&mut self, file: &str) {
+        let files = self.repo_status.conflicted_files();
+        self.conflicts = Some(ConflictsState::open(files, file));
+    }
+
+    pub fn handle_conflicts_key(&mut self, code: KeyCode) {
+        let Some(conflicts) = self.conflicts.as_mut() else {
+            return;
+        };
+
+        match code {
+            KeyCode::Up => conflicts.move_file_up(),
+            KeyCode::Down => conflicts.move_file_down(),
+            KeyCode::Char('n') => conflicts.next_hunk(),
+            KeyCode::Char('p') => conflicts.previous_hunk(),
+            KeyCode::Char('o') => conflicts.pick(ConflictSide::Ours),
+            KeyCode::Char('t') => conflicts.pick(ConflictSide::Theirs),
+            KeyCode::Char('b') => conflicts.pick(ConflictSide::Both),
+            KeyCode::Char('u') => conflicts.clear_pick(),
+            KeyCode::Enter => self.resolve_hovered_conflict(),
+            KeyCode::Esc | KeyCode::Char('q') => self.conflicts = None,
+            _ => {}
+        }
+    }
+
+    fn resolve_hovered_conflict(&mut self) {
+        let Some(conflicts) = self.conflicts.as_ref() else {
+            return;
+        };
+        let Some(file) = conflicts.hovered_file().map(str::to_string) else {
+            return;
+        };
+
+        let no_hunks_left =
+            matches!(&conflicts.conflict, Some(Ok(conflict)) if conflict.hunk_count() == 0);
+        let result = if no_hunks_left {
+            git::stage_change(&file)
+        } else {
+            match conflicts.resolved_contents() {
+                Ok(_) if conflicts.deletes_file() => git::remove_conflicted(&file),
+                Ok(contents) => git::resolve_conflict(&file, &contents),
+                Err(err) => {
+                    self.show_notification(err);
+                    return;
+                }
+            }
+        };
+        if let Err(err) = result {
+            self.show_notification(err);
+            return;
+        }
+
+        self.refresh_after_change();
+        let Some(conflicts) = self.conflicts.as_mut() else {
+            return;
+        };
+        conflicts.remove_file(&file);
+        if !conflicts.files.is_empty() {
+            self.show_notification(format!("Resolved {file}"));
+            return;
+        }
+        self.conflicts = None;
+        self.ensure_change_selection();
+        let next = match self.repo_status.operation {
+            Some(operation) if operation.can_continue() => {
+                format!("press [C] to continue the {}", operation.kind.as_str())
+            }
+            _ => "commit to conclude".to_string(),
+        };
+        self.show_notification(format!("All conflicts resolved; {next}"));
+    }
+}
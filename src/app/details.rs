@@ -0,0 +1,47 @@
+use crossterm::event::KeyCode;
+
+use crate::{App, regions::Region, ui::details};
+
+impl App {
+    pub fn refresh_details(&mut self) {
+        let previous = std::mem::take(&mut self.details);
+        self.details = details::DetailsState::refresh(previous, self.hovered_commit_id.as_deref());
+    }
+
+    pub fn handle_details_region_keys(&mut self, code: KeyCode) {
+        if self.selected_region != Region::Details {
+            return;
+        }
+
+        if self.details.file_diff.is_some() {
+            match code {
+                KeyCode::Up => self.details.scroll_diff_up(1),
+                KeyCode::Down => self.details.scroll_diff_down(1),
+                KeyCode::PageUp => self.details.page_diff_up(),
+                KeyCode::PageDown => self.details.page_diff_down(),
+                KeyCode::Esc | KeyCode::Backspace => self.details.close_file_diff(),
+                KeyCode::Char('p') => self.cycle_details_parent(),
+                _ => {}
+            }
+            return;
+        }
+
+        match code {
+            KeyCode::Up => self.details.move_hover_up(),
+            KeyCode::Down => self.details.move_hover_down(),
+            KeyCode::Enter => {
+                if let Err(err) = self.details.open_file_diff() {
+                    self.show_notification(err);
+                }
+            }
+            KeyCode::Char('p') => self.cycle_details_parent(),
+            _ => {}
+        }
+    }
+
+    fn cycle_details_parent(&mut self) {
+        if let Err(err) = self.details.cycle_parent() {
+            self.show_notification(err);
+        }
+    }
+}
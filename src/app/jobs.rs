@@ -0,0 +1,212 @@
+use crate::{
+    App,
+    git::{
+        self, BranchInfo, CancelToken, MergeMode, MergeOutcome, OperationKind, OperationOutcome,
+        RepoStatus, Stash, Tag,
+    },
+    ui::{branches, commits, stashes, tags},
+};
+
+/// Everything the periodic refresh reads from git, gathered on a worker thread.
+#[derive(Debug)]
+pub struct RefreshData {
+    branches: BranchInfo,
+    commits: commits::CommitsFetch,
+    status: RepoStatus,
+    stashes: Result<Vec<Stash>, String>,
+    tags: Result<Vec<Tag>, String>,
+}
+
+#[derive(Debug)]
+pub enum JobOutput {
+    Refresh {
+        generation: u64,
+        commits_generation: u64,
+        data: Box<RefreshData>,
+    },
+    /// A user-triggered operation whose message is shown once it finishes.
+    Operation(Result<String, String>),
+    /// A merge, rebase, cherry-pick or revert step, with the status read right after it.
+    OperationStep {
+        kind: OperationKind,
+        result: Result<OperationOutcome, String>,
+        finished: String,
+        status: RepoStatus,
+    },
+    /// A merge of `branch` into `target`, with the status read right after it.
+    Merge {
+        branch: String,
+        target: String,
+        mode: MergeMode,
+        result: Result<MergeOutcome, String>,
+        status: RepoStatus,
+    },
+    /// A commit of the staged changes, or an amend of HEAD, carrying the message that was used.
+    Commit {
+        amend: bool,
+        result: Result<String, String>,
+    },
+}
+
+impl App {
+    /// Re-reads the repository in the background. While a refresh is already
+    /// running, another one is queued to start once it lands.
+    pub fn spawn_refresh(&mut self) {
+        if self.refresh_job.is_some_and(|id| self.jobs.is_running(id)) {
+            self.refresh_queued = true;
+            return;
+        }
+        self.refresh_queued = false;
+
+        let generation = self.refresh_generation;
+        let commits_generation = self.commits_generation;
+        let known_counts = self.selected_branch.counts.clone();
+        let known_ref_tips = self.commits.ref_tips.clone();
+        let limit = self.commits.limit;
+        let filter = self.commits.query.filter.clone();
+        let id = self
+            .jobs
+            .spawn_quiet("Refreshing", move |_| JobOutput::Refresh {
+                generation,
+                commits_generation,
+                data: Box::new(RefreshData {
+                    branches: git::fetch_branch_info(&known_counts),
+                    commits: commits::CommitsFetch::load(known_ref_tips.as_deref(), limit, &filter),
+                    status: git::fetch_repo_status(),
+                    stashes: git::fetch_stashes(),
+                    tags: git::fetch_tags(),
+                }),
+            });
+        self.refresh_job = Some(id);
+    }
+
+    /// Re-reads the repository after the app changed it. A refresh already in
+    /// flight may have read the old state, so its result is dropped and a new
+    /// one queued behind it.
+    pub fn refresh_after_change(&mut self) {
+        self.refresh_generation += 1;
+        self.spawn_refresh();
+    }
+
+    pub fn spawn_operation<F>(&mut self, label: impl Into<String>, cancellable: bool, work: F)
+    where
+        F: FnOnce(&CancelToken) -> Result<String, String> + Send + 'static,
+    {
+        self.jobs.spawn(label, cancellable, move |cancel| {
+            JobOutput::Operation(work(cancel))
+        });
+    }
+
+    pub fn poll_jobs(&mut self) {
+        for output in self.jobs.poll() {
+            match output {
+                JobOutput::Refresh {
+                    generation,
+                    commits_generation,
+                    data,
+                } => self.land_refresh(generation, commits_generation, *data),
+                JobOutput::Operation(result) => {
+                    self.refresh_after_change();
+                    self.show_notification(result.unwrap_or_else(|err| err));
+                }
+                JobOutput::OperationStep {
+                    kind,
+                    result,
+                    finished,
+                    status,
+                } => self.finish_operation_step(kind, result, finished, status),
+                JobOutput::Merge {
+                    branch,
+                    target,
+                    mode,
+                    result,
+                    status,
+                } => self.finish_merge(branch, target, mode, result, status),
+                JobOutput::Commit { amend, result } => self.finish_commit(amend, result),
+            }
+        }
+    }
+
+    pub fn cancel_jobs(&mut self) {
+        match self.jobs.cancel_all() {
+            0 => self.show_notification("Nothing to cancel".to_string()),
+            1 => self.show_notification("Cancelling…".to_string()),
+            count => self.show_notification(format!("Cancelling {count} operations…")),
+        }
+    }
+
+    /// Applies a finished background refresh. One that read the repository
+    /// before the app last changed it is dropped and read again; commits
+    /// reloaded on the UI thread since it started are kept over the ones it read.
+    fn land_refresh(&mut self, generation: u64, commits_generation: u64, data: RefreshData) {
+        if generation == self.refresh_generation {
+            self.apply_refresh(data, commits_generation == self.commits_generation);
+        } else {
+            self.refresh_queued = true;
+        }
+        if self.refresh_queued {
+            self.spawn_refresh();
+        }
+    }
+
+    fn apply_refresh(&mut self, data: RefreshData, with_commits: bool) {
+        let previous = std::mem::take(&mut self.selected_branch);
+        self.selected_branch = branches::apply(previous, data.branches);
+        if with_commits {
+            self.apply_commits(data.commits);
+        }
+        self.apply_status(data.status);
+        self.stashes = stashes::StashesState::apply(data.stashes, self.stashes.hovered);
+        self.tags = tags::TagsState::apply(data.tags, self.tags.hovered);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::git::LogFilter;
+
+    #[test]
+    fn refresh_outdated_by_a_change_is_read_again() {
+        let mut app = App::default();
+        app.spawn_refresh();
+        app.refresh_after_change();
+        app.tags.status = Some("stale".to_string());
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while app.tags.status.as_deref() == Some("stale") && Instant::now() < deadline {
+            app.poll_jobs();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_ne!(app.tags.status.as_deref(), Some("stale"));
+    }
+
+    #[test]
+    fn commit_reload_mid_refresh_keeps_the_rest_of_it() {
+        let mut app = App::default();
+        let generation = app.refresh_generation;
+        let commits_generation = app.commits_generation;
+        app.refresh_commits();
+
+        let stash = Stash {
+            index: 0,
+            branch: Some("main".to_string()),
+            message: "wip".to_string(),
+            age: "now".to_string(),
+        };
+        let data = RefreshData {
+            branches: BranchInfo::default(),
+            commits: commits::CommitsFetch::load(None, 1, &LogFilter::default()),
+            status: RepoStatus::default(),
+            stashes: Ok(vec![stash.clone()]),
+            tags: Ok(Vec::new()),
+        };
+        app.land_refresh(generation, commits_generation, data);
+
+        assert_eq!(app.stashes.stashes, vec![stash]);
+        assert_eq!(app.commits.limit, commits::PAGE_SIZE);
+    }
+}
@@ -0,0 +1,118 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App,
+    app::jobs::JobOutput,
+    git::{self, MergeMode, MergeOutcome, RepoStatus},
+    regions::Region,
+    ui::{merge::MergePrompt, message_editor::MessageEditor},
+};
+
+impl App {
+    /// Asks how to merge the hovered branch into the current one.
+    pub fn start_merge(&mut self) {
+        let Some(current) = self.selected_branch.current.clone() else {
+            self.show_notification("No current branch to merge into".to_string());
+            return;
+        };
+        let Some(branch) = self
+            .selected_branch
+            .hovered
+            .and_then(|idx| self.selected_branch.branches.get(idx))
+        else {
+            return;
+        };
+        let source = if branch.has_local {
+            branch.name.clone()
+        } else if let Some(remote_ref) = &branch.remote_ref {
+            remote_ref.clone()
+        } else {
+            self.show_notification("Missing branch reference".to_string());
+            return;
+        };
+        if source == current {
+            self.show_notification(format!("Cannot merge {current} into itself"));
+            return;
+        }
+        self.merge_prompt = Some(MergePrompt {
+            branch: source,
+            target: current,
+        });
+    }
+
+    pub fn handle_merge_prompt_key(&mut self, code: KeyCode) {
+        let mode = match code {
+            KeyCode::Char('f') => MergeMode::FastForwardOnly,
+            KeyCode::Char('n') => MergeMode::NoFastForward,
+            KeyCode::Char('s') => MergeMode::Squash,
+            KeyCode::Esc => {
+                self.merge_prompt = None;
+                return;
+            }
+            _ => return,
+        };
+        let Some(MergePrompt { branch, target }) = self.merge_prompt.take() else {
+            return;
+        };
+
+        // Merges can take a while and run hooks, so they go through the job
+        // system like the other operation steps, reading the status after.
+        self.jobs.spawn(
+            format!("Merging {branch} into {target}"),
+            false,
+            move |_| {
+                let result = git::merge_branch(&branch, mode);
+                JobOutput::Merge {
+                    branch,
+                    target,
+                    mode,
+                    result,
+                    status: git::fetch_repo_status(),
+                }
+            },
+        );
+    }
+
+    pub fn finish_merge(
+        &mut self,
+        branch: String,
+        target: String,
+        mode: MergeMode,
+        result: Result<MergeOutcome, String>,
+        status: RepoStatus,
+    ) {
+        self.apply_status(status);
+        self.refresh_after_change();
+        match result {
+            Ok(MergeOutcome::Merged) => {
+                self.show_notification(format!("Merged {branch} into {target}"));
+            }
+            Ok(MergeOutcome::UpToDate) => {
+                self.show_notification(format!("{target} is already up to date with {branch}"));
+            }
+            Ok(MergeOutcome::Squashed) => {
+                self.open_commit_message(format!("Squash {branch} into {target}"));
+                self.show_notification(format!("Squashed {branch}; commit to finish"));
+            }
+            Ok(MergeOutcome::Conflicts) => {
+                if mode != MergeMode::Squash {
+                    self.commit_input = MessageEditor::new(format!("Merge branch '{branch}'"));
+                    self.commit_amend = None;
+                }
+                self.open_changes_popup();
+                self.show_notification(format!(
+                    "Merging {branch} stopped on conflicts: resolve and stage them, then commit"
+                ));
+            }
+            Err(err) => self.show_notification(format!("Merge failed: {err}")),
+        }
+    }
+
+    fn open_commit_message(&mut self, message: String) {
+        self.commit_input = MessageEditor::new(message);
+        self.commit_amend = None;
+        self.open_changes_popup();
+        self.popup_region = Region::CommitMessage;
+        self.commit_message_editing = true;
+    }
+}
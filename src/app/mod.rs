@@ -1,4 +1,17 @@
 pub mod branches;
+pub mod change_viewer;
 pub mod changes;
+pub mod cherry_pick;
 pub mod commit_message;
 pub mod commits;
+pub mod conflicts;
+pub mod details;
+pub mod jobs;
+pub mod merge;
+pub mod operation;
+pub mod rebase;
+pub mod remotes;
+pub mod reset;
+pub mod stashes;
+pub mod tags;
+pub mod upstream;
@@ -0,0 +1,125 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App,
+    app::jobs::JobOutput,
+    git::{self, OperationKind, OperationOutcome, RepoStatus},
+    ui::operation::AbortPrompt,
+};
+
+impl App {
+    /// Continue, skip and abort keys for the merge, rebase, cherry-pick, revert
+    /// or bisect the repository is in the middle of. Aborting asks first.
+    pub fn handle_operation_key(&mut self, code: KeyCode) -> bool {
+        let Some(operation) = self.repo_status.operation else {
+            return false;
+        };
+        let kind = operation.kind;
+        let finished = format!("{} finished", kind.title());
+        match code {
+            KeyCode::Char('C') if operation.can_continue() => {
+                let label = format!("Continuing {}", kind.as_str());
+                self.spawn_operation_step(label, kind, finished, move || {
+                    git::continue_operation(kind)
+                });
+            }
+            KeyCode::Char('S') if operation.can_skip() => {
+                let label = format!("Skipping a {} step", kind.as_str());
+                self.spawn_operation_step(label, kind, finished, move || git::skip_operation(kind));
+            }
+            KeyCode::Char('A') => self.abort_prompt = Some(AbortPrompt { kind }),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn handle_abort_prompt_key(&mut self, code: KeyCode) {
+        let Some(AbortPrompt { kind }) = self.abort_prompt else {
+            return;
+        };
+        match code {
+            KeyCode::Char('y') => {
+                self.abort_prompt = None;
+                let (label, done) = if kind == OperationKind::Bisect {
+                    ("Resetting bisect".to_string(), "Bisect reset".to_string())
+                } else {
+                    (
+                        format!("Aborting {}", kind.as_str()),
+                        format!("{} aborted", kind.title()),
+                    )
+                };
+                self.spawn_operation_step(label, kind, done, move || {
+                    git::abort_operation(kind).map(|()| OperationOutcome::Finished)
+                });
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.abort_prompt = None,
+            _ => {}
+        }
+    }
+
+    /// Runs a step of `kind` in the background. The status is read on the
+    /// worker too, so conflicts can be listed as soon as the step lands.
+    pub fn spawn_operation_step<F>(
+        &mut self,
+        label: impl Into<String>,
+        kind: OperationKind,
+        finished: String,
+        work: F,
+    ) where
+        F: FnOnce() -> Result<OperationOutcome, String> + Send + 'static,
+    {
+        self.jobs.spawn(label, false, move |_| {
+            let result = work();
+            JobOutput::OperationStep {
+                kind,
+                result,
+                finished,
+                status: git::fetch_repo_status(),
+            }
+        });
+    }
+
+    /// Refreshes after a step of `kind` and, when it stopped, brings up the
+    /// changes popup so the conflict or edit can be dealt with there.
+    pub fn finish_operation_step(
+        &mut self,
+        kind: OperationKind,
+        result: Result<OperationOutcome, String>,
+        finished: String,
+        status: RepoStatus,
+    ) {
+        self.apply_status(status);
+        self.refresh_after_change();
+        if result.is_ok() && matches!(kind, OperationKind::CherryPick | OperationKind::Revert) {
+            self.commits.marked.clear();
+        }
+        match result {
+            Ok(OperationOutcome::Finished) => {
+                self.show_changes_popup = false;
+                self.show_notification(finished);
+            }
+            Ok(OperationOutcome::Conflicts) => {
+                self.open_changes_popup();
+                self.show_notification(format!(
+                    "{} stopped on conflicts in {}: resolve and stage them, then [C] continue",
+                    kind.title(),
+                    self.repo_status.conflicted_files().join(", ")
+                ));
+            }
+            Ok(OperationOutcome::Stopped) if kind == OperationKind::Rebase => {
+                self.open_changes_popup();
+                self.show_notification(
+                    "Rebase stopped for editing: make your changes, then [C] continue".to_string(),
+                );
+            }
+            Ok(OperationOutcome::Stopped) => {
+                self.open_changes_popup();
+                self.show_notification(format!(
+                    "{} stopped: review the changes, then [C] continue",
+                    kind.title()
+                ));
+            }
+            Err(err) => self.show_notification(err),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    App,
+    git::{self, OperationKind, RebaseAction},
+    ui::rebase::{RebaseOntoPrompt, RebasePlan},
+};
+
+impl App {
+    /// Opens a rebase plan for the commits between the hovered commit and HEAD.
+    pub fn open_rebase_plan(&mut self) {
+        let Some(base) = self.commits.hovered_commit_id().map(str::to_string) else {
+            return;
+        };
+        match git::fetch_rebase_plan(&base) {
+            Ok(steps) => self.rebase_plan = Some(RebasePlan::new(base, steps)),
+            Err(err) => self.show_notification(err),
+        }
+    }
+
+    pub fn handle_rebase_plan_key(&mut self, key_event: KeyEvent) {
+        let Some(plan) = self.rebase_plan.as_mut() else {
+            return;
+        };
+
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        if let Some(input) = plan.reword_input.as_mut() {
+            match key_event.code {
+                KeyCode::Char('s') | KeyCode::Enter if ctrl => plan.finish_reword(),
+                KeyCode::Esc => plan.reword_input = None,
+                code if !ctrl => input.handle_key(code),
+                _ => {}
+            }
+            return;
+        }
+
+        let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+        match key_event.code {
+            KeyCode::Up if shift => plan.move_step_up(),
+            KeyCode::Down if shift => plan.move_step_down(),
+            KeyCode::Up => plan.move_hover_up(),
+            KeyCode::Down => plan.move_hover_down(),
+            KeyCode::Char('p') => plan.set_action(RebaseAction::Pick),
+            KeyCode::Char('r') => plan.start_reword(),
+            KeyCode::Char('e') => plan.set_action(RebaseAction::Edit),
+            KeyCode::Char('s') => plan.set_action(RebaseAction::Squash),
+            KeyCode::Char('f') => plan.set_action(RebaseAction::Fixup),
+            KeyCode::Char('d') => plan.set_action(RebaseAction::Drop),
+            KeyCode::Enter => self.run_rebase_plan(),
+            KeyCode::Esc => self.rebase_plan = None,
+            _ => {}
+        }
+    }
+
+    fn run_rebase_plan(&mut self) {
+        let Some(plan) = self.rebase_plan.as_ref() else {
+            return;
+        };
+        if let Err(err) = plan.validate() {
+            self.show_notification(err);
+            return;
+        }
+
+        let Some(plan) = self.rebase_plan.take() else {
+            return;
+        };
+        let finished = format!("Rebased onto {}", plan.base);
+        self.spawn_operation_step("Rebasing", OperationKind::Rebase, finished, move || {
+            git::run_interactive_rebase(&plan.base, &plan.steps)
+        });
+    }
+
+    /// Asks to rebase the current branch onto the hovered branch, showing how
+    /// many commits would be replayed.
+    pub fn start_rebase_onto(&mut self) {
+        let Some(branch) = self.selected_branch.current.clone() else {
+            self.show_notification("No current branch to rebase".to_string());
+            return;
+        };
+        let Some(hovered) = self
+            .selected_branch
+            .hovered
+            .and_then(|idx| self.selected_branch.branches.get(idx))
+        else {
+            return;
+        };
+        let onto = if hovered.has_local {
+            hovered.name.clone()
+        } else if let Some(remote_ref) = &hovered.remote_ref {
+            remote_ref.clone()
+        } else {
+            self.show_notification("Missing branch reference".to_string());
+            return;
+        };
+        if onto == branch {
+            self.show_notification(format!("Cannot rebase {branch} onto itself"));
+            return;
+        }
+        let Some((replay, behind)) = git::ahead_behind(&branch, &onto) else {
+            self.show_notification(format!("Cannot compare {branch} with {onto}"));
+            return;
+        };
+        self.rebase_onto = Some(RebaseOntoPrompt {
+            branch,
+            onto,
+            replay,
+            behind,
+        });
+    }
+
+    pub fn handle_rebase_onto_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => {
+                let Some(prompt) = self.rebase_onto.take() else {
+                    return;
+                };
+                if prompt.behind == 0 {
+                    self.show_notification(format!(
+                        "{} already contains {}",
+                        prompt.branch, prompt.onto
+                    ));
+                    return;
+                }
+                let finished = format!("Rebased {} onto {}", prompt.branch, prompt.onto);
+                let label = format!("Rebasing onto {}", prompt.onto);
+                self.spawn_operation_step(label, OperationKind::Rebase, finished, move || {
+                    git::rebase_onto(&prompt.onto)
+                });
+            }
+            KeyCode::Esc => self.rebase_onto = None,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App, git,
+    ui::{
+        input::TextInput,
+        remotes::{RemotePrompt, RemotesState},
+    },
+};
+
+impl App {
+    pub fn open_remotes(&mut self) {
+        self.remotes = Some(RemotesState::load(0));
+    }
+
+    pub fn handle_remotes_key(&mut self, code: KeyCode) {
+        let Some(state) = self.remotes.as_mut() else {
+            return;
+        };
+
+        if let Some(prompt) = state.prompt.as_mut() {
+            match (prompt, code) {
+                (_, KeyCode::Esc) => state.prompt = None,
+                (RemotePrompt::ConfirmRemove(_), KeyCode::Char('y')) => self.submit_remote_prompt(),
+                (RemotePrompt::ConfirmRemove(_), KeyCode::Char('n')) => state.prompt = None,
+                (RemotePrompt::ConfirmRemove(_), _) => {}
+                (_, KeyCode::Enter) => self.submit_remote_prompt(),
+                (prompt, code) => {
+                    if let Some(input) = prompt.input_mut() {
+                        input.handle_key(code);
+                    }
+                }
+            }
+            return;
+        }
+
+        let hovered = state.hovered_remote().cloned();
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => self.remotes = None,
+            KeyCode::Up => state.move_hover_up(),
+            KeyCode::Down => state.move_hover_down(),
+            KeyCode::Char('a') => {
+                state.prompt = Some(RemotePrompt::AddName(TextInput::default()));
+            }
+            KeyCode::Char('f') => {
+                if let Some(remote) = hovered {
+                    let name = remote.name;
+                    self.spawn_operation(format!("Fetching {name}"), true, move |cancel| {
+                        git::fetch_remote(&name, cancel).map(|()| format!("Fetched {name}"))
+                    });
+                }
+            }
+            KeyCode::Char('r') => {
+                if let Some(remote) = hovered {
+                    state.prompt = Some(RemotePrompt::Rename {
+                        input: TextInput::new(remote.name.clone()),
+                        remote: remote.name,
+                    });
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Some(remote) = hovered {
+                    state.prompt = Some(RemotePrompt::SetUrl {
+                        input: TextInput::new(remote.fetch_url),
+                        remote: remote.name,
+                        push: false,
+                    });
+                }
+            }
+            KeyCode::Char('u') => {
+                if let Some(remote) = hovered {
+                    state.prompt = Some(RemotePrompt::SetUrl {
+                        input: TextInput::new(remote.push_url.unwrap_or(remote.fetch_url)),
+                        remote: remote.name,
+                        push: true,
+                    });
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if let Some(remote) = hovered {
+                    state.prompt = Some(RemotePrompt::ConfirmRemove(remote.name));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn submit_remote_prompt(&mut self) {
+        let Some(state) = self.remotes.as_mut() else {
+            return;
+        };
+        let Some(prompt) = state.prompt.take() else {
+            return;
+        };
+
+        // Adding asks for the name first, then for the URL.
+        if let RemotePrompt::AddName(input) = &prompt {
+            let name = input.value.trim().to_string();
+            if name.is_empty() {
+                state.prompt = Some(prompt);
+                self.show_notification("Remote name cannot be empty".to_string());
+            } else {
+                state.prompt = Some(RemotePrompt::AddUrl {
+                    name,
+                    input: TextInput::default(),
+                });
+            }
+            return;
+        }
+
+        let result = match &prompt {
+            RemotePrompt::AddName(_) => return,
+            RemotePrompt::AddUrl { name, input } => {
+                git::add_remote(name, &input.value).map(|()| format!("Added remote {name}"))
+            }
+            RemotePrompt::Rename { remote, input } => {
+                let new_name = input.value.trim();
+                git::rename_remote(remote, new_name)
+                    .map(|()| format!("Renamed remote {remote} to {new_name}"))
+            }
+            RemotePrompt::SetUrl {
+                remote,
+                input,
+                push,
+            } => git::set_remote_url(remote, &input.value, *push).map(|()| {
+                let which = if *push { "push URL" } else { "URL" };
+                format!("Set the {which} of {remote}")
+            }),
+            RemotePrompt::ConfirmRemove(remote) => {
+                git::remove_remote(remote).map(|()| format!("Removed remote {remote}"))
+            }
+        };
+
+        match result {
+            Ok(message) => {
+                let hovered = state.hovered;
+                self.remotes = Some(RemotesState::load(hovered));
+                self.refresh_after_change();
+                self.show_notification(message);
+            }
+            Err(err) => {
+                // Keep the typed value so it can be corrected.
+                if !matches!(prompt, RemotePrompt::ConfirmRemove(_)) {
+                    state.prompt = Some(prompt);
+                }
+                self.show_notification(err);
+            }
+        }
+    }
+}
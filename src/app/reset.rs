@@ -0,0 +1,92 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App,
+    git::{self, ResetMode},
+    ui::{input::TextInput, reset::ResetPrompt},
+};
+
+impl App {
+    /// Previews resetting the current branch to the hovered commit.
+    pub fn start_reset(&mut self) {
+        let Some(commit) = self.commits.hovered_commit() else {
+            return;
+        };
+        let (target, summary) = (commit.id.clone(), commit.summary.clone());
+        let branch = self
+            .selected_branch
+            .current
+            .clone()
+            .unwrap_or_else(|| "HEAD".to_string());
+        match git::fetch_reset_preview(&target) {
+            Ok(preview) => {
+                self.reset_prompt = Some(ResetPrompt {
+                    target,
+                    summary,
+                    branch,
+                    preview,
+                    confirm_input: None,
+                });
+            }
+            Err(err) => self.show_notification(err),
+        }
+    }
+
+    pub fn handle_reset_prompt_key(&mut self, code: KeyCode) {
+        let Some(prompt) = self.reset_prompt.as_mut() else {
+            return;
+        };
+
+        if prompt.confirm_input.is_some() {
+            match code {
+                KeyCode::Enter if prompt.is_confirmed() => self.run_reset(ResetMode::Hard),
+                KeyCode::Enter => {
+                    let message = format!("Type {} to confirm the hard reset", prompt.branch);
+                    self.show_notification(message);
+                }
+                KeyCode::Esc => prompt.confirm_input = None,
+                code => {
+                    if let Some(input) = prompt.confirm_input.as_mut() {
+                        input.handle_key(code);
+                    }
+                }
+            }
+            return;
+        }
+
+        match code {
+            KeyCode::Char('s') => self.run_reset(ResetMode::Soft),
+            KeyCode::Char('m') => self.run_reset(ResetMode::Mixed),
+            KeyCode::Char('h') => prompt.confirm_input = Some(TextInput::default()),
+            KeyCode::Esc => self.reset_prompt = None,
+            _ => {}
+        }
+    }
+
+    fn run_reset(&mut self, mode: ResetMode) {
+        let Some(prompt) = self.reset_prompt.take() else {
+            return;
+        };
+        let ResetPrompt { target, branch, .. } = prompt;
+        self.spawn_operation(
+            format!("Resetting {branch} to {target}"),
+            false,
+            move |_| {
+                git::reset_to(&target, mode)?;
+                Ok(match mode {
+                    ResetMode::Soft => {
+                        format!(
+                            "Reset {branch} to {target}; the undone changes are staged to commit"
+                        )
+                    }
+                    ResetMode::Mixed => {
+                        format!("Reset {branch} to {target}; the undone changes are left unstaged")
+                    }
+                    ResetMode::Hard => {
+                        format!("Reset {branch} to {target} and discarded local changes")
+                    }
+                })
+            },
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App,
+    branch_input::{BranchInput, BranchInputPurpose},
+    git::{self, StashMode},
+    regions::Region,
+    ui::stashes,
+};
+
+impl App {
+    pub fn refresh_stashes(&mut self) {
+        self.refresh_generation += 1;
+        self.stashes = stashes::StashesState::refresh(self.stashes.hovered);
+    }
+
+    pub fn handle_stashes_region_keys(&mut self, code: KeyCode) {
+        if self.selected_region != Region::Stashes {
+            return;
+        }
+
+        match code {
+            KeyCode::Up => self.stashes.move_hover_up(),
+            KeyCode::Down => self.stashes.move_hover_down(),
+            KeyCode::Char('n') => self.create_stash(StashMode::TrackedChanges),
+            KeyCode::Char('u') => self.create_stash(StashMode::IncludeUntracked),
+            KeyCode::Char('i') => self.create_stash(StashMode::StagedOnly),
+            KeyCode::Enter => self.run_on_hovered_stash("Applying", "Applied", git::apply_stash),
+            KeyCode::Char('p') => self.run_on_hovered_stash("Popping", "Popped", git::pop_stash),
+            KeyCode::Char('x') | KeyCode::Delete => {
+                self.run_on_hovered_stash("Dropping", "Dropped", git::drop_stash)
+            }
+            KeyCode::Char('t') => {
+                if let Some(stash) = self.stashes.hovered_stash() {
+                    let purpose = BranchInputPurpose::FromStash(stash.index);
+                    self.branch_input = Some(BranchInput::new(purpose));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn create_stash(&mut self, mode: StashMode) {
+        self.stashes.hovered = Some(0);
+        self.spawn_operation("Stashing local changes", false, move |_| {
+            git::create_stash(mode).map(|()| "Stashed local changes".to_string())
+        });
+    }
+
+    fn run_on_hovered_stash(
+        &mut self,
+        doing: &str,
+        done: &'static str,
+        action: fn(usize) -> Result<(), String>,
+    ) {
+        let Some(index) = self.stashes.hovered_stash().map(|stash| stash.index) else {
+            return;
+        };
+
+        self.spawn_operation(format!("{doing} stash@{{{index}}}"), false, move |_| {
+            action(index).map(|()| format!("{done} stash@{{{index}}}"))
+        });
+    }
+}
@@ -0,0 +1,103 @@
+use crossterm::event::KeyCode;
+
+use crate::{
+    App, git,
+    regions::Region,
+    ui::tags::{self, RemoteTagDeletePrompt, TagInput},
+};
+
+impl App {
+    pub fn refresh_tags(&mut self) {
+        self.refresh_generation += 1;
+        self.tags = tags::TagsState::refresh(self.tags.hovered);
+    }
+
+    pub fn handle_tags_region_keys(&mut self, code: KeyCode) {
+        if self.selected_region != Region::Tags {
+            return;
+        }
+
+        match code {
+            KeyCode::Up => self.tags.move_hover_up(),
+            KeyCode::Down => self.tags.move_hover_down(),
+            KeyCode::Char('p') => {
+                let Some(name) = self.hovered_tag_name() else {
+                    return;
+                };
+                self.spawn_operation(format!("Pushing tag {name}"), true, move |cancel| {
+                    git::push_tag(&name, cancel)
+                        .map(|remote| format!("Pushed tag {name} to {remote}"))
+                });
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                let Some(name) = self.hovered_tag_name() else {
+                    return;
+                };
+                let result = git::delete_tag(&name);
+                self.refresh_after_change();
+                match result {
+                    Ok(()) => self.show_notification(format!("Deleted tag {name}")),
+                    Err(err) => self.show_notification(err),
+                }
+            }
+            KeyCode::Char('X') => {
+                if let Some(name) = self.hovered_tag_name() {
+                    self.remote_tag_delete_prompt = Some(RemoteTagDeletePrompt { name });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn handle_remote_tag_delete_prompt_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y') => {
+                let Some(RemoteTagDeletePrompt { name }) = self.remote_tag_delete_prompt.take()
+                else {
+                    return;
+                };
+                self.spawn_operation(format!("Deleting tag {name}"), true, move |cancel| {
+                    let remote = git::delete_remote_tag(&name, cancel)?;
+                    git::delete_tag(&name)?;
+                    Ok(format!("Deleted tag {name} locally and from {remote}"))
+                });
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.remote_tag_delete_prompt = None,
+            _ => {}
+        }
+    }
+
+    /// Opens the tag popup for the hovered commit.
+    pub fn start_tag(&mut self) {
+        if let Some(commit) = self.commits.hovered_commit() {
+            self.tag_input = Some(TagInput::new(commit.id.clone(), commit.summary.clone()));
+        }
+    }
+
+    pub fn handle_tag_input_key(&mut self, code: KeyCode) {
+        let Some(input) = self.tag_input.as_mut() else {
+            return;
+        };
+
+        match code {
+            KeyCode::Esc => self.tag_input = None,
+            KeyCode::Tab | KeyCode::BackTab => input.toggle_focus(),
+            KeyCode::Enter => {
+                let name = input.name.value.trim().to_string();
+                match git::create_tag(&name, &input.target, input.annotation()) {
+                    Ok(()) => {
+                        self.tag_input = None;
+                        self.refresh_after_change();
+                        self.show_notification(format!("Created tag {name}"));
+                    }
+                    Err(err) => self.show_notification(err),
+                }
+            }
+            code => input.focused_mut().handle_key(code),
+        }
+    }
+
+    fn hovered_tag_name(&self) -> Option<String> {
+        self.tags.hovered_tag().map(|tag| tag.name.clone())
+    }
+}
@@ -0,0 +1,71 @@
+use crossterm::event::KeyCode;
+
+use crate::{App, git, ui::upstream::UpstreamPicker};
+
+impl App {
+    /// Lets the hovered local branch pick, change or drop its upstream.
+    pub fn start_upstream_picker(&mut self) {
+        let Some(branch) = self
+            .selected_branch
+            .hovered
+            .and_then(|idx| self.selected_branch.branches.get(idx))
+        else {
+            return;
+        };
+        if !branch.has_local {
+            self.show_notification(format!("{} has no local branch to track", branch.name));
+            return;
+        }
+        let (name, current) = (branch.name.clone(), branch.upstream.clone());
+        match git::list_remote_branches() {
+            Ok(candidates) => {
+                self.upstream_picker = Some(UpstreamPicker::new(name, current, candidates));
+            }
+            Err(err) => self.show_notification(err),
+        }
+    }
+
+    pub fn handle_upstream_picker_key(&mut self, code: KeyCode) {
+        let Some(picker) = self.upstream_picker.as_mut() else {
+            return;
+        };
+
+        match code {
+            KeyCode::Up => picker.move_up(),
+            KeyCode::Down => picker.move_down(),
+            KeyCode::Esc => self.upstream_picker = None,
+            KeyCode::Enter => {
+                let Some(upstream) = picker.hovered_candidate().map(str::to_string) else {
+                    return;
+                };
+                let branch = picker.branch.clone();
+                self.finish_upstream_change(
+                    git::set_upstream(&branch, &upstream),
+                    format!("{branch} now tracks {upstream}"),
+                );
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if picker.current.is_none() {
+                    let message = format!("{} has no upstream", picker.branch);
+                    self.show_notification(message);
+                    return;
+                }
+                let branch = picker.branch.clone();
+                self.finish_upstream_change(
+                    git::unset_upstream(&branch),
+                    format!("{branch} no longer tracks an upstream"),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn finish_upstream_change(&mut self, result: Result<(), String>, done: String) {
+        self.upstream_picker = None;
+        self.refresh_after_change();
+        match result {
+            Ok(()) => self.show_notification(done),
+            Err(err) => self.show_notification(err),
+        }
+    }
+}
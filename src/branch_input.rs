@@ -9,14 +9,87 @@ use ratatui::{
 
 use crate::ui::layout::centered_rect;
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum BranchInputPurpose {
+    #[default]
+    Create,
+    /// A new branch starting at the given branch or commit instead of HEAD.
+    CreateFrom(String),
+    FromStash(usize),
+    /// Renames a local branch; one with an upstream can have it renamed too.
+    Rename {
+        branch: String,
+        upstream: Option<String>,
+    },
+}
+
+impl BranchInputPurpose {
+    fn title(&self) -> &'static str {
+        match self {
+            BranchInputPurpose::Create | BranchInputPurpose::CreateFrom(_) => "Create Branch",
+            BranchInputPurpose::FromStash(_) => "Branch from Stash",
+            BranchInputPurpose::Rename { .. } => "Rename Branch",
+        }
+    }
+
+    fn prompt(&self) -> String {
+        match self {
+            BranchInputPurpose::Create => "New branch name:".to_string(),
+            BranchInputPurpose::CreateFrom(start) => {
+                format!("New branch name, starting at {start}:")
+            }
+            BranchInputPurpose::FromStash(index) => {
+                format!("New branch name for stash@{{{index}}}:")
+            }
+            BranchInputPurpose::Rename { branch, .. } => format!("Rename {branch} to:"),
+        }
+    }
+
+    fn action(&self) -> &'static str {
+        match self {
+            BranchInputPurpose::Rename { .. } => "[Enter] Rename",
+            _ => "[Enter] Create",
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct BranchInput {
     pub value: String,
     pub error: Option<String>,
     pub cursor: usize,
+    pub purpose: BranchInputPurpose,
+    /// When renaming, whether the upstream branch on the remote is renamed as well.
+    pub rename_upstream: bool,
 }
 
 impl BranchInput {
+    pub fn new(purpose: BranchInputPurpose) -> Self {
+        Self {
+            purpose,
+            ..Self::default()
+        }
+    }
+
+    /// Starts a rename of `branch`, pre-filled with its current name.
+    pub fn rename(branch: String, upstream: Option<String>) -> Self {
+        Self {
+            cursor: branch.len(),
+            value: branch.clone(),
+            purpose: BranchInputPurpose::Rename { branch, upstream },
+            ..Self::default()
+        }
+    }
+
+    pub fn toggle_rename_upstream(&mut self) {
+        if let BranchInputPurpose::Rename {
+            upstream: Some(_), ..
+        } = self.purpose
+        {
+            self.rename_upstream = !self.rename_upstream;
+        }
+    }
+
     pub fn clamp_cursor(&mut self) {
         if self.cursor > self.value.len() {
             self.cursor = self.value.len();
@@ -102,15 +175,26 @@ impl BranchInput {
 }
 
 pub fn render_branch_popup(area: Rect, buf: &mut ratatui::buffer::Buffer, input: &BranchInput) {
-    let popup_area = centered_rect(40, 10, area);
+    let popup_area = centered_rect(40, 15, area);
 
     Clear.render(popup_area, buf);
 
     let mut lines = vec![
-        Line::from("New branch name:"),
+        Line::from(input.purpose.prompt()),
         Line::from(render_input_line(input)),
     ];
 
+    if let BranchInputPurpose::Rename {
+        upstream: Some(upstream),
+        ..
+    } = &input.purpose
+    {
+        let state = if input.rename_upstream { "on" } else { "off" };
+        lines.push(Line::from(format!(
+            "[Tab] also rename {upstream} on the remote: {state}"
+        )));
+    }
+
     if let Some(err) = &input.error {
         lines.push(Line::from(err.as_str()).style(Style::default().fg(Color::Red)));
     }
@@ -120,14 +204,14 @@ pub fn render_branch_popup(area: Rect, buf: &mut ratatui::buffer::Buffer, input:
         .block(
             Block::default()
                 .title(Line::from(Span::styled(
-                    "Create Branch",
+                    input.purpose.title(),
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
                 )))
                 .title_bottom(Line::from(vec![
                     Span::styled(
-                        "[Enter] Create",
+                        input.purpose.action(),
                         Style::default()
                             .fg(Color::Green)
                             .add_modifier(Modifier::BOLD),
@@ -199,4 +283,17 @@ mod tests {
         assert_eq!(input.value, "f-");
         assert_eq!(input.cursor, 2);
     }
+
+    #[test]
+    fn renaming_remote_branch_needs_an_upstream() {
+        let mut input = BranchInput::rename("topic".to_string(), None);
+        assert_eq!(input.value, "topic");
+        assert_eq!(input.cursor, 5);
+        input.toggle_rename_upstream();
+        assert!(!input.rename_upstream);
+
+        let mut input = BranchInput::rename("topic".to_string(), Some("origin/topic".to_string()));
+        input.toggle_rename_upstream();
+        assert!(input.rename_upstream);
+    }
 }
@@ -1,10 +1,67 @@
-use std::{collections::HashSet, path::Path};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Commit {
     pub id: String,
     pub summary: String,
     pub branches: Vec<String>,
+    /// Abbreviated parent ids, matching the abbreviation used for `id`.
+    pub parents: Vec<String>,
+    /// Tags pointing at this commit.
+    pub tags: Vec<String>,
+}
+
+/// Narrows the commit log to an author, a touched path and a date range. Dates
+/// accept anything `git log --since` does, like `2024-05-01` or `1.week.ago`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LogFilter {
+    pub author: Option<String>,
+    pub path: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+}
+
+impl LogFilter {
+    pub fn is_empty(&self) -> bool {
+        self.author.is_none() && self.path.is_none() && self.since.is_none() && self.until.is_none()
+    }
+
+    /// Whether the filter drops commits without rewriting the parents of the
+    /// ones it keeps, so their parents are mostly missing from the log. Only a
+    /// lone path filter keeps the history connected.
+    pub fn hides_ancestry(&self) -> bool {
+        self.author.is_some() || self.since.is_some() || self.until.is_some()
+    }
+
+    fn log_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(author) = &self.author {
+            args.push("--regexp-ignore-case".to_string());
+            args.push(format!("--author={author}"));
+        }
+        if let Some(since) = &self.since {
+            args.push(format!("--since={since}"));
+        }
+        if let Some(until) = &self.until {
+            args.push(format!("--until={until}"));
+        }
+        if let Some(path) = &self.path {
+            // Rewrite parents past the commits that leave the path alone so the
+            // graph and branch labels still connect.
+            args.push("--parents".to_string());
+            args.push("--".to_string());
+            args.push(path.clone());
+        }
+        args
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -15,17 +72,37 @@ pub struct BranchSummary {
     pub has_local: bool,
     pub has_remote: bool,
     pub remote_ref: Option<String>,
+    /// The remote-tracking branch a local branch is set to track.
+    pub upstream: Option<String>,
+    /// How far the branch has moved from the integration branch.
+    pub integration: Option<AheadBehind>,
+}
+
+/// Commits a branch has that `target` lacks, and the other way around.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AheadBehind {
+    pub target: String,
+    pub ahead: usize,
+    pub behind: usize,
 }
 
 #[derive(Debug, Default)]
 pub struct BranchInfo {
     pub branches: Vec<BranchSummary>,
     pub current: Option<String>,
+    /// The branch others are compared against: configured, else main or master.
+    pub integration_branch: Option<String>,
     pub status: Option<String>,
     pub hovered: Option<usize>,
     pub selected: Option<String>,
+    /// The ahead/behind counts behind `branches`, handed to the next fetch.
+    pub counts: AheadBehindCounts,
 }
 
+/// Ahead/behind counts keyed by the two commits they were counted between, so
+/// a refresh only recounts branches whose tip or target moved.
+pub type AheadBehindCounts = HashMap<(gix::ObjectId, gix::ObjectId), (usize, usize)>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChangeType {
     Added,
@@ -51,6 +128,69 @@ pub struct RepoStatus {
     pub changes: Vec<FileChange>,
     pub error: Option<String>,
     pub repo_name: Option<String>,
+    /// A merge, rebase, cherry-pick, revert or bisect left in progress.
+    pub operation: Option<RepoOperation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+impl OperationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OperationKind::Merge => "merge",
+            OperationKind::Rebase => "rebase",
+            OperationKind::CherryPick => "cherry-pick",
+            OperationKind::Revert => "revert",
+            OperationKind::Bisect => "bisect",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            OperationKind::Merge => "Merge",
+            OperationKind::Rebase => "Rebase",
+            OperationKind::CherryPick => "Cherry-pick",
+            OperationKind::Revert => "Revert",
+            OperationKind::Bisect => "Bisect",
+        }
+    }
+}
+
+/// A multi-step operation git is in the middle of, read from the git directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepoOperation {
+    pub kind: OperationKind,
+    /// The step being applied and the number of steps, for a rebase.
+    pub progress: Option<(usize, usize)>,
+}
+
+impl RepoOperation {
+    /// A short description such as "rebase 3/7".
+    pub fn label(&self) -> String {
+        match self.progress {
+            Some((step, total)) => format!("{} {step}/{total}", self.kind.as_str()),
+            None => self.kind.as_str().to_string(),
+        }
+    }
+
+    /// Bisect has no step to conclude; it can only be reset.
+    pub fn can_continue(&self) -> bool {
+        self.kind != OperationKind::Bisect
+    }
+
+    pub fn can_skip(&self) -> bool {
+        matches!(
+            self.kind,
+            OperationKind::Rebase | OperationKind::CherryPick | OperationKind::Revert
+        )
+    }
 }
 
 impl RepoStatus {
@@ -61,21 +201,194 @@ impl RepoStatus {
     pub fn is_clean(&self) -> bool {
         self.error.is_none() && self.changes.is_empty()
     }
+
+    pub fn conflicted_files(&self) -> Vec<String> {
+        self.changes
+            .iter()
+            .filter(|change| change.change == ChangeType::Unmerged)
+            .map(|change| change.path.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitFile {
+    pub path: String,
+    pub change: ChangeType,
+    pub additions: Option<usize>,
+    pub deletions: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitDetails {
+    pub id: String,
+    pub author: String,
+    pub author_email: String,
+    pub author_date: String,
+    pub committer: String,
+    pub committer_email: String,
+    pub commit_date: String,
+    pub parents: Vec<String>,
+    pub message: String,
+    pub files: Vec<CommitFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stash {
+    pub index: usize,
+    pub branch: Option<String>,
+    pub message: String,
+    pub age: String,
+}
+
+/// A tag with the abbreviated id of the commit it points at and, for an
+/// annotated tag, the subject of its message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub target: String,
+    pub annotation: Option<String>,
+}
+
+/// A configured remote. `push_url` is only set when it differs from `fetch_url`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remote {
+    pub name: String,
+    pub fetch_url: String,
+    pub push_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StashMode {
+    TrackedChanges,
+    IncludeUntracked,
+    StagedOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+    NoNewline,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub header: String,
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    pub header: Vec<String>,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl FileDiff {
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty() && self.hunks.is_empty()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.header.len()
+            + self
+                .hunks
+                .iter()
+                .map(|hunk| hunk.lines.len() + 1)
+                .sum::<usize>()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchAction {
+    Stage,
+    Unstage,
+    Discard,
+}
+
+/// What an interactive rebase does with one commit, as in a `git rebase -i` todo list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Edit => "edit",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+}
+
+/// One commit of an interactive rebase plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebaseStep {
+    pub action: RebaseAction,
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    /// The commit's whole message, body and trailers included, which rewording starts from.
+    pub original_message: String,
+    /// Replacement message for a `Reword` step; without one the message is kept.
+    pub message: Option<String>,
+}
+
+/// How a rebase, cherry-pick, revert or merge step ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationOutcome {
+    Finished,
+    /// Stopped with unmerged files to resolve before continuing.
+    Conflicts,
+    /// Stopped at an `edit` step or a failed message rewrite.
+    Stopped,
+}
+
+/// Shared flag asking a long-running git command, such as a push or fetch, to stop.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
 }
 
-pub fn fetch_branch_info() -> BranchInfo {
-    fetch_branch_info_in(".")
+/// Lists the branches, reusing the `known` counts of the previous fetch.
+pub fn fetch_branch_info(known: &AheadBehindCounts) -> BranchInfo {
+    fetch_branch_info_in(".", known)
 }
 
-pub fn fetch_branch_info_in(path: impl AsRef<Path>) -> BranchInfo {
-    match try_fetch_branch_info(path) {
+pub fn fetch_branch_info_in(path: impl AsRef<Path>, known: &AheadBehindCounts) -> BranchInfo {
+    match try_fetch_branch_info(path, known) {
         Ok(info) => info,
         Err(err) => BranchInfo {
-            branches: Vec::new(),
-            current: None,
             status: Some(err),
-            hovered: None,
-            selected: None,
+            ..BranchInfo::default()
         },
     }
 }
@@ -223,6 +536,263 @@ pub fn discard_change_in(repo: impl AsRef<Path>, path: &str) -> Result<(), Strin
     }
 }
 
+pub fn fetch_change_diff(change: &FileChange) -> Result<FileDiff, String> {
+    fetch_change_diff_in(".", change)
+}
+
+pub fn fetch_change_diff_in(
+    repo: impl AsRef<Path>,
+    change: &FileChange,
+) -> Result<FileDiff, String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.arg("diff").arg("--no-color").arg("--no-ext-diff");
+    if change.staged {
+        cmd.arg("--cached").arg("--").arg(&change.path);
+    } else if change.change == ChangeType::Untracked {
+        cmd.arg("--no-index")
+            .arg("--")
+            .arg("/dev/null")
+            .arg(&change.path);
+    } else {
+        cmd.arg("--").arg(&change.path);
+    }
+
+    let output = cmd
+        .current_dir(repo.as_ref())
+        .output()
+        .map_err(|err| format!("Failed to run git diff: {err}"))?;
+
+    // `--no-index` exits with 1 when the files differ, which is the expected case.
+    let no_index_diff = change.change == ChangeType::Untracked && output.status.code() == Some(1);
+    if output.status.success() || no_index_diff {
+        Ok(parse_diff(&String::from_utf8_lossy(&output.stdout)))
+    } else {
+        Err(format_git_error("git diff", &output))
+    }
+}
+
+fn parse_diff(text: &str) -> FileDiff {
+    let mut diff = FileDiff::default();
+    for line in text.lines() {
+        if line.starts_with("@@") {
+            diff.hunks.push(parse_hunk_header(line));
+            continue;
+        }
+
+        let Some(hunk) = diff.hunks.last_mut() else {
+            diff.header.push(line.to_string());
+            continue;
+        };
+
+        let (kind, content) = match line.chars().next() {
+            Some('+') => (DiffLineKind::Added, &line[1..]),
+            Some('-') => (DiffLineKind::Removed, &line[1..]),
+            Some('\\') => (DiffLineKind::NoNewline, line),
+            Some(' ') => (DiffLineKind::Context, &line[1..]),
+            _ => (DiffLineKind::Context, line),
+        };
+        hunk.lines.push(DiffLine {
+            kind,
+            content: content.to_string(),
+        });
+    }
+    diff
+}
+
+fn parse_hunk_header(line: &str) -> DiffHunk {
+    let ranges = line
+        .trim_start_matches("@@")
+        .split("@@")
+        .next()
+        .unwrap_or("")
+        .trim();
+    let mut old = (0, 0);
+    let mut new = (0, 0);
+    for part in ranges.split_whitespace() {
+        if let Some(range) = part.strip_prefix('-') {
+            old = parse_hunk_range(range);
+        } else if let Some(range) = part.strip_prefix('+') {
+            new = parse_hunk_range(range);
+        }
+    }
+
+    DiffHunk {
+        header: line.to_string(),
+        old_start: old.0,
+        old_lines: old.1,
+        new_start: new.0,
+        new_lines: new.1,
+        lines: Vec::new(),
+    }
+}
+
+fn parse_hunk_range(range: &str) -> (usize, usize) {
+    match range.split_once(',') {
+        Some((start, len)) => (start.parse().unwrap_or(0), len.parse().unwrap_or(0)),
+        None => (range.parse().unwrap_or(0), 1),
+    }
+}
+
+pub fn apply_partial_change(
+    change: &FileChange,
+    diff: &FileDiff,
+    selected: &HashSet<(usize, usize)>,
+    action: PatchAction,
+) -> Result<(), String> {
+    apply_partial_change_in(".", change, diff, selected, action)
+}
+
+/// Applies the selected `(hunk, line)` pairs of `diff` to the index or the
+/// working tree, leaving every other change in place.
+pub fn apply_partial_change_in(
+    repo: impl AsRef<Path>,
+    change: &FileChange,
+    diff: &FileDiff,
+    selected: &HashSet<(usize, usize)>,
+    action: PatchAction,
+) -> Result<(), String> {
+    match action {
+        PatchAction::Stage | PatchAction::Discard if change.staged => {
+            return Err("Only unstaged changes can be staged or discarded".to_string());
+        }
+        PatchAction::Unstage if !change.staged => {
+            return Err("Only staged changes can be unstaged".to_string());
+        }
+        _ => {}
+    }
+
+    let reverse = action != PatchAction::Stage;
+    let Some(patch) = build_partial_patch(diff, &change.path, selected, reverse) else {
+        return Err("No changed lines selected".to_string());
+    };
+
+    let mut args = vec!["apply", "--whitespace=nowarn"];
+    if action != PatchAction::Discard {
+        args.push("--cached");
+    }
+    if reverse {
+        args.push("--reverse");
+    }
+    args.push("-");
+    run_git_with_input(repo.as_ref(), &args, &patch, "git apply")
+}
+
+fn build_partial_patch(
+    diff: &FileDiff,
+    path: &str,
+    selected: &HashSet<(usize, usize)>,
+    reverse: bool,
+) -> Option<String> {
+    let total_changes = diff
+        .hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.iter())
+        .filter(|line| matches!(line.kind, DiffLineKind::Added | DiffLineKind::Removed))
+        .count();
+    let mut selected_changes = 0;
+    let mut hunks = String::new();
+    let mut delta: isize = 0;
+
+    for (hunk_idx, hunk) in diff.hunks.iter().enumerate() {
+        let mut body = String::new();
+        let mut old_lines = 0;
+        let mut new_lines = 0;
+        let mut hunk_changes = 0;
+        let mut previous_kept = false;
+
+        for (line_idx, line) in hunk.lines.iter().enumerate() {
+            let is_selected = selected.contains(&(hunk_idx, line_idx));
+            // Unselected lines must still describe the side the patch is applied to,
+            // so they either turn into context or disappear from the patch.
+            let emitted = match line.kind {
+                DiffLineKind::Context => Some(' '),
+                DiffLineKind::Added if is_selected => Some('+'),
+                DiffLineKind::Removed if is_selected => Some('-'),
+                DiffLineKind::Added if reverse => Some(' '),
+                DiffLineKind::Removed if !reverse => Some(' '),
+                DiffLineKind::Added | DiffLineKind::Removed => None,
+                DiffLineKind::NoNewline => {
+                    if previous_kept {
+                        body.push_str(&line.content);
+                        body.push('\n');
+                    }
+                    continue;
+                }
+            };
+
+            previous_kept = emitted.is_some();
+            let Some(prefix) = emitted else {
+                continue;
+            };
+            match prefix {
+                '+' => {
+                    new_lines += 1;
+                    hunk_changes += 1;
+                }
+                '-' => {
+                    old_lines += 1;
+                    hunk_changes += 1;
+                }
+                _ => {
+                    old_lines += 1;
+                    new_lines += 1;
+                }
+            }
+            body.push(prefix);
+            body.push_str(&line.content);
+            body.push('\n');
+        }
+
+        if hunk_changes == 0 {
+            continue;
+        }
+        selected_changes += hunk_changes;
+
+        // The side the patch applies to keeps its original position; the other
+        // side only shifts by what the previously emitted hunks added or removed.
+        let (old_start, new_start) = if reverse {
+            let old_start = (hunk.new_start as isize - delta).max(0) as usize;
+            (old_start, hunk.new_start)
+        } else {
+            let new_start = (hunk.old_start as isize + delta).max(0) as usize;
+            (hunk.old_start, new_start)
+        };
+        delta += new_lines as isize - old_lines as isize;
+
+        hunks.push_str(&format!(
+            "@@ -{old_start},{old_lines} +{new_start},{new_lines} @@\n"
+        ));
+        hunks.push_str(&body);
+    }
+
+    if selected_changes == 0 {
+        return None;
+    }
+
+    let partial = selected_changes < total_changes;
+    let mut patch = String::new();
+    for line in &diff.header {
+        if partial && !reverse && line.starts_with("deleted file mode") {
+            continue;
+        }
+        if partial && reverse && line.starts_with("new file mode") {
+            continue;
+        }
+        if partial && !reverse && line == "+++ /dev/null" {
+            patch.push_str(&format!("+++ b/{path}\n"));
+            continue;
+        }
+        if partial && reverse && line == "--- /dev/null" {
+            patch.push_str(&format!("--- a/{path}\n"));
+            continue;
+        }
+        patch.push_str(line);
+        patch.push('\n');
+    }
+    patch.push_str(&hunks);
+    Some(patch)
+}
+
 pub fn commit_staged(message: &str) -> Result<(), String> {
     commit_staged_in(".", message)
 }
@@ -248,100 +818,468 @@ pub fn commit_staged_in(repo: impl AsRef<Path>, message: &str) -> Result<(), Str
     }
 }
 
-pub fn create_branch(branch: &str) -> Result<(), String> {
-    create_branch_in(".", branch)
+pub fn amend_head(message: &str) -> Result<(), String> {
+    amend_head_in(".", message)
 }
 
-pub fn create_branch_in(path: impl AsRef<Path>, branch: &str) -> Result<(), String> {
-    if branch.trim().is_empty() {
-        return Err("Branch name cannot be empty".to_string());
+/// Folds the staged changes into HEAD and replaces its message.
+pub fn amend_head_in(repo: impl AsRef<Path>, message: &str) -> Result<(), String> {
+    if message.trim().is_empty() {
+        return Err("Commit message cannot be empty".to_string());
     }
-
     let output = std::process::Command::new("git")
-        .arg("checkout")
-        .arg("-b")
-        .arg(branch)
-        .current_dir(path.as_ref())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::piped())
+        .args(["commit", "--amend", "--allow-empty", "-m", message])
+        .current_dir(repo.as_ref())
         .output()
-        .map_err(|err| format!("Failed to run git checkout -b: {err}"))?;
-
+        .map_err(|err| format!("Failed to run git commit --amend: {err}"))?;
     if output.status.success() {
         Ok(())
     } else {
-        let message = String::from_utf8_lossy(&output.stderr);
-        let trimmed = message.trim();
-        if trimmed.is_empty() {
-            Err(format!(
-                "git checkout -b exited with status: {}",
-                output.status
-            ))
-        } else {
-            Err(format!("Failed to create branch {branch}: {trimmed}"))
-        }
+        Err(format_git_error("git commit --amend", &output))
     }
 }
 
-pub fn delete_branch(branch: &str) -> Result<(), String> {
-    delete_branch_in(".", branch)
+pub fn fetch_head_message() -> Result<String, String> {
+    fetch_head_message_in(".")
 }
 
-pub fn delete_branch_in(path: impl AsRef<Path>, branch: &str) -> Result<(), String> {
+pub fn fetch_head_message_in(repo: impl AsRef<Path>) -> Result<String, String> {
     let output = std::process::Command::new("git")
-        .arg("branch")
-        .arg("-d")
-        .arg(branch)
-        .current_dir(path.as_ref())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::piped())
+        .args(["log", "-1", "--format=%B"])
+        .current_dir(repo.as_ref())
         .output()
-        .map_err(|err| format!("Failed to run git branch -d: {err}"))?;
-
-    if output.status.success() {
-        Ok(())
-    } else {
-        let message = String::from_utf8_lossy(&output.stderr);
-        let trimmed = message.trim();
-        if trimmed.is_empty() {
-            Err(format!(
-                "git branch -d exited with status: {}",
-                output.status
-            ))
-        } else {
-            Err(format!("Failed to delete branch {branch}: {trimmed}"))
-        }
+        .map_err(|err| format!("Failed to run git log: {err}"))?;
+    if !output.status.success() {
+        return Err(format_git_error("git log", &output));
     }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
 }
 
-pub fn delete_remote_branch(remote_ref: &str) -> Result<(), String> {
-    delete_remote_branch_in(".", remote_ref)
+pub fn head_pushed_to() -> Option<String> {
+    head_pushed_to_in(".")
 }
 
-pub fn delete_remote_branch_in(path: impl AsRef<Path>, remote_ref: &str) -> Result<(), String> {
-    let (remote, branch) = split_remote_ref(remote_ref)?;
+/// The upstream of the current branch when it already contains HEAD, meaning
+/// an amend would rewrite published history.
+pub fn head_pushed_to_in(repo: impl AsRef<Path>) -> Option<String> {
+    let path = repo.as_ref();
+    let upstream = upstream_for_branch(path, "HEAD")?;
+    std::process::Command::new("git")
+        .args(["merge-base", "--is-ancestor", "HEAD", &upstream])
+        .current_dir(path)
+        .output()
+        .ok()?
+        .status
+        .success()
+        .then_some(upstream)
+}
+
+pub fn fetch_stashes() -> Result<Vec<Stash>, String> {
+    fetch_stashes_in(".")
+}
+
+pub fn fetch_stashes_in(path: impl AsRef<Path>) -> Result<Vec<Stash>, String> {
     let output = std::process::Command::new("git")
-        .arg("push")
-        .arg(remote)
-        .arg("--delete")
-        .arg(branch)
+        .arg("stash")
+        .arg("list")
+        .arg("--format=%gd%x09%gs%x09%cr")
         .current_dir(path.as_ref())
         .output()
-        .map_err(|err| format!("Failed to run git push --delete: {err}"))?;
+        .map_err(|err| format!("Failed to run git stash list: {err}"))?;
 
-    if output.status.success() {
-        Ok(())
+    if !output.status.success() {
+        return Err(format_git_error("git stash list", &output));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_stash_line)
+        .collect())
+}
+
+fn parse_stash_line(line: &str) -> Option<Stash> {
+    let mut parts = line.splitn(3, '\t');
+    let reference = parts.next()?.trim();
+    let subject = parts.next().unwrap_or("").trim();
+    let age = parts.next().unwrap_or("").trim().to_string();
+
+    let index = reference
+        .strip_prefix("stash@{")?
+        .strip_suffix('}')?
+        .parse()
+        .ok()?;
+
+    // Subjects look like "WIP on main: abc1234 summary" or "On main: message".
+    let (branch, message) = match subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "))
+        .and_then(|rest| rest.split_once(": "))
+    {
+        Some((branch, message)) => (Some(branch.to_string()), message.to_string()),
+        None => (None, subject.to_string()),
+    };
+
+    Some(Stash {
+        index,
+        branch,
+        message,
+        age,
+    })
+}
+
+pub fn create_stash(mode: StashMode) -> Result<(), String> {
+    create_stash_in(".", mode)
+}
+
+pub fn create_stash_in(path: impl AsRef<Path>, mode: StashMode) -> Result<(), String> {
+    let mut args = vec!["stash", "push"];
+    match mode {
+        StashMode::TrackedChanges => {}
+        StashMode::IncludeUntracked => args.push("--include-untracked"),
+        StashMode::StagedOnly => args.push("--staged"),
+    }
+
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .current_dir(path.as_ref())
+        .output()
+        .map_err(|err| format!("Failed to run git stash push: {err}"))?;
+
+    if !output.status.success() {
+        return Err(format_git_error("git stash push", &output));
+    }
+
+    // `git stash push` succeeds without creating anything when there is nothing to save.
+    if String::from_utf8_lossy(&output.stdout).contains("No local changes to save") {
+        return Err("No local changes to stash".to_string());
+    }
+    Ok(())
+}
+
+pub fn apply_stash(index: usize) -> Result<(), String> {
+    apply_stash_in(".", index)
+}
+
+pub fn apply_stash_in(path: impl AsRef<Path>, index: usize) -> Result<(), String> {
+    let stash = format!("stash@{{{index}}}");
+    run_git_command(path.as_ref(), ["stash", "apply", &stash], "git stash apply")
+}
+
+pub fn pop_stash(index: usize) -> Result<(), String> {
+    pop_stash_in(".", index)
+}
+
+pub fn pop_stash_in(path: impl AsRef<Path>, index: usize) -> Result<(), String> {
+    let stash = format!("stash@{{{index}}}");
+    run_git_command(path.as_ref(), ["stash", "pop", &stash], "git stash pop")
+}
+
+pub fn drop_stash(index: usize) -> Result<(), String> {
+    drop_stash_in(".", index)
+}
+
+pub fn drop_stash_in(path: impl AsRef<Path>, index: usize) -> Result<(), String> {
+    let stash = format!("stash@{{{index}}}");
+    run_git_command(path.as_ref(), ["stash", "drop", &stash], "git stash drop")
+}
+
+pub fn branch_from_stash(branch: &str, index: usize) -> Result<(), String> {
+    branch_from_stash_in(".", branch, index)
+}
+
+pub fn branch_from_stash_in(
+    path: impl AsRef<Path>,
+    branch: &str,
+    index: usize,
+) -> Result<(), String> {
+    if branch.trim().is_empty() {
+        return Err("Branch name cannot be empty".to_string());
+    }
+    let stash = format!("stash@{{{index}}}");
+    run_git_command(
+        path.as_ref(),
+        ["stash", "branch", branch, &stash],
+        "git stash branch",
+    )
+}
+
+pub fn fetch_tags() -> Result<Vec<Tag>, String> {
+    fetch_tags_in(".")
+}
+
+/// Lists tags, newest first.
+pub fn fetch_tags_in(path: impl AsRef<Path>) -> Result<Vec<Tag>, String> {
+    let output = std::process::Command::new("git")
+        .arg("for-each-ref")
+        .arg("refs/tags")
+        .arg("--sort=-creatordate")
+        .arg("--format=%(refname:short)%00%(objecttype)%00%(*objectname:short)%00%(objectname:short)%00%(contents:subject)")
+        .current_dir(path.as_ref())
+        .output()
+        .map_err(|err| format!("Failed to run git for-each-ref: {err}"))?;
+
+    if !output.status.success() {
+        return Err(format_git_error("git for-each-ref", &output));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_tag_line)
+        .collect())
+}
+
+fn parse_tag_line(line: &str) -> Option<Tag> {
+    let mut parts = line.splitn(5, '\0');
+    let name = parts.next()?.trim();
+    let kind = parts.next().unwrap_or("");
+    let peeled = parts.next().unwrap_or("");
+    let object = parts.next().unwrap_or("");
+    let subject = parts.next().unwrap_or("").trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    // An annotated tag is its own object; the commit is the object it peels to.
+    let (target, annotation) = if kind == "tag" {
+        (peeled, Some(subject.to_string()))
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let message = clean_git_message(stderr.trim());
-        if message.is_empty() {
-            Err(format!("git push --delete exited with status: {}", output.status))
+        (object, None)
+    };
+    Some(Tag {
+        name: name.to_string(),
+        target: target.to_string(),
+        annotation,
+    })
+}
+
+pub fn create_tag(name: &str, target: &str, message: Option<&str>) -> Result<(), String> {
+    create_tag_in(".", name, target, message)
+}
+
+/// Tags `target`, annotated with `message` when one is given.
+pub fn create_tag_in(
+    path: impl AsRef<Path>,
+    name: &str,
+    target: &str,
+    message: Option<&str>,
+) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    match message.map(str::trim).filter(|message| !message.is_empty()) {
+        Some(message) => run_git_command(
+            path.as_ref(),
+            ["tag", "-a", name, "-m", message, target],
+            "git tag",
+        ),
+        None => run_git_command(path.as_ref(), ["tag", name, target], "git tag"),
+    }
+}
+
+pub fn delete_tag(name: &str) -> Result<(), String> {
+    delete_tag_in(".", name)
+}
+
+pub fn delete_tag_in(path: impl AsRef<Path>, name: &str) -> Result<(), String> {
+    run_git_command(path.as_ref(), ["tag", "-d", name], "git tag -d")
+}
+
+pub fn push_tag(name: &str, cancel: &CancelToken) -> Result<String, String> {
+    push_tag_in(".", name, cancel)
+}
+
+/// Pushes the tag to the current branch's remote, returning the remote's name.
+pub fn push_tag_in(
+    path: impl AsRef<Path>,
+    name: &str,
+    cancel: &CancelToken,
+) -> Result<String, String> {
+    let path = path.as_ref();
+    let remote = default_remote(path)?;
+    let refspec = format!("refs/tags/{name}");
+    run_git_cancellable(path, &["push", &remote, &refspec], "git push", cancel)?;
+    Ok(remote)
+}
+
+pub fn delete_remote_tag(name: &str, cancel: &CancelToken) -> Result<String, String> {
+    delete_remote_tag_in(".", name, cancel)
+}
+
+/// Deletes the tag from the current branch's remote, returning the remote's name.
+pub fn delete_remote_tag_in(
+    path: impl AsRef<Path>,
+    name: &str,
+    cancel: &CancelToken,
+) -> Result<String, String> {
+    let path = path.as_ref();
+    let remote = default_remote(path)?;
+    let refspec = format!("refs/tags/{name}");
+    run_git_cancellable(
+        path,
+        &["push", &remote, "--delete", &refspec],
+        "git push --delete",
+        cancel,
+    )?;
+    Ok(remote)
+}
+
+pub fn create_branch(branch: &str) -> Result<(), String> {
+    create_branch_in(".", branch)
+}
+
+pub fn create_branch_in(path: impl AsRef<Path>, branch: &str) -> Result<(), String> {
+    create_branch_from_in(path, branch, "HEAD")
+}
+
+pub fn create_branch_from(branch: &str, start_point: &str) -> Result<(), String> {
+    create_branch_from_in(".", branch, start_point)
+}
+
+/// Creates `branch` at `start_point` (a branch, remote-tracking branch or
+/// commit) and checks it out. The new branch does not track `start_point`.
+pub fn create_branch_from_in(
+    path: impl AsRef<Path>,
+    branch: &str,
+    start_point: &str,
+) -> Result<(), String> {
+    if branch.trim().is_empty() {
+        return Err("Branch name cannot be empty".to_string());
+    }
+
+    let output = std::process::Command::new("git")
+        .arg("checkout")
+        .arg("--no-track")
+        .arg("-b")
+        .arg(branch)
+        .arg(start_point)
+        .current_dir(path.as_ref())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .map_err(|err| format!("Failed to run git checkout -b: {err}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let message = String::from_utf8_lossy(&output.stderr);
+        let trimmed = message.trim();
+        if trimmed.is_empty() {
+            Err(format!(
+                "git checkout -b exited with status: {}",
+                output.status
+            ))
         } else {
-            Err(message)
+            Err(format!("Failed to create branch {branch}: {trimmed}"))
+        }
+    }
+}
+
+pub fn rename_branch(branch: &str, new_name: &str) -> Result<(), String> {
+    rename_branch_in(".", branch, new_name)
+}
+
+/// Renames a local branch. Its upstream setting moves along unchanged.
+pub fn rename_branch_in(
+    path: impl AsRef<Path>,
+    branch: &str,
+    new_name: &str,
+) -> Result<(), String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Branch name cannot be empty".to_string());
+    }
+    run_git_command(
+        path.as_ref(),
+        ["branch", "-m", branch, new_name],
+        "git branch -m",
+    )
+}
+
+pub fn rename_upstream_branch(
+    branch: &str,
+    upstream: &str,
+    cancel: &CancelToken,
+) -> Result<String, String> {
+    rename_upstream_branch_in(".", branch, upstream, cancel)
+}
+
+/// Pushes the local `branch` to `upstream`'s remote under its own name, deletes
+/// `upstream` there and makes `branch` track the new remote branch, which is returned.
+pub fn rename_upstream_branch_in(
+    path: impl AsRef<Path>,
+    branch: &str,
+    upstream: &str,
+    cancel: &CancelToken,
+) -> Result<String, String> {
+    let path = path.as_ref();
+    let (remote, old_name) = split_remote_ref(upstream)?;
+    let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+    run_git_cancellable(path, &["push", remote, &refspec], "git push", cancel)?;
+    if old_name != branch {
+        run_git_cancellable(
+            path,
+            &["push", remote, "--delete", old_name],
+            "git push --delete",
+            cancel,
+        )?;
+    }
+    let new_upstream = format!("{remote}/{branch}");
+    set_branch_upstream(path, branch, &new_upstream)?;
+    Ok(new_upstream)
+}
+
+pub fn delete_branch(branch: &str) -> Result<(), String> {
+    delete_branch_in(".", branch)
+}
+
+pub fn delete_branch_in(path: impl AsRef<Path>, branch: &str) -> Result<(), String> {
+    let output = std::process::Command::new("git")
+        .arg("branch")
+        .arg("-d")
+        .arg(branch)
+        .current_dir(path.as_ref())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .map_err(|err| format!("Failed to run git branch -d: {err}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let message = String::from_utf8_lossy(&output.stderr);
+        let trimmed = message.trim();
+        if trimmed.is_empty() {
+            Err(format!(
+                "git branch -d exited with status: {}",
+                output.status
+            ))
+        } else {
+            Err(format!("Failed to delete branch {branch}: {trimmed}"))
         }
     }
 }
 
+pub fn delete_remote_branch(remote_ref: &str, cancel: &CancelToken) -> Result<(), String> {
+    delete_remote_branch_in(".", remote_ref, cancel)
+}
+
+pub fn delete_remote_branch_in(
+    path: impl AsRef<Path>,
+    remote_ref: &str,
+    cancel: &CancelToken,
+) -> Result<(), String> {
+    let (remote, branch) = split_remote_ref(remote_ref)?;
+    run_git_cancellable(
+        path.as_ref(),
+        &["push", remote, "--delete", branch],
+        "git push --delete",
+        cancel,
+    )
+}
+
 pub fn delete_remote_tracking_ref(remote_ref: &str) -> Result<(), String> {
     delete_remote_tracking_ref_in(".", remote_ref)
 }
@@ -373,20 +1311,17 @@ pub fn delete_remote_tracking_ref_in(
         }
     }
 }
-pub fn fetch_remotes() -> Result<(), String> {
-    fetch_remotes_in(".")
+pub fn fetch_remotes(cancel: &CancelToken) -> Result<(), String> {
+    fetch_remotes_in(".", cancel)
 }
 
-pub fn fetch_remotes_in(path: impl AsRef<Path>) -> Result<(), String> {
-    let output = std::process::Command::new("git")
-        .arg("fetch")
+pub fn fetch_remotes_in(path: impl AsRef<Path>, cancel: &CancelToken) -> Result<(), String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.arg("fetch")
         .arg("--all")
         .arg("--prune")
-        .current_dir(path.as_ref())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::piped())
-        .output()
-        .map_err(|err| format!("Failed to run git fetch: {err}"))?;
+        .current_dir(path.as_ref());
+    let output = output_cancellable(cmd, cancel, "git fetch")?;
 
     if output.status.success() {
         Ok(())
@@ -401,70 +1336,314 @@ pub fn fetch_remotes_in(path: impl AsRef<Path>) -> Result<(), String> {
     }
 }
 
-pub fn pull_current_branch() -> Result<(), String> {
-    pull_current_branch_in(".")
+pub fn fetch_remote(name: &str, cancel: &CancelToken) -> Result<(), String> {
+    fetch_remote_in(".", name, cancel)
 }
 
-pub fn pull_current_branch_in(path: impl AsRef<Path>) -> Result<(), String> {
-    let path = path.as_ref();
-    let branch = current_branch_name_in(path)
-        .ok_or_else(|| "Failed to read current branch name".to_string())?;
+/// Fetches and prunes a single remote.
+pub fn fetch_remote_in(
+    path: impl AsRef<Path>,
+    name: &str,
+    cancel: &CancelToken,
+) -> Result<(), String> {
+    run_git_cancellable(
+        path.as_ref(),
+        &["fetch", "--prune", name],
+        "git fetch",
+        cancel,
+    )
+}
 
-    let upstream = upstream_for_branch(path, &branch);
-    let remote = upstream
-        .as_deref()
-        .and_then(upstream_remote)
-        .map(str::to_string)
-        .or_else(|| remote_for_branch(path, &branch))
-        .or_else(|| first_remote(path))
-        .ok_or_else(|| "No remote configured for current branch".to_string())?;
+pub fn list_remotes() -> Result<Vec<Remote>, String> {
+    list_remotes_in(".")
+}
 
-    git_fetch(path, &remote)?;
+/// Lists the configured remotes in the order git reports them.
+pub fn list_remotes_in(path: impl AsRef<Path>) -> Result<Vec<Remote>, String> {
+    let output = std::process::Command::new("git")
+        .arg("remote")
+        .arg("-v")
+        .current_dir(path.as_ref())
+        .output()
+        .map_err(|err| format!("Failed to run git remote: {err}"))?;
 
-    if upstream.is_none() {
-        set_branch_upstream(path, &branch, &branch_remote_target(path, &branch, &remote))?;
+    if !output.status.success() {
+        return Err(format_git_error("git remote", &output));
     }
 
-    git_pull_ff_only(path)
+    Ok(parse_remotes(&String::from_utf8_lossy(&output.stdout)))
 }
 
-pub fn push_current_branch() -> Result<(), String> {
-    push_current_branch_in(".")
+/// Folds `git remote -v` lines like `origin\tgit@host:repo (fetch)` into remotes.
+fn parse_remotes(output: &str) -> Vec<Remote> {
+    let mut remotes: Vec<Remote> = Vec::new();
+    for line in output.lines() {
+        let Some((name, rest)) = line.split_once('\t') else {
+            continue;
+        };
+        let (url, kind) = match rest.rsplit_once(' ') {
+            Some((url, kind)) => (url.trim(), kind.trim()),
+            None => (rest.trim(), "(fetch)"),
+        };
+        let index = match remotes.iter().position(|remote| remote.name == name) {
+            Some(index) => index,
+            None => {
+                remotes.push(Remote {
+                    name: name.to_string(),
+                    fetch_url: String::new(),
+                    push_url: None,
+                });
+                remotes.len() - 1
+            }
+        };
+        let remote = &mut remotes[index];
+        if kind == "(push)" {
+            remote.push_url = Some(url.to_string());
+        } else {
+            remote.fetch_url = url.to_string();
+        }
+    }
+    for remote in &mut remotes {
+        if remote.push_url.as_deref() == Some(remote.fetch_url.as_str()) {
+            remote.push_url = None;
+        }
+    }
+    remotes
 }
 
-pub fn push_current_branch_in(path: impl AsRef<Path>) -> Result<(), String> {
-    let path = path.as_ref();
-    let branch = current_branch_name_in(path)
-        .ok_or_else(|| "Failed to read current branch name".to_string())?;
-    let upstream = upstream_for_branch(path, &branch);
-    let remote = upstream
-        .as_deref()
-        .map(str::to_string)
-        .or_else(|| remote_for_branch(path, &branch))
-        .or_else(|| first_remote(path))
-        .ok_or_else(|| "No remote configured for current branch".to_string())?;
+pub fn add_remote(name: &str, url: &str) -> Result<(), String> {
+    add_remote_in(".", name, url)
+}
 
-    git_push(path, &remote, &branch, upstream.is_some())
+pub fn add_remote_in(path: impl AsRef<Path>, name: &str, url: &str) -> Result<(), String> {
+    let (name, url) = (name.trim(), url.trim());
+    if name.is_empty() {
+        return Err("Remote name cannot be empty".to_string());
+    }
+    if url.is_empty() {
+        return Err("Remote URL cannot be empty".to_string());
+    }
+    run_git_command(
+        path.as_ref(),
+        ["remote", "add", name, url],
+        "git remote add",
+    )
 }
 
-pub fn fetch_commits() -> Result<Vec<Commit>, String> {
-    fetch_commits_in(".")
+pub fn rename_remote(name: &str, new_name: &str) -> Result<(), String> {
+    rename_remote_in(".", name, new_name)
 }
 
-pub fn fetch_commits_in(path: impl AsRef<Path>) -> Result<Vec<Commit>, String> {
-    let main_branch = find_main_branch_in(path.as_ref());
-    let main_commits = main_branch
+/// Renames a remote; git moves its remote-tracking refs and branch settings along.
+pub fn rename_remote_in(path: impl AsRef<Path>, name: &str, new_name: &str) -> Result<(), String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Remote name cannot be empty".to_string());
+    }
+    run_git_command(
+        path.as_ref(),
+        ["remote", "rename", name, new_name],
+        "git remote rename",
+    )
+}
+
+pub fn remove_remote(name: &str) -> Result<(), String> {
+    remove_remote_in(".", name)
+}
+
+pub fn remove_remote_in(path: impl AsRef<Path>, name: &str) -> Result<(), String> {
+    run_git_command(
+        path.as_ref(),
+        ["remote", "remove", name],
+        "git remote remove",
+    )
+}
+
+pub fn set_remote_url(name: &str, url: &str, push: bool) -> Result<(), String> {
+    set_remote_url_in(".", name, url, push)
+}
+
+/// Sets the remote's fetch URL, or only its push URL when `push` is set.
+pub fn set_remote_url_in(
+    path: impl AsRef<Path>,
+    name: &str,
+    url: &str,
+    push: bool,
+) -> Result<(), String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("Remote URL cannot be empty".to_string());
+    }
+    if push {
+        run_git_command(
+            path.as_ref(),
+            ["remote", "set-url", "--push", name, url],
+            "git remote set-url",
+        )
+    } else {
+        run_git_command(
+            path.as_ref(),
+            ["remote", "set-url", name, url],
+            "git remote set-url",
+        )
+    }
+}
+
+pub fn pull_current_branch(cancel: &CancelToken) -> Result<(), String> {
+    pull_current_branch_in(".", cancel)
+}
+
+pub fn pull_current_branch_in(path: impl AsRef<Path>, cancel: &CancelToken) -> Result<(), String> {
+    let path = path.as_ref();
+    let branch = current_branch_name_in(path)
+        .ok_or_else(|| "Failed to read current branch name".to_string())?;
+
+    let upstream = upstream_for_branch(path, &branch);
+    let remote = upstream
         .as_deref()
-        .map(|name| commits_in_branch(path.as_ref(), name))
-        .transpose()?
-        .unwrap_or_default();
+        .and_then(upstream_remote)
+        .map(str::to_string)
+        .or_else(|| remote_for_branch(path, &branch))
+        .or_else(|| first_remote(path))
+        .ok_or_else(|| "No remote configured for current branch".to_string())?;
+
+    git_fetch(path, &remote, cancel)?;
+
+    if upstream.is_none() {
+        set_branch_upstream(path, &branch, &branch_remote_target(path, &branch, &remote))?;
+    }
+
+    git_pull_ff_only(path, cancel)
+}
+
+pub fn push_current_branch(cancel: &CancelToken) -> Result<(), String> {
+    push_current_branch_in(".", cancel)
+}
+
+pub fn push_current_branch_in(path: impl AsRef<Path>, cancel: &CancelToken) -> Result<(), String> {
+    let path = path.as_ref();
+    let branch = current_branch_name_in(path)
+        .ok_or_else(|| "Failed to read current branch name".to_string())?;
+    let upstream = upstream_for_branch(path, &branch);
+    let remote = upstream
+        .as_deref()
+        .map(str::to_string)
+        .or_else(|| remote_for_branch(path, &branch))
+        .or_else(|| first_remote(path))
+        .ok_or_else(|| "No remote configured for current branch".to_string())?;
+
+    git_push(path, &remote, &branch, upstream.is_some(), cancel)
+}
+
+pub fn set_upstream(branch: &str, remote_ref: &str) -> Result<(), String> {
+    set_upstream_in(".", branch, remote_ref)
+}
+
+/// Makes `branch` track the remote-tracking branch `remote_ref`, like `origin/main`.
+pub fn set_upstream_in(
+    path: impl AsRef<Path>,
+    branch: &str,
+    remote_ref: &str,
+) -> Result<(), String> {
+    set_branch_upstream(path.as_ref(), branch, remote_ref)
+}
 
+pub fn unset_upstream(branch: &str) -> Result<(), String> {
+    unset_upstream_in(".", branch)
+}
+
+pub fn unset_upstream_in(path: impl AsRef<Path>, branch: &str) -> Result<(), String> {
+    run_git_command(
+        path.as_ref(),
+        ["branch", "--unset-upstream", branch],
+        "git branch --unset-upstream",
+    )
+}
+
+pub fn list_remote_branches() -> Result<Vec<String>, String> {
+    list_remote_branches_in(".")
+}
+
+/// Lists remote-tracking branches such as `origin/main`, skipping the remotes' HEADs.
+pub fn list_remote_branches_in(path: impl AsRef<Path>) -> Result<Vec<String>, String> {
     let output = std::process::Command::new("git")
-        .arg("log")
-        .arg("--all")
-        .arg("--pretty=format:%H%x09%h%x09%s")
+        .arg("for-each-ref")
+        .arg("refs/remotes")
+        .arg("--format=%(refname:short)%00%(symref)")
         .current_dir(path.as_ref())
         .output()
+        .map_err(|err| format!("Failed to run git for-each-ref: {err}"))?;
+
+    if !output.status.success() {
+        return Err(format_git_error("git for-each-ref", &output));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, symref) = line.split_once('\0').unwrap_or((line, ""));
+            (symref.is_empty() && !name.is_empty()).then(|| name.to_string())
+        })
+        .collect())
+}
+
+/// Where the next page of the log picks up, so paging never re-reads the
+/// commits already listed.
+#[derive(Debug, Default, Clone)]
+pub struct LogCursor {
+    /// Commits listed so far, skipped by the next page of a filtered log.
+    pub skip: usize,
+    /// The commits an unfiltered log goes on from: ref tips and parents of
+    /// listed commits that are not listed yet. `None` before the first page.
+    frontier: Option<BTreeSet<String>>,
+    /// Branches reaching the not yet listed parents of listed commits, by
+    /// full parent id and index into the local branch tips.
+    pending: HashMap<String, BTreeSet<usize>>,
+}
+
+pub fn fetch_commits(
+    cursor: &mut LogCursor,
+    count: usize,
+    filter: &LogFilter,
+) -> Result<Vec<Commit>, String> {
+    fetch_commits_in(".", cursor, count, filter)
+}
+
+/// Lists the next `count` commits after `cursor` reachable from any ref that
+/// pass `filter`, children before parents, and moves the cursor past them.
+pub fn fetch_commits_in(
+    path: impl AsRef<Path>,
+    cursor: &mut LogCursor,
+    count: usize,
+    filter: &LogFilter,
+) -> Result<Vec<Commit>, String> {
+    let path = path.as_ref();
+    let branch_tips = local_branch_tips(path)?;
+    let main_branch = ["main", "master"]
+        .into_iter()
+        .find(|candidate| branch_tips.iter().any(|(name, _)| name == candidate));
+
+    // A filter hides commits the walk still goes through, so only an
+    // unfiltered log has listed everything above its frontier.
+    let continues = filter.is_empty();
+    let mut log = std::process::Command::new("git");
+    log.arg("log").arg("--date-order");
+    match &cursor.frontier {
+        Some(frontier) if continues && frontier.is_empty() => return Ok(Vec::new()),
+        Some(frontier) if continues => {
+            log.args(frontier);
+        }
+        _ => {
+            log.arg("--all").arg(format!("--skip={}", cursor.skip));
+        }
+    }
+    let output = log
+        .arg(format!("--max-count={count}"))
+        .arg("--decorate-refs=refs/tags")
+        .arg("--pretty=format:%H%x09%h%x09%P%x09%p%x09%D%x09%s")
+        .args(filter.log_args())
+        .current_dir(path)
+        .output()
         .map_err(|err| format!("Failed to run git log: {err}"))?;
 
     if !output.status.success() {
@@ -478,748 +1657,2728 @@ pub fn fetch_commits_in(path: impl AsRef<Path>) -> Result<Vec<Commit>, String> {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut commits = Vec::new();
+    let mut entries = Vec::new();
     for line in stdout.lines() {
-        let mut parts = line.splitn(3, '\t');
+        let mut parts = line.splitn(6, '\t');
         let full_id = parts.next().unwrap_or("").trim();
         let short_id = parts.next().unwrap_or("").trim();
-        let summary = parts.next().unwrap_or("").trim().to_string();
+        let parents: Vec<&str> = parts.next().unwrap_or("").split_whitespace().collect();
+        let short_parents: Vec<&str> = parts.next().unwrap_or("").split_whitespace().collect();
+        let tags = parse_tag_decorations(parts.next().unwrap_or(""));
+        let summary = parts.next().unwrap_or("").trim();
         if full_id.is_empty() || short_id.is_empty() {
             continue;
         }
+        entries.push((full_id, short_id, parents, short_parents, tags, summary));
+    }
 
-        let mut branches = Vec::new();
-        if let Some(main) = &main_branch {
-            if main_commits.contains(full_id) {
-                branches.push(main.clone());
-            }
+    // When the filter leaves the parents out, branches can't be followed down
+    // through them, so only the commits a branch points at get its label.
+    let graph: Vec<(&str, &[&str])> = entries
+        .iter()
+        .map(|(full_id, _, parents, _, _, _)| {
+            let parents = if filter.hides_ancestry() {
+                &[]
+            } else {
+                parents.as_slice()
+            };
+            (*full_id, parents)
+        })
+        .collect();
+    let membership = branch_membership(&graph, &branch_tips, &mut cursor.pending);
+    cursor.skip += entries.len();
+    if continues {
+        let mut frontier = match cursor.frontier.take() {
+            Some(frontier) => frontier,
+            None => ref_tip_commits(path)?,
+        };
+        for (_, parents) in &graph {
+            frontier.extend(parents.iter().map(|id| id.to_string()));
         }
-
-        if branches.is_empty() {
-            let mut containing = branches_containing_commit(path.as_ref(), full_id)?;
-            if let Some(main) = &main_branch {
-                containing.retain(|b| b != main);
-            }
-            branches = containing;
+        for (id, _) in &graph {
+            frontier.remove(*id);
         }
-
-        commits.push(Commit {
-            id: short_id.to_string(),
-            summary,
-            branches,
-        });
+        cursor.frontier = Some(frontier);
     }
 
-    Ok(commits)
-}
-
-pub fn fetch_repo_status() -> RepoStatus {
-    fetch_repo_status_in(".")
-}
+    let commits = entries
+        .iter()
+        .zip(membership)
+        .map(
+            |((_, short_id, _, short_parents, tags, summary), containing)| {
+                let names = containing.iter().map(|&idx| branch_tips[idx].0.as_str());
+                let branches = match main_branch {
+                    Some(main) if names.clone().any(|name| name == main) => vec![main.to_string()],
+                    _ => {
+                        let mut names: Vec<String> = names.map(str::to_string).collect();
+                        names.sort();
+                        names
+                    }
+                };
+                Commit {
+                    id: short_id.to_string(),
+                    summary: summary.to_string(),
+                    branches,
+                    parents: short_parents.iter().map(|id| id.to_string()).collect(),
+                    tags: tags.clone(),
+                }
+            },
+        )
+        .collect();
 
-pub fn fetch_repo_status_in(path: impl AsRef<Path>) -> RepoStatus {
-    let path = path.as_ref();
-    match try_fetch_repo_status(path) {
-        Ok(status) => status,
-        Err(err) => RepoStatus {
-            changes: Vec::new(),
-            error: Some(err),
-            repo_name: repository_name(path),
-        },
-    }
+    Ok(commits)
 }
 
-fn try_fetch_repo_status(path: &Path) -> Result<RepoStatus, String> {
+/// The commits HEAD and every ref point at, with tags peeled.
+fn ref_tip_commits(path: &Path) -> Result<BTreeSet<String>, String> {
     let output = std::process::Command::new("git")
-        .arg("status")
-        .arg("--porcelain=v1")
-        .arg("--untracked-files=all")
+        .arg("log")
+        .arg("--no-walk")
+        .arg("--all")
+        .arg("--format=%H")
         .current_dir(path)
         .output()
-        .map_err(|err| format!("Failed to run git status: {err}"))?;
+        .map_err(|err| format!("Failed to run git log: {err}"))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let trimmed = stderr.trim();
-        return Err(if trimmed.is_empty() {
-            format!("git status exited with status: {}", output.status)
-        } else {
-            trimmed.to_string()
-        });
+        return Err(format_git_error("git log --no-walk", &output));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut changes: Vec<FileChange> = stdout.lines().flat_map(parse_status_line).collect();
-    changes.sort_by(|a, b| a.path.cmp(&b.path));
-    Ok(RepoStatus {
-        changes,
-        error: None,
-        repo_name: repository_name(path),
-    })
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
 }
 
-fn parse_status_line(line: &str) -> Vec<FileChange> {
-    if line.len() < 3 {
-        return Vec::new();
-    }
-    let code = &line[0..2];
-    let mut path = line[3..].trim();
-    if path.is_empty() {
-        return Vec::new();
-    }
-    if let Some((_, new)) = path.split_once(" -> ") {
-        path = new.trim();
-    }
-    let mut entries = Vec::new();
-    if code == "??" {
-        entries.push(FileChange {
-            path: path.to_string(),
-            change: ChangeType::Untracked,
-            staged: false,
-        });
-        return entries;
-    }
-
-    let mut chars = code.chars();
-    let x = chars.next().unwrap_or(' ');
-    let y = chars.next().unwrap_or(' ');
-
-    if x != ' ' {
-        entries.push(FileChange {
-            path: path.to_string(),
-            change: change_type_from_flag(x),
-            staged: true,
-        });
-    }
-    if y != ' ' {
-        entries.push(FileChange {
-            path: path.to_string(),
-            change: change_type_from_flag(y),
-            staged: false,
-        });
-    }
-
-    entries
+/// Reads the tag names out of a `%D` decoration like `tag: v1.0, tag: latest`.
+fn parse_tag_decorations(decorations: &str) -> Vec<String> {
+    decorations
+        .split(", ")
+        .filter_map(|decoration| decoration.trim().strip_prefix("tag: "))
+        .map(str::to_string)
+        .collect()
 }
 
-fn is_staged(code: &str) -> bool {
-    if code == "??" {
-        return false;
-    }
-    code.chars().next().map_or(false, |c| c != ' ')
+/// Returns an opaque snapshot of HEAD and every ref tip. The commit list only
+/// needs rebuilding when this changes.
+pub fn fetch_ref_tips() -> Result<String, String> {
+    fetch_ref_tips_in(".")
 }
 
-fn change_type_from_code(code: &str) -> ChangeType {
-    if code == "??" {
-        return ChangeType::Untracked;
+pub fn fetch_ref_tips_in(path: impl AsRef<Path>) -> Result<String, String> {
+    let output = std::process::Command::new("git")
+        .arg("show-ref")
+        .arg("--head")
+        .current_dir(path.as_ref())
+        .output()
+        .map_err(|err| format!("Failed to run git show-ref: {err}"))?;
+
+    // show-ref exits with 1 when there is nothing to list, e.g. before the first commit.
+    if output.status.success() || (output.status.code() == Some(1) && output.stdout.is_empty()) {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format_git_error("git show-ref", &output))
     }
+}
 
-    let mut chars = code.chars();
-    let x = chars.next().unwrap_or(' ');
-    let y = chars.next().unwrap_or(' ');
+fn local_branch_tips(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let output = std::process::Command::new("git")
+        .arg("for-each-ref")
+        .arg("--format=%(refname:short)%09%(objectname)")
+        .arg("refs/heads")
+        .current_dir(path)
+        .output()
+        .map_err(|err| format!("Failed to list branches: {err}"))?;
 
-    let flag = if x != ' ' { x } else { y };
-    match flag {
-        'A' => ChangeType::Added,
-        'M' => ChangeType::Modified,
-        'D' => ChangeType::Deleted,
-        'R' => ChangeType::Renamed,
-        'C' => ChangeType::Copied,
-        'T' => ChangeType::TypeChange,
-        'U' => ChangeType::Unmerged,
-        '?' => ChangeType::Untracked,
-        _ => ChangeType::Unknown,
+    if !output.status.success() {
+        return Err(format_git_error("git for-each-ref", &output));
     }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(name, id)| (name.to_string(), id.to_string()))
+        .collect())
 }
 
-fn change_type_from_flag(flag: char) -> ChangeType {
-    match flag {
-        'A' => ChangeType::Added,
-        'M' => ChangeType::Modified,
-        'D' => ChangeType::Deleted,
-        'R' => ChangeType::Renamed,
-        'C' => ChangeType::Copied,
-        'T' => ChangeType::TypeChange,
-        'U' => ChangeType::Unmerged,
-        '?' => ChangeType::Untracked,
-        _ => ChangeType::Unknown,
+/// Walks a page of the commit graph, children before parents, pushing every
+/// branch from its tip down to its ancestors. `pending` carries the branches
+/// reaching parents that later pages list. Returns, per commit in `graph`, the
+/// indices into `branch_tips` of the branches that contain it.
+fn branch_membership(
+    graph: &[(&str, &[&str])],
+    branch_tips: &[(String, String)],
+    pending: &mut HashMap<String, BTreeSet<usize>>,
+) -> Vec<BTreeSet<usize>> {
+    let mut tips: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (branch, (_, tip)) in branch_tips.iter().enumerate() {
+        tips.entry(tip.as_str()).or_default().push(branch);
     }
-}
 
-fn repository_name(path: &Path) -> Option<String> {
-    repository_name_with_gix(path)
-        .or_else(|| repository_name_with_git(path))
-        .or_else(|| {
-            path.file_name()
-                .map(|name| name.to_string_lossy().to_string())
+    // `--date-order` lists every child before its parents, so by the time a
+    // commit comes up all the branches reaching it are already pending.
+    graph
+        .iter()
+        .map(|(id, parents)| {
+            let mut branches = pending.remove(*id).unwrap_or_default();
+            branches.extend(tips.get(id).into_iter().flatten().copied());
+            for parent in parents.iter().filter(|_| !branches.is_empty()) {
+                pending
+                    .entry(parent.to_string())
+                    .or_default()
+                    .extend(branches.iter().copied());
+            }
+            branches
         })
+        .collect()
 }
 
-fn repository_name_with_gix(path: &Path) -> Option<String> {
-    let repo = gix::discover(path).ok()?;
-    let work_dir = repo.workdir()?;
-    work_dir
-        .file_name()
-        .map(|name| name.to_string_lossy().to_string())
+pub fn fetch_commit_details(id: &str) -> Result<CommitDetails, String> {
+    fetch_commit_details_in(".", id)
 }
 
-fn repository_name_with_git(path: &Path) -> Option<String> {
+pub fn fetch_commit_details_in(path: impl AsRef<Path>, id: &str) -> Result<CommitDetails, String> {
+    let path = path.as_ref();
     let output = std::process::Command::new("git")
-        .arg("rev-parse")
-        .arg("--show-toplevel")
+        .arg("show")
+        .arg("-s")
+        .arg("--format=%H%x00%an%x00%ae%x00%ai%x00%cn%x00%ce%x00%ci%x00%P%x00%B")
+        .arg(id)
+        .arg("--")
         .current_dir(path)
         .output()
-        .ok()?;
+        .map_err(|err| format!("Failed to run git show: {err}"))?;
+
     if !output.status.success() {
-        return None;
+        return Err(format_git_error("git show", &output));
     }
-    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if root.is_empty() {
-        return None;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.splitn(9, '\0');
+    let mut next = || fields.next().unwrap_or("").to_string();
+    let full_id = next();
+    let author = next();
+    let author_email = next();
+    let author_date = next();
+    let committer = next();
+    let committer_email = next();
+    let commit_date = next();
+    let parents: Vec<String> = next().split_whitespace().map(str::to_string).collect();
+    let message = next().trim_end().to_string();
+
+    if full_id.trim().is_empty() {
+        return Err(format!("Commit {id} not found"));
     }
-    std::path::Path::new(&root)
-        .file_name()
-        .map(|name| name.to_string_lossy().to_string())
+
+    let files = fetch_commit_files_in(path, &full_id, parents.first().map(String::as_str))?;
+    Ok(CommitDetails {
+        id: full_id.trim().to_string(),
+        author,
+        author_email,
+        author_date,
+        committer,
+        committer_email,
+        commit_date,
+        parents,
+        message,
+        files,
+    })
 }
 
-fn find_main_branch_in(path: impl AsRef<Path>) -> Option<String> {
-    if branch_exists_in(path.as_ref(), "main") {
-        Some("main".to_string())
-    } else if branch_exists_in(path.as_ref(), "master") {
-        Some("master".to_string())
-    } else {
-        None
-    }
+pub fn fetch_commit_files(id: &str, parent: Option<&str>) -> Result<Vec<CommitFile>, String> {
+    fetch_commit_files_in(".", id, parent)
 }
 
-fn commits_in_branch(path: &Path, branch: &str) -> Result<HashSet<String>, String> {
-    let output = std::process::Command::new("git")
-        .arg("rev-list")
-        .arg(branch)
-        .current_dir(path)
+/// Lists the files `id` changed relative to `parent`, or relative to the empty
+/// tree for root commits.
+pub fn fetch_commit_files_in(
+    path: impl AsRef<Path>,
+    id: &str,
+    parent: Option<&str>,
+) -> Result<Vec<CommitFile>, String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.arg("diff-tree")
+        .arg("-r")
+        .arg("--raw")
+        .arg("--numstat")
+        .arg("--no-commit-id");
+    match parent {
+        Some(parent) => cmd.arg(parent).arg(id),
+        None => cmd.arg("--root").arg(id),
+    };
+
+    let output = cmd
+        .current_dir(path.as_ref())
         .output()
-        .map_err(|err| format!("Failed to list commits for {branch}: {err}"))?;
+        .map_err(|err| format!("Failed to run git diff-tree: {err}"))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!(
-            "git rev-list exited with status {}: {}",
-            output.status,
-            stderr.trim()
-        ));
+        return Err(format_git_error("git diff-tree", &output));
     }
 
-    let set = String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|line| line.trim().to_string())
-        .filter(|line| !line.is_empty())
-        .collect();
-    Ok(set)
-}
-
-fn branch_exists_in(path: &Path, branch: &str) -> bool {
-    std::process::Command::new("git")
-        .arg("show-ref")
-        .arg("--verify")
-        .arg(format!("refs/heads/{branch}"))
-        .current_dir(path)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
-        .map(|status| status.success())
-        .unwrap_or(false)
+    Ok(parse_commit_files(&String::from_utf8_lossy(&output.stdout)))
 }
 
-pub fn local_branch_exists(branch: &str) -> bool {
-    local_branch_exists_in(".", branch)
+pub fn fetch_commit_file_diff(
+    id: &str,
+    parent: Option<&str>,
+    file: &str,
+) -> Result<FileDiff, String> {
+    fetch_commit_file_diff_in(".", id, parent, file)
 }
 
-pub fn local_branch_exists_in(path: impl AsRef<Path>, branch: &str) -> bool {
-    branch_exists_in(path.as_ref(), branch)
-}
+/// Diffs a single file of commit `id` against `parent`, or against the empty
+/// tree for root commits.
+pub fn fetch_commit_file_diff_in(
+    path: impl AsRef<Path>,
+    id: &str,
+    parent: Option<&str>,
+    file: &str,
+) -> Result<FileDiff, String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.arg("diff-tree")
+        .arg("-p")
+        .arg("--no-color")
+        .arg("--no-ext-diff")
+        .arg("--no-commit-id");
+    match parent {
+        Some(parent) => cmd.arg(parent).arg(id),
+        None => cmd.arg("--root").arg(id),
+    };
 
-fn branches_containing_commit(path: &Path, full_id: &str) -> Result<Vec<String>, String> {
-    let output = std::process::Command::new("git")
-        .arg("branch")
-        .arg("--contains")
-        .arg(full_id)
-        .arg("--format=%(refname:short)")
-        .current_dir(path)
+    let output = cmd
+        .arg("--")
+        .arg(file)
+        .current_dir(path.as_ref())
         .output()
-        .map_err(|err| format!("Failed to find containing branches: {err}"))?;
+        .map_err(|err| format!("Failed to run git diff-tree: {err}"))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!(
-            "git branch --contains exited with status {}: {}",
-            output.status,
-            stderr.trim()
-        ));
+        return Err(format_git_error("git diff-tree", &output));
     }
 
-    let mut branches = String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|line| {
-            let trimmed = line.trim_start_matches("* ").trim();
-            trimmed.to_string()
-        })
-        .filter(|line| !line.is_empty())
-        .collect::<Vec<_>>();
-    branches.sort();
-    branches.dedup();
-    Ok(branches)
+    Ok(parse_diff(&String::from_utf8_lossy(&output.stdout)))
 }
 
-fn branch_ahead_behind(
-    path: &Path,
-    branch: &str,
-    default_branch: Option<&str>,
-) -> Option<(usize, usize)> {
-    let target = upstream_for_branch(path, branch).or_else(|| {
-        default_branch
-            .filter(|candidate| *candidate != branch)
-            .map(|name| name.to_string())
-    })?;
+fn parse_commit_files(text: &str) -> Vec<CommitFile> {
+    let mut files: Vec<CommitFile> = Vec::new();
+    for line in text.lines() {
+        if let Some(raw) = line.strip_prefix(':') {
+            let Some((meta, path)) = raw.split_once('\t') else {
+                continue;
+            };
+            let flag = meta
+                .split_whitespace()
+                .last()
+                .and_then(|status| status.chars().next())
+                .unwrap_or(' ');
+            files.push(CommitFile {
+                path: path.to_string(),
+                change: change_type_from_flag(flag),
+                additions: None,
+                deletions: None,
+            });
+            continue;
+        }
 
-    ahead_behind_for_pair(path, branch, &target)
+        let mut parts = line.splitn(3, '\t');
+        let (Some(additions), Some(deletions), Some(path)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        if let Some(file) = files.iter_mut().find(|file| file.path == path) {
+            file.additions = additions.parse().ok();
+            file.deletions = deletions.parse().ok();
+        }
+    }
+    files
 }
 
-fn ahead_behind_for_pair(path: &Path, branch: &str, target: &str) -> Option<(usize, usize)> {
-    let output = std::process::Command::new("git")
-        .arg("rev-list")
-        .arg("--left-right")
-        .arg("--count")
-        .arg(format!("{branch}...{target}"))
+/// Lists the commits an interactive rebase onto `base` would replay, oldest first,
+/// all set to `pick`. Merge commits are left out, as `git rebase -i` does.
+pub fn fetch_rebase_plan(base: &str) -> Result<Vec<RebaseStep>, String> {
+    fetch_rebase_plan_in(".", base)
+}
+
+pub fn fetch_rebase_plan_in(path: impl AsRef<Path>, base: &str) -> Result<Vec<RebaseStep>, String> {
+    let path = path.as_ref();
+    let ancestor = std::process::Command::new("git")
+        .args(["merge-base", "--is-ancestor", base, "HEAD"])
         .current_dir(path)
         .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
+        .map_err(|err| format!("Failed to run git merge-base: {err}"))?;
+    match ancestor.status.code() {
+        Some(0) => {}
+        Some(1) => return Err(format!("{base} is not an ancestor of HEAD")),
+        _ => return Err(format_git_error("git merge-base", &ancestor)),
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut parts = stdout.trim().split_whitespace();
-    let ahead = parts.next()?.parse().ok()?;
-    let behind = parts.next().unwrap_or("0").parse().ok()?;
-    Some((ahead, behind))
-}
-
-fn upstream_for_branch(path: &Path, branch: &str) -> Option<String> {
     let output = std::process::Command::new("git")
-        .arg("rev-parse")
-        .arg("--abbrev-ref")
-        .arg(format!("{branch}@{{upstream}}"))
+        .arg("log")
+        .arg("--reverse")
+        .arg("--topo-order")
+        .arg("--no-merges")
+        .arg("-z")
+        .arg("--format=%H%x09%h%x09%s%x09%B")
+        .arg(format!("{base}..HEAD"))
         .current_dir(path)
         .output()
-        .ok()?;
+        .map_err(|err| format!("Failed to run git log: {err}"))?;
     if !output.status.success() {
-        return None;
+        return Err(format_git_error("git log", &output));
     }
-    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+
+    let steps: Vec<RebaseStep> = String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter_map(|record| {
+            let mut parts = record.trim_start_matches('\n').splitn(4, '\t');
+            let id = parts.next()?.trim();
+            let short_id = parts.next()?.trim();
+            let summary = parts.next()?.trim();
+            Some(RebaseStep {
+                action: RebaseAction::Pick,
+                id: id.to_string(),
+                short_id: short_id.to_string(),
+                summary: summary.to_string(),
+                original_message: parts.next().unwrap_or("").trim_end().to_string(),
+                message: None,
+            })
+        })
+        .collect();
+    if steps.is_empty() {
+        return Err(format!("No commits to rebase after {base}"));
+    }
+    Ok(steps)
 }
 
-fn current_branch_name_in(path: &Path) -> Option<String> {
-    let output = std::process::Command::new("git")
-        .arg("rev-parse")
-        .arg("--abbrev-ref")
-        .arg("HEAD")
-        .current_dir(path)
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
+pub fn run_interactive_rebase(
+    base: &str,
+    steps: &[RebaseStep],
+) -> Result<OperationOutcome, String> {
+    run_interactive_rebase_in(".", base, steps)
+}
+
+/// Runs `git rebase -i base` with `steps` as the todo list. Rewording is done by
+/// an `exec` amend after the pick, so no editor is ever opened.
+pub fn run_interactive_rebase_in(
+    path: impl AsRef<Path>,
+    base: &str,
+    steps: &[RebaseStep],
+) -> Result<OperationOutcome, String> {
+    let path = path.as_ref();
+    let (_, git_dir) = repo_dirs_in(path)?;
+    let plan_dir = git_dir.join("easygit-rebase");
+    let _ = std::fs::remove_dir_all(&plan_dir);
+    std::fs::create_dir_all(&plan_dir)
+        .map_err(|err| format!("Failed to prepare rebase plan: {err}"))?;
+
+    let mut todo = String::new();
+    for (idx, step) in steps.iter().enumerate() {
+        match (step.action, &step.message) {
+            (RebaseAction::Reword, Some(message)) => {
+                let message_file = plan_dir.join(format!("message-{idx}"));
+                std::fs::write(&message_file, message)
+                    .map_err(|err| format!("Failed to prepare rebase plan: {err}"))?;
+                todo.push_str(&format!("pick {}\n", step.id));
+                todo.push_str(&format!(
+                    "exec git commit --amend --only --allow-empty --quiet -F {}\n",
+                    shell_quote(&message_file.to_string_lossy())
+                ));
+            }
+            (action, _) => todo.push_str(&format!("{} {}\n", action.as_str(), step.id)),
+        }
     }
-    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    let todo_file = plan_dir.join("todo");
+    std::fs::write(&todo_file, todo)
+        .map_err(|err| format!("Failed to prepare rebase plan: {err}"))?;
+
+    let mut command = std::process::Command::new("git");
+    command.args(["rebase", "--interactive", base]).env(
+        "GIT_SEQUENCE_EDITOR",
+        format!("cp {}", shell_quote(&todo_file.to_string_lossy())),
+    );
+    run_operation(path, command, "git rebase")
 }
 
-fn git_pull_ff_only(path: &Path) -> Result<(), String> {
-    run_git_command(path, ["pull", "--ff-only"], "git pull")
+pub fn rebase_onto(upstream: &str) -> Result<OperationOutcome, String> {
+    rebase_onto_in(".", upstream)
 }
 
-fn git_push(path: &Path, remote: &str, branch: &str, has_upstream: bool) -> Result<(), String> {
-    let mut cmd = std::process::Command::new("git");
-    cmd.current_dir(path);
-    if has_upstream {
-        cmd.arg("push");
-    } else {
-        cmd.arg("push")
-            .arg("--set-upstream")
-            .arg(remote)
-            .arg(branch);
+/// Replays the commits of the current branch that `upstream` lacks on top of it.
+pub fn rebase_onto_in(path: impl AsRef<Path>, upstream: &str) -> Result<OperationOutcome, String> {
+    let mut command = std::process::Command::new("git");
+    command.args(["rebase", upstream]);
+    run_operation(path.as_ref(), command, "git rebase")
+}
+
+pub fn cherry_pick_commits(
+    ids: &[String],
+    record_origin: bool,
+) -> Result<OperationOutcome, String> {
+    cherry_pick_commits_in(".", ids, record_origin)
+}
+
+/// Applies `ids` (oldest first) on top of HEAD. With `record_origin` each new
+/// message gets a "(cherry picked from commit …)" line, as `git cherry-pick -x`.
+pub fn cherry_pick_commits_in(
+    path: impl AsRef<Path>,
+    ids: &[String],
+    record_origin: bool,
+) -> Result<OperationOutcome, String> {
+    let mut command = std::process::Command::new("git");
+    command.arg("cherry-pick");
+    if record_origin {
+        command.arg("-x");
     }
+    command.args(ids);
+    run_operation(path.as_ref(), command, "git cherry-pick")
+}
 
-    let output = cmd
-        .output()
-        .map_err(|err| format!("Failed to run git push: {err}"))?;
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(format_git_error("git push", &output))
+pub fn revert_commits(ids: &[String]) -> Result<OperationOutcome, String> {
+    revert_commits_in(".", ids)
+}
+
+/// Creates a revert commit for each of `ids`, in the order given.
+pub fn revert_commits_in(
+    path: impl AsRef<Path>,
+    ids: &[String],
+) -> Result<OperationOutcome, String> {
+    let mut command = std::process::Command::new("git");
+    command.args(["revert", "--no-edit"]).args(ids);
+    run_operation(path.as_ref(), command, "git revert")
+}
+
+pub fn continue_operation(kind: OperationKind) -> Result<OperationOutcome, String> {
+    continue_operation_in(".", kind)
+}
+
+/// Concludes the current step of `kind` once its conflicts are staged, moving
+/// on to the next one.
+pub fn continue_operation_in(
+    path: impl AsRef<Path>,
+    kind: OperationKind,
+) -> Result<OperationOutcome, String> {
+    if kind == OperationKind::Bisect {
+        return Err("A bisect has no step to continue".to_string());
     }
+    let mut command = std::process::Command::new("git");
+    command.args([kind.as_str(), "--continue"]);
+    run_operation(
+        path.as_ref(),
+        command,
+        &format!("git {} --continue", kind.as_str()),
+    )
 }
 
-fn git_fetch(path: &Path, remote: &str) -> Result<(), String> {
-    run_git_command(path, ["fetch", remote], "git fetch")
+pub fn skip_operation(kind: OperationKind) -> Result<OperationOutcome, String> {
+    skip_operation_in(".", kind)
 }
 
-fn run_git_command<const N: usize>(
+/// Drops the commit the operation stopped on and carries on with the rest.
+pub fn skip_operation_in(
+    path: impl AsRef<Path>,
+    kind: OperationKind,
+) -> Result<OperationOutcome, String> {
+    if matches!(kind, OperationKind::Merge | OperationKind::Bisect) {
+        return Err(format!("A {} has no step to skip", kind.as_str()));
+    }
+    let mut command = std::process::Command::new("git");
+    command.args([kind.as_str(), "--skip"]);
+    run_operation(
+        path.as_ref(),
+        command,
+        &format!("git {} --skip", kind.as_str()),
+    )
+}
+
+pub fn abort_operation(kind: OperationKind) -> Result<(), String> {
+    abort_operation_in(".", kind)
+}
+
+/// Abandons the operation and puts HEAD back where it started.
+pub fn abort_operation_in(path: impl AsRef<Path>, kind: OperationKind) -> Result<(), String> {
+    let path = path.as_ref();
+    if kind == OperationKind::Bisect {
+        return run_git_command(path, ["bisect", "reset"], "git bisect reset");
+    }
+    let label = format!("git {} --abort", kind.as_str());
+    run_git_command(path, [kind.as_str(), "--abort"], &label)
+}
+
+/// Runs a rebase, cherry-pick, revert or merge command with the message editor
+/// disabled and works out whether it finished or stopped halfway.
+fn run_operation(
     path: &Path,
-    args: [&str; N],
+    mut command: std::process::Command,
     label: &str,
-) -> Result<(), String> {
-    let output = std::process::Command::new("git")
-        .args(args)
+) -> Result<OperationOutcome, String> {
+    let output = command
+        .env("GIT_EDITOR", "true")
         .current_dir(path)
         .output()
         .map_err(|err| format!("Failed to run {label}: {err}"))?;
+
+    let operation = repo_dirs_in(path)
+        .ok()
+        .and_then(|(_, git_dir)| detect_operation(&git_dir));
+    if operation.is_some_and(|operation| operation.kind != OperationKind::Bisect) {
+        return Ok(if has_unmerged_changes(path)? {
+            OperationOutcome::Conflicts
+        } else {
+            OperationOutcome::Stopped
+        });
+    }
     if output.status.success() {
-        Ok(())
+        Ok(OperationOutcome::Finished)
     } else {
         Err(format_git_error(label, &output))
     }
 }
 
-fn format_git_error(label: &str, output: &std::process::Output) -> String {
-    let message = String::from_utf8_lossy(&output.stderr);
-    let trimmed = message.trim();
-    if trimmed.is_empty() {
-        format!("{label} exited with status: {}", output.status)
-    } else {
-        clean_git_message(trimmed)
+/// Reads which operation is in progress from the marker files git leaves in
+/// `git_dir`. A bisect is reported last since the others can run inside one.
+fn detect_operation(git_dir: &Path) -> Option<RepoOperation> {
+    let operation = |kind, progress| Some(RepoOperation { kind, progress });
+    let merge_dir = git_dir.join("rebase-merge");
+    if merge_dir.is_dir() {
+        return operation(
+            OperationKind::Rebase,
+            read_progress(&merge_dir, "msgnum", "end"),
+        );
     }
+    let apply_dir = git_dir.join("rebase-apply");
+    if apply_dir.is_dir() {
+        return operation(
+            OperationKind::Rebase,
+            read_progress(&apply_dir, "next", "last"),
+        );
+    }
+    if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+        return operation(OperationKind::CherryPick, None);
+    }
+    if git_dir.join("REVERT_HEAD").is_file() {
+        return operation(OperationKind::Revert, None);
+    }
+    if git_dir.join("MERGE_HEAD").is_file() {
+        return operation(OperationKind::Merge, None);
+    }
+    if git_dir.join("BISECT_START").is_file() {
+        return operation(OperationKind::Bisect, None);
+    }
+    None
 }
 
-fn remote_for_branch(path: &Path, branch: &str) -> Option<String> {
-    git_config_value(path, &format!("branch.{branch}.remote"))
-}
-
-fn first_remote(path: &Path) -> Option<String> {
-    let output = std::process::Command::new("git")
-        .arg("remote")
-        .current_dir(path)
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
-    }
-    String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|line| line.trim().to_string())
-        .find(|line| !line.is_empty())
+fn read_progress(dir: &Path, step_file: &str, total_file: &str) -> Option<(usize, usize)> {
+    let read = |name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .ok()?
+            .trim()
+            .parse::<usize>()
+            .ok()
+    };
+    Some((read(step_file)?, read(total_file)?))
 }
 
-fn git_config_value(path: &Path, key: &str) -> Option<String> {
-    let output = std::process::Command::new("git")
-        .arg("config")
-        .arg("--get")
-        .arg(key)
-        .current_dir(path)
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
-    }
-    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+fn has_unmerged_changes(path: &Path) -> Result<bool, String> {
+    Ok(try_fetch_repo_status(path)?
+        .changes
+        .iter()
+        .any(|change| change.change == ChangeType::Unmerged))
 }
 
-fn set_branch_upstream(path: &Path, branch: &str, remote_ref: &str) -> Result<(), String> {
-    run_git_command(
-        path,
-        ["branch", "--set-upstream-to", remote_ref, branch],
-        "git branch --set-upstream-to",
-    )
+/// Which side a conflict hunk is resolved to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+    Ours,
+    Theirs,
+    /// Ours followed by theirs.
+    Both,
 }
 
-fn branch_remote_target(path: &Path, branch: &str, remote: &str) -> String {
-    if let Some(remote_branch) = git_config_value(path, &format!("branch.{branch}.merge")) {
-        let trimmed = remote_branch.trim_start_matches("refs/heads/");
-        format!("{remote}/{trimmed}")
-    } else {
-        format!("{remote}/{branch}")
-    }
+/// One conflicting region; every line keeps its line ending.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictHunk {
+    pub ours: Vec<String>,
+    pub base: Vec<String>,
+    pub theirs: Vec<String>,
 }
 
-fn upstream_remote(upstream: &str) -> Option<&str> {
-    upstream.split_once('/').map(|(remote, _)| remote)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictSegment {
+    Clean(Vec<String>),
+    Conflict(ConflictHunk),
 }
 
-fn clean_git_message(message: &str) -> String {
-    let first_line = message.lines().next().unwrap_or(message).trim();
-    first_line
-        .trim_start_matches("fatal: ")
-        .trim_start_matches("error: ")
-        .trim()
-        .to_string()
+/// A conflicted file rebuilt from the ours, base and theirs index stages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictFile {
+    pub path: String,
+    pub segments: Vec<ConflictSegment>,
+    /// The side that deleted the file, in a modify/delete conflict.
+    pub deleted_by: Option<ConflictSide>,
 }
 
-fn try_fetch_branch_info(path: impl AsRef<Path>) -> Result<BranchInfo, String> {
-    let path = path.as_ref();
-    let repo = gix::discover(path).map_err(|err| format!("Not a git repository: {err}"))?;
+impl ConflictFile {
+    pub fn hunks(&self) -> impl Iterator<Item = &ConflictHunk> {
+        self.segments.iter().filter_map(|segment| match segment {
+            ConflictSegment::Conflict(hunk) => Some(hunk),
+            ConflictSegment::Clean(_) => None,
+        })
+    }
 
-    let current = repo
-        .head()
-        .map_err(|err| format!("Failed to read HEAD: {err}"))?
-        .referent_name()
-        .map(|name| name.shorten().to_string());
+    pub fn hunk_count(&self) -> usize {
+        self.hunks().count()
+    }
 
-    let mut locals = std::collections::HashMap::new();
-    for reference in repo
-        .references()
-        .map_err(|err| format!("Failed to list references: {err}"))?
-        .prefixed("refs/heads/")
-        .map_err(|err| format!("Failed to filter branches: {err}"))?
-    {
-        if let Ok(r) = reference {
-            let name = r.name().shorten().to_string();
-            locals.entry(name.clone()).or_insert(BranchSummary {
-                name,
-                ahead: None,
-                behind: None,
-                has_local: true,
-                has_remote: false,
-                remote_ref: None,
-            });
+    /// Writes the file back out with `choices[i]` applied to the i-th hunk.
+    /// Hunks without a choice keep their conflict markers.
+    pub fn resolve(&self, choices: &[Option<ConflictSide>]) -> String {
+        let mut text = String::new();
+        let mut hunk_index = 0;
+        for segment in &self.segments {
+            let hunk = match segment {
+                ConflictSegment::Clean(lines) => {
+                    text.extend(lines.iter().map(String::as_str));
+                    continue;
+                }
+                ConflictSegment::Conflict(hunk) => hunk,
+            };
+            match choices.get(hunk_index).copied().flatten() {
+                Some(ConflictSide::Ours) => text.extend(hunk.ours.iter().map(String::as_str)),
+                Some(ConflictSide::Theirs) => text.extend(hunk.theirs.iter().map(String::as_str)),
+                Some(ConflictSide::Both) => {
+                    text.extend(hunk.ours.iter().chain(&hunk.theirs).map(String::as_str));
+                }
+                None => {
+                    text.push_str("<<<<<<< ours\n");
+                    text.extend(hunk.ours.iter().map(String::as_str));
+                    text.push_str("||||||| base\n");
+                    text.extend(hunk.base.iter().map(String::as_str));
+                    text.push_str("=======\n");
+                    text.extend(hunk.theirs.iter().map(String::as_str));
+                    text.push_str(">>>>>>> theirs\n");
+                }
+            }
+            hunk_index += 1;
         }
+        text
     }
 
-    let mut remotes = std::collections::HashMap::new();
-    for reference in repo
-        .references()
-        .map_err(|err| format!("Failed to list references: {err}"))?
-        .prefixed("refs/remotes/")
-        .map_err(|err| format!("Failed to filter remote branches: {err}"))?
+    /// Whether `choices` take the deleting side for every hunk, so the file is
+    /// removed rather than written out empty.
+    pub fn deletes(&self, choices: &[Option<ConflictSide>]) -> bool {
+        self.deleted_by.is_some_and(|side| {
+            choices.len() == self.hunk_count() && choices.iter().all(|choice| *choice == Some(side))
+        })
+    }
+}
+
+pub fn fetch_conflict(file: &str) -> Result<ConflictFile, String> {
+    fetch_conflict_in(".", file)
+}
+
+/// Re-merges the three index stages of `file` (a path from the repository root)
+/// so the hunks come with their base, whatever the merge left in the work tree.
+pub fn fetch_conflict_in(path: impl AsRef<Path>, file: &str) -> Result<ConflictFile, String> {
+    let (work_dir, git_dir) = repo_dirs_in(path)?;
+    let (stage_files, deleted_by) = write_conflict_stages(&work_dir, &git_dir, file)?;
+    let merged = merge_conflict_stages(&work_dir, &stage_files, true)?;
+    Ok(ConflictFile {
+        path: file.to_string(),
+        segments: parse_conflict_segments(&merged),
+        deleted_by,
+    })
+}
+
+/// Writes the ours, base and theirs index stages of `file` to scratch files,
+/// returning them in that order along with the side that has no stage.
+fn write_conflict_stages(
+    work_dir: &Path,
+    git_dir: &Path,
+    file: &str,
+) -> Result<(Vec<PathBuf>, Option<ConflictSide>), String> {
+    let scratch = git_dir.join("easygit-conflict");
+    std::fs::create_dir_all(&scratch)
+        .map_err(|err| format!("Failed to prepare conflict view: {err}"))?;
+
+    let mut stage_files = Vec::new();
+    let mut deleted_by = None;
+    for (stage, name, side) in [
+        (2, "ours", Some(ConflictSide::Ours)),
+        (1, "base", None),
+        (3, "theirs", Some(ConflictSide::Theirs)),
+    ] {
+        // A side that deleted or never had the file has no stage; merge against empty.
+        let contents = std::process::Command::new("git")
+            .arg("show")
+            .arg(format!(":{stage}:{file}"))
+            .current_dir(work_dir)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| output.stdout);
+        if contents.is_none() && side.is_some() {
+            deleted_by = side;
+        }
+        let stage_file = scratch.join(name);
+        std::fs::write(&stage_file, contents.unwrap_or_default())
+            .map_err(|err| format!("Failed to prepare conflict view: {err}"))?;
+        stage_files.push(stage_file);
+    }
+    Ok((stage_files, deleted_by))
+}
+
+fn merge_conflict_stages(
+    work_dir: &Path,
+    stage_files: &[PathBuf],
+    diff3: bool,
+) -> Result<String, String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.args(["merge-file", "-p"]);
+    if diff3 {
+        cmd.arg("--diff3");
+    }
+    let output = cmd
+        .args(["-L", "ours", "-L", "base", "-L", "theirs"])
+        .args(stage_files)
+        .current_dir(work_dir)
+        .output()
+        .map_err(|err| format!("Failed to run git merge-file: {err}"))?;
+    // merge-file exits with the number of conflicts; only negative codes are errors.
+    if output
+        .status
+        .code()
+        .is_none_or(|code| !(0..=127).contains(&code))
     {
-        if let Ok(r) = reference {
-            let full = r.name().shorten().to_string();
-            if full.ends_with("/HEAD") {
-                continue;
+        return Err(format_git_error("git merge-file", &output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Refuses to replace `file` once it no longer holds what the merge left in
+/// it, so edits made outside the conflict view are not thrown away. Conflict
+/// markers are left out of the comparison since git labels them differently.
+fn ensure_conflict_untouched(work_dir: &Path, git_dir: &Path, file: &str) -> Result<(), String> {
+    let (stage_files, _) = write_conflict_stages(work_dir, git_dir, file)?;
+    let merged = merge_conflict_stages(work_dir, &stage_files, false)?;
+    let current = std::fs::read(work_dir.join(file))
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default();
+    if without_conflict_markers(&current) == without_conflict_markers(&merged) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{file} was edited since the merge; finish resolving it in your editor and stage it"
+        ))
+    }
+}
+
+/// The lines of `text` without conflict markers or the base sections of
+/// diff3-style conflicts.
+fn without_conflict_markers(text: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut in_base = false;
+    for line in text.split_inclusive('\n') {
+        if is_conflict_marker(line, "|||||||") {
+            in_base = true;
+        } else if is_conflict_marker(line, "=======") {
+            in_base = false;
+        } else if !in_base
+            && !is_conflict_marker(line, "<<<<<<<")
+            && !is_conflict_marker(line, ">>>>>>>")
+        {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+fn is_conflict_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\n', '\r']))
+}
+
+fn parse_conflict_segments(text: &str) -> Vec<ConflictSegment> {
+    #[derive(PartialEq)]
+    enum Part {
+        Clean,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut segments = Vec::new();
+    let mut clean = Vec::new();
+    let mut hunk = ConflictHunk::default();
+    let mut part = Part::Clean;
+    for line in text.split_inclusive('\n') {
+        match part {
+            Part::Clean if is_conflict_marker(line, "<<<<<<<") => {
+                if !clean.is_empty() {
+                    segments.push(ConflictSegment::Clean(std::mem::take(&mut clean)));
+                }
+                part = Part::Ours;
             }
-            let Some((remote, short)) = full.split_once('/') else {
-                continue;
-            };
-            let entry = remotes.entry(short.to_string()).or_insert(BranchSummary {
-                name: short.to_string(),
-                ahead: None,
-                behind: None,
-                has_local: false,
-                has_remote: true,
-                remote_ref: Some(full.clone()),
-            });
-            entry.has_remote = true;
-            if entry.remote_ref.is_none() || remote == "origin" {
-                entry.remote_ref = Some(full.clone());
+            Part::Clean => clean.push(line.to_string()),
+            Part::Ours if is_conflict_marker(line, "|||||||") => part = Part::Base,
+            Part::Ours | Part::Base if is_conflict_marker(line, "=======") => part = Part::Theirs,
+            Part::Ours => hunk.ours.push(line.to_string()),
+            Part::Base => hunk.base.push(line.to_string()),
+            Part::Theirs if is_conflict_marker(line, ">>>>>>>") => {
+                segments.push(ConflictSegment::Conflict(std::mem::take(&mut hunk)));
+                part = Part::Clean;
             }
+            Part::Theirs => hunk.theirs.push(line.to_string()),
+        }
+    }
+    if !clean.is_empty() {
+        segments.push(ConflictSegment::Clean(clean));
+    }
+    segments
+}
+
+pub fn resolve_conflict(file: &str, contents: &str) -> Result<(), String> {
+    resolve_conflict_in(".", file, contents)
+}
+
+/// Writes the resolved `contents` of `file` and stages it, marking it resolved.
+pub fn resolve_conflict_in(
+    path: impl AsRef<Path>,
+    file: &str,
+    contents: &str,
+) -> Result<(), String> {
+    let (work_dir, git_dir) = repo_dirs_in(path)?;
+    ensure_conflict_untouched(&work_dir, &git_dir, file)?;
+    std::fs::write(work_dir.join(file), contents)
+        .map_err(|err| format!("Failed to write {file}: {err}"))?;
+    run_git_command(&work_dir, ["add", "--", file], "git add")
+}
+
+pub fn remove_conflicted(file: &str) -> Result<(), String> {
+    remove_conflicted_in(".", file)
+}
+
+/// Resolves a modify/delete conflict on `file` to the deleting side.
+pub fn remove_conflicted_in(path: impl AsRef<Path>, file: &str) -> Result<(), String> {
+    let (work_dir, git_dir) = repo_dirs_in(path)?;
+    if work_dir.join(file).exists() {
+        ensure_conflict_untouched(&work_dir, &git_dir, file)?;
+    }
+    run_git_command(&work_dir, ["rm", "-q", "--", file], "git rm")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMode {
+    FastForwardOnly,
+    NoFastForward,
+    /// Stages the combined changes without committing or recording the merge.
+    Squash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Merged,
+    UpToDate,
+    /// A squash merge staged its changes and now needs a commit.
+    Squashed,
+    Conflicts,
+}
+
+pub fn merge_branch(branch: &str, mode: MergeMode) -> Result<MergeOutcome, String> {
+    merge_branch_in(".", branch, mode)
+}
+
+/// Merges `branch` (a local branch or remote-tracking ref) into the current branch.
+pub fn merge_branch_in(
+    path: impl AsRef<Path>,
+    branch: &str,
+    mode: MergeMode,
+) -> Result<MergeOutcome, String> {
+    let path = path.as_ref();
+    let mode_arg = match mode {
+        MergeMode::FastForwardOnly => "--ff-only",
+        MergeMode::NoFastForward => "--no-ff",
+        MergeMode::Squash => "--squash",
+    };
+    // Decided up front rather than from git's output, which is translated.
+    if is_ancestor(path, branch, "HEAD")? {
+        return Ok(MergeOutcome::UpToDate);
+    }
+    let output = std::process::Command::new("git")
+        .args(["merge", mode_arg, "--no-edit", branch])
+        .current_dir(path)
+        .output()
+        .map_err(|err| format!("Failed to run git merge: {err}"))?;
+
+    if !output.status.success() {
+        // git merge exits with 1 when the merge itself stopped on conflicts,
+        // and only then leaves MERGE_HEAD behind; a squash never records one.
+        let (_, git_dir) = repo_dirs_in(path)?;
+        let stopped = mode == MergeMode::Squash || git_dir.join("MERGE_HEAD").is_file();
+        if output.status.code() == Some(1) && stopped {
+            return Ok(MergeOutcome::Conflicts);
+        }
+        return Err(format_git_error("git merge", &output));
+    }
+    Ok(match mode {
+        MergeMode::Squash => MergeOutcome::Squashed,
+        _ => MergeOutcome::Merged,
+    })
+}
+
+fn is_ancestor(path: &Path, ancestor: &str, descendant: &str) -> Result<bool, String> {
+    let output = std::process::Command::new("git")
+        .args(["merge-base", "--is-ancestor", ancestor, descendant])
+        .current_dir(path)
+        .output()
+        .map_err(|err| format!("Failed to run git merge-base: {err}"))?;
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(format_git_error("git merge-base", &output)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMode {
+    /// Moves the branch only; the undone commits' changes stay staged.
+    Soft,
+    /// Moves the branch and the index; the changes stay in the work tree.
+    Mixed,
+    /// Moves the branch and discards every uncommitted change to tracked files.
+    Hard,
+}
+
+impl ResetMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResetMode::Soft => "soft",
+            ResetMode::Mixed => "mixed",
+            ResetMode::Hard => "hard",
         }
     }
+}
+
+/// What moving HEAD to a commit leaves behind.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ResetPreview {
+    /// Commits on HEAD that the target lacks, newest first.
+    pub commits: Vec<Commit>,
+    /// Uncommitted changes to tracked files, which a hard reset discards.
+    pub changes: Vec<FileChange>,
+}
+
+pub fn fetch_reset_preview(target: &str) -> Result<ResetPreview, String> {
+    fetch_reset_preview_in(".", target)
+}
+
+pub fn fetch_reset_preview_in(
+    path: impl AsRef<Path>,
+    target: &str,
+) -> Result<ResetPreview, String> {
+    let path = path.as_ref();
+    let output = std::process::Command::new("git")
+        .args(["log", "--format=%h%x09%s", &format!("{target}..HEAD")])
+        .current_dir(path)
+        .output()
+        .map_err(|err| format!("Failed to run git log: {err}"))?;
+    if !output.status.success() {
+        return Err(format_git_error("git log", &output));
+    }
+    let commits = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (id, summary) = line.split_once('\t')?;
+            Some(Commit {
+                id: id.to_string(),
+                summary: summary.to_string(),
+                branches: Vec::new(),
+                parents: Vec::new(),
+                tags: Vec::new(),
+            })
+        })
+        .collect();
+    let changes = try_fetch_repo_status(path)?
+        .changes
+        .into_iter()
+        .filter(|change| change.change != ChangeType::Untracked)
+        .collect();
+    Ok(ResetPreview { commits, changes })
+}
+
+pub fn reset_to(target: &str, mode: ResetMode) -> Result<(), String> {
+    reset_to_in(".", target, mode)
+}
+
+/// Points the current branch (or a detached HEAD) at `target`.
+pub fn reset_to_in(path: impl AsRef<Path>, target: &str, mode: ResetMode) -> Result<(), String> {
+    let flag = format!("--{}", mode.as_str());
+    run_git_command(path.as_ref(), ["reset", &flag, target], "git reset")
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+pub fn fetch_repo_status() -> RepoStatus {
+    fetch_repo_status_in(".")
+}
+
+pub fn fetch_repo_status_in(path: impl AsRef<Path>) -> RepoStatus {
+    let path = path.as_ref();
+    match try_fetch_repo_status(path) {
+        Ok(status) => status,
+        Err(err) => RepoStatus {
+            changes: Vec::new(),
+            error: Some(err),
+            repo_name: repository_name(path),
+            operation: None,
+        },
+    }
+}
+
+fn try_fetch_repo_status(path: &Path) -> Result<RepoStatus, String> {
+    // Without optional locks, status does not refresh the index, which would
+    // otherwise wake the file watcher and trigger another refresh.
+    let output = std::process::Command::new("git")
+        .arg("--no-optional-locks")
+        .arg("status")
+        .arg("--porcelain=v1")
+        .arg("--untracked-files=all")
+        .current_dir(path)
+        .output()
+        .map_err(|err| format!("Failed to run git status: {err}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let trimmed = stderr.trim();
+        return Err(if trimmed.is_empty() {
+            format!("git status exited with status: {}", output.status)
+        } else {
+            trimmed.to_string()
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut changes: Vec<FileChange> = stdout.lines().flat_map(parse_status_line).collect();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(RepoStatus {
+        changes,
+        error: None,
+        repo_name: repository_name(path),
+        operation: repo_dirs_in(path)
+            .ok()
+            .and_then(|(_, git_dir)| detect_operation(&git_dir)),
+    })
+}
+
+fn parse_status_line(line: &str) -> Vec<FileChange> {
+    if line.len() < 3 {
+        return Vec::new();
+    }
+    let code = &line[0..2];
+    let mut path = line[3..].trim();
+    if path.is_empty() {
+        return Vec::new();
+    }
+    if let Some((_, new)) = path.split_once(" -> ") {
+        path = new.trim();
+    }
+    let mut entries = Vec::new();
+    if matches!(code, "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU") {
+        entries.push(FileChange {
+            path: path.to_string(),
+            change: ChangeType::Unmerged,
+            staged: false,
+        });
+        return entries;
+    }
+    if code == "??" {
+        entries.push(FileChange {
+            path: path.to_string(),
+            change: ChangeType::Untracked,
+            staged: false,
+        });
+        return entries;
+    }
+
+    let mut chars = code.chars();
+    let x = chars.next().unwrap_or(' ');
+    let y = chars.next().unwrap_or(' ');
+
+    if x != ' ' {
+        entries.push(FileChange {
+            path: path.to_string(),
+            change: change_type_from_flag(x),
+            staged: true,
+        });
+    }
+    if y != ' ' {
+        entries.push(FileChange {
+            path: path.to_string(),
+            change: change_type_from_flag(y),
+            staged: false,
+        });
+    }
+
+    entries
+}
+
+fn change_type_from_flag(flag: char) -> ChangeType {
+    match flag {
+        'A' => ChangeType::Added,
+        'M' => ChangeType::Modified,
+        'D' => ChangeType::Deleted,
+        'R' => ChangeType::Renamed,
+        'C' => ChangeType::Copied,
+        'T' => ChangeType::TypeChange,
+        'U' => ChangeType::Unmerged,
+        '?' => ChangeType::Untracked,
+        _ => ChangeType::Unknown,
+    }
+}
+
+/// Returns the working tree root and the absolute git directory of the repository
+/// containing `path`.
+pub fn repo_dirs_in(path: impl AsRef<Path>) -> Result<(PathBuf, PathBuf), String> {
+    let output = std::process::Command::new("git")
+        .arg("rev-parse")
+        .arg("--show-toplevel")
+        .arg("--absolute-git-dir")
+        .current_dir(path.as_ref())
+        .output()
+        .map_err(|err| format!("Failed to run git rev-parse: {err}"))?;
+
+    if !output.status.success() {
+        return Err(format_git_error("git rev-parse", &output));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    match (lines.next(), lines.next()) {
+        (Some(work_dir), Some(git_dir)) => Ok((PathBuf::from(work_dir), PathBuf::from(git_dir))),
+        _ => Err("Repository has no working tree".to_string()),
+    }
+}
+
+fn repository_name(path: &Path) -> Option<String> {
+    repository_name_with_gix(path)
+        .or_else(|| repository_name_with_git(path))
+        .or_else(|| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+}
+
+fn repository_name_with_gix(path: &Path) -> Option<String> {
+    let repo = gix::discover(path).ok()?;
+    let work_dir = repo.workdir()?;
+    work_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+fn repository_name_with_git(path: &Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("rev-parse")
+        .arg("--show-toplevel")
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if root.is_empty() {
+        return None;
+    }
+    std::path::Path::new(&root)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// The git config key naming the integration branch, e.g. `develop` or `origin/trunk`.
+const INTEGRATION_BRANCH_KEY: &str = "easygit.integrationBranch";
+
+/// The configured integration branch, falling back to `main` or `master`.
+fn integration_branch_in(path: &Path) -> Option<String> {
+    git_config_value(path, INTEGRATION_BRANCH_KEY)
+        .filter(|branch| !branch.is_empty())
+        .or_else(|| find_main_branch_in(path))
+}
+
+pub fn set_integration_branch(branch: Option<&str>) -> Result<(), String> {
+    set_integration_branch_in(".", branch)
+}
+
+/// Stores the integration branch in the repository's config, or forgets it
+/// to go back to guessing `main` or `master`.
+pub fn set_integration_branch_in(
+    path: impl AsRef<Path>,
+    branch: Option<&str>,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    match branch {
+        Some(branch) => run_git_command(
+            path,
+            ["config", INTEGRATION_BRANCH_KEY, branch],
+            "git config",
+        ),
+        None if git_config_value(path, INTEGRATION_BRANCH_KEY).is_none() => Ok(()),
+        None => run_git_command(
+            path,
+            ["config", "--unset", INTEGRATION_BRANCH_KEY],
+            "git config --unset",
+        ),
+    }
+}
+
+fn find_main_branch_in(path: impl AsRef<Path>) -> Option<String> {
+    if branch_exists_in(path.as_ref(), "main") {
+        Some("main".to_string())
+    } else if branch_exists_in(path.as_ref(), "master") {
+        Some("master".to_string())
+    } else {
+        None
+    }
+}
+
+fn branch_exists_in(path: &Path, branch: &str) -> bool {
+    std::process::Command::new("git")
+        .arg("show-ref")
+        .arg("--verify")
+        .arg(format!("refs/heads/{branch}"))
+        .current_dir(path)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+pub fn local_branch_exists(branch: &str) -> bool {
+    local_branch_exists_in(".", branch)
+}
+
+pub fn local_branch_exists_in(path: impl AsRef<Path>, branch: &str) -> bool {
+    branch_exists_in(path.as_ref(), branch)
+}
+
+/// Counts the commits `branch` has that `target` lacks, and the other way round.
+pub fn ahead_behind(branch: &str, target: &str) -> Option<(usize, usize)> {
+    ahead_behind_for_pair(Path::new("."), branch, target)
+}
+
+fn ahead_behind_for_pair(path: &Path, branch: &str, target: &str) -> Option<(usize, usize)> {
+    let output = std::process::Command::new("git")
+        .arg("rev-list")
+        .arg("--left-right")
+        .arg("--count")
+        .arg(format!("{branch}...{target}"))
+        .current_dir(path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut parts = stdout.split_whitespace();
+    let ahead = parts.next()?.parse().ok()?;
+    let behind = parts.next().unwrap_or("0").parse().ok()?;
+    Some((ahead, behind))
+}
+
+fn upstream_for_branch(path: &Path, branch: &str) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("rev-parse")
+        .arg("--abbrev-ref")
+        .arg(format!("{branch}@{{upstream}}"))
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn current_branch_name_in(path: &Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("rev-parse")
+        .arg("--abbrev-ref")
+        .arg("HEAD")
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn git_pull_ff_only(path: &Path, cancel: &CancelToken) -> Result<(), String> {
+    run_git_cancellable(path, &["pull", "--ff-only"], "git pull", cancel)
+}
+
+fn git_push(
+    path: &Path,
+    remote: &str,
+    branch: &str,
+    has_upstream: bool,
+    cancel: &CancelToken,
+) -> Result<(), String> {
+    if has_upstream {
+        run_git_cancellable(path, &["push"], "git push", cancel)
+    } else {
+        run_git_cancellable(
+            path,
+            &["push", "--set-upstream", remote, branch],
+            "git push",
+            cancel,
+        )
+    }
+}
+
+fn git_fetch(path: &Path, remote: &str, cancel: &CancelToken) -> Result<(), String> {
+    run_git_cancellable(path, &["fetch", remote], "git fetch", cancel)
+}
+
+fn run_git_command<const N: usize>(
+    path: &Path,
+    args: [&str; N],
+    label: &str,
+) -> Result<(), String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .map_err(|err| format!("Failed to run {label}: {err}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format_git_error(label, &output))
+    }
+}
+
+fn run_git_with_input(path: &Path, args: &[&str], input: &str, label: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut child = std::process::Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Failed to run {label}: {err}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|err| format!("Failed to write to {label}: {err}"))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("Failed to run {label}: {err}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format_git_error(label, &output))
+    }
+}
+
+fn run_git_cancellable(
+    path: &Path,
+    args: &[&str],
+    label: &str,
+    cancel: &CancelToken,
+) -> Result<(), String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.args(args).current_dir(path);
+    let output = output_cancellable(cmd, cancel, label)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format_git_error(label, &output))
+    }
+}
+
+/// Runs `cmd` to completion like [`std::process::Command::output`], but kills it
+/// as soon as `cancel` fires.
+fn output_cancellable(
+    mut cmd: std::process::Command,
+    cancel: &CancelToken,
+    label: &str,
+) -> Result<std::process::Output, String> {
+    use std::io::Read;
+
+    fn drain(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut bytes);
+            }
+            bytes
+        })
+    }
+
+    let mut child = cmd
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Failed to run {label}: {err}"))?;
+    // Read both pipes on their own threads so a chatty command cannot block on a full pipe.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = loop {
+        if cancel.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("{label} cancelled"));
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(err) => return Err(format!("Failed to run {label}: {err}")),
+        }
+    };
+
+    Ok(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn format_git_error(label: &str, output: &std::process::Output) -> String {
+    let message = String::from_utf8_lossy(&output.stderr);
+    let trimmed = message.trim();
+    if trimmed.is_empty() {
+        format!("{label} exited with status: {}", output.status)
+    } else {
+        clean_git_message(trimmed)
+    }
+}
+
+/// The remote the current branch pushes to, falling back to the first one configured.
+fn default_remote(path: &Path) -> Result<String, String> {
+    let branch = current_branch_name_in(path);
+    branch
+        .as_deref()
+        .and_then(|branch| upstream_for_branch(path, branch))
+        .and_then(|upstream| upstream_remote(&upstream).map(str::to_string))
+        .or_else(|| {
+            branch
+                .as_deref()
+                .and_then(|branch| remote_for_branch(path, branch))
+        })
+        .or_else(|| first_remote(path))
+        .ok_or_else(|| "No remote configured".to_string())
+}
+
+fn remote_for_branch(path: &Path, branch: &str) -> Option<String> {
+    git_config_value(path, &format!("branch.{branch}.remote"))
+}
+
+fn first_remote(path: &Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("remote")
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .find(|line| !line.is_empty())
+}
+
+fn git_config_value(path: &Path, key: &str) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("config")
+        .arg("--get")
+        .arg(key)
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn set_branch_upstream(path: &Path, branch: &str, remote_ref: &str) -> Result<(), String> {
+    run_git_command(
+        path,
+        ["branch", "--set-upstream-to", remote_ref, branch],
+        "git branch --set-upstream-to",
+    )
+}
+
+fn branch_remote_target(path: &Path, branch: &str, remote: &str) -> String {
+    if let Some(remote_branch) = git_config_value(path, &format!("branch.{branch}.merge")) {
+        let trimmed = remote_branch.trim_start_matches("refs/heads/");
+        format!("{remote}/{trimmed}")
+    } else {
+        format!("{remote}/{branch}")
+    }
+}
+
+fn upstream_remote(upstream: &str) -> Option<&str> {
+    upstream.split_once('/').map(|(remote, _)| remote)
+}
+
+fn clean_git_message(message: &str) -> String {
+    let first_line = message.lines().next().unwrap_or(message).trim();
+    first_line
+        .trim_start_matches("fatal: ")
+        .trim_start_matches("error: ")
+        .trim()
+        .to_string()
+}
+
+fn try_fetch_branch_info(
+    path: impl AsRef<Path>,
+    known: &AheadBehindCounts,
+) -> Result<BranchInfo, String> {
+    let path = path.as_ref();
+    let repo = gix::discover(path).map_err(|err| format!("Not a git repository: {err}"))?;
+
+    let current = repo
+        .head()
+        .map_err(|err| format!("Failed to read HEAD: {err}"))?
+        .referent_name()
+        .map(|name| name.shorten().to_string());
+
+    let mut locals = std::collections::HashMap::new();
+    for r in repo
+        .references()
+        .map_err(|err| format!("Failed to list references: {err}"))?
+        .prefixed("refs/heads/")
+        .map_err(|err| format!("Failed to filter branches: {err}"))?
+        .flatten()
+    {
+        let name = r.name().shorten().to_string();
+        locals.entry(name.clone()).or_insert(BranchSummary {
+            name,
+            ahead: None,
+            behind: None,
+            has_local: true,
+            has_remote: false,
+            remote_ref: None,
+            upstream: None,
+            integration: None,
+        });
+    }
+
+    let mut remotes = std::collections::HashMap::new();
+    for r in repo
+        .references()
+        .map_err(|err| format!("Failed to list references: {err}"))?
+        .prefixed("refs/remotes/")
+        .map_err(|err| format!("Failed to filter remote branches: {err}"))?
+        .flatten()
+    {
+        let full = r.name().shorten().to_string();
+        if full.ends_with("/HEAD") {
+            continue;
+        }
+        let Some((remote, short)) = full.split_once('/') else {
+            continue;
+        };
+        let entry = remotes.entry(short.to_string()).or_insert(BranchSummary {
+            name: short.to_string(),
+            ahead: None,
+            behind: None,
+            has_local: false,
+            has_remote: true,
+            remote_ref: Some(full.clone()),
+            upstream: None,
+            integration: None,
+        });
+        entry.has_remote = true;
+        if entry.remote_ref.is_none() || remote == "origin" {
+            entry.remote_ref = Some(full.clone());
+        }
+    }
+
+    for (name, remote_branch) in remotes {
+        locals
+            .entry(name)
+            .and_modify(|local| {
+                local.has_remote = true;
+                if local.remote_ref.is_none()
+                    || remote_branch
+                        .remote_ref
+                        .as_deref()
+                        .map(|full| full.starts_with("origin/"))
+                        .unwrap_or(false)
+                {
+                    local.remote_ref = remote_branch.remote_ref.clone();
+                }
+            })
+            .or_insert(remote_branch);
+    }
+
+    let mut branches: Vec<BranchSummary> = locals.into_values().collect();
+
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    let integration_branch = integration_branch_in(path);
+    let mut counts = AheadBehindCounts::new();
+    let mut count = |branch: &str, target: &str| {
+        let tip = |name: &str| repo.rev_parse_single(name).ok().map(|id| id.detach());
+        let key = (tip(branch)?, tip(target)?);
+        let pair = match known.get(&key) {
+            Some(&pair) => pair,
+            None => ahead_behind_for_pair(path, branch, target)?,
+        };
+        counts.insert(key, pair);
+        Some(pair)
+    };
+    for branch in branches.iter_mut() {
+        if !branch.has_local {
+            continue;
+        }
+        branch.upstream = upstream_for_branch(path, &branch.name);
+        if let Some(upstream) = branch.upstream.as_deref()
+            && let Some((ahead, behind)) = count(&branch.name, upstream)
+        {
+            branch.ahead = Some(ahead);
+            branch.behind = Some(behind);
+        }
+        if let Some(target) = integration_branch
+            .as_deref()
+            .filter(|target| *target != branch.name)
+            && let Some((ahead, behind)) = count(&branch.name, target)
+        {
+            branch.integration = Some(AheadBehind {
+                target: target.to_string(),
+                ahead,
+                behind,
+            });
+        }
+    }
+
+    Ok(BranchInfo {
+        branches,
+        current,
+        integration_branch,
+        status: None,
+        hovered: None,
+        selected: None,
+        counts,
+    })
+}
+
+fn split_remote_ref(remote_ref: &str) -> Result<(&str, &str), String> {
+    let mut parts = remote_ref.splitn(2, '/');
+    let remote = parts.next().unwrap_or("");
+    let branch = parts.next().unwrap_or("");
+    if remote.trim().is_empty() || branch.trim().is_empty() {
+        return Err("Invalid remote branch name".to_string());
+    }
+    Ok((remote, branch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        fs::{self, File},
+        io::Write,
+        path::{Path, PathBuf},
+        process::Command,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn fetch_lists_all_branches_and_current() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("README.md", "hello").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.git(&["branch", "feature"]).unwrap();
+
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+
+        assert_eq!(info.current.as_deref(), Some("main"));
+        assert_eq!(
+            branch_names(&info),
+            vec!["feature".to_string(), "main".to_string()]
+        );
+    }
+
+    #[test]
+    fn fetch_lists_remote_branches_after_fetch() {
+        let remote = create_bare_repo().unwrap();
+
+        let source = TestRepo::init().unwrap();
+        source.write_file("file.txt", "base").unwrap();
+        source.git(&["add", "."]).unwrap();
+        source.git(&["commit", "-m", "init"]).unwrap();
+        source.add_remote("origin", &remote).unwrap();
+        source.git(&["checkout", "-b", "feature"]).unwrap();
+        source.write_file("file.txt", "feature work").unwrap();
+        source.git(&["add", "."]).unwrap();
+        source.git(&["commit", "-m", "feature"]).unwrap();
+        source.git(&["push", "origin", "feature"]).unwrap();
+
+        let fetch_repo = TestRepo::init().unwrap();
+        fetch_repo.add_remote("origin", &remote).unwrap();
+        fetch_remotes_in(fetch_repo.path(), &CancelToken::default()).unwrap();
+        let info = fetch_branch_info_in(fetch_repo.path(), &AheadBehindCounts::new());
+
+        let remote_branch = info
+            .branches
+            .iter()
+            .find(|b| b.has_remote && b.name == "feature")
+            .expect("remote branch");
+        assert_eq!(remote_branch.remote_ref.as_deref(), Some("origin/feature"));
+
+        let _ = std::fs::remove_dir_all(remote);
+    }
+
+    #[test]
+    fn cancelled_fetch_stops_without_fetching() {
+        let remote = create_bare_repo().unwrap();
+        let source = TestRepo::init().unwrap();
+        source.write_file("file.txt", "base").unwrap();
+        source.git(&["add", "."]).unwrap();
+        source.git(&["commit", "-m", "init"]).unwrap();
+        source.add_remote("origin", &remote).unwrap();
+        source.git(&["push", "origin", "main"]).unwrap();
+
+        let fetch_repo = TestRepo::init().unwrap();
+        fetch_repo.add_remote("origin", &remote).unwrap();
+        let cancel = CancelToken::default();
+        cancel.cancel();
+
+        let err = fetch_remotes_in(fetch_repo.path(), &cancel).unwrap_err();
+
+        assert_eq!(err, "git fetch cancelled");
+        let info = fetch_branch_info_in(fetch_repo.path(), &AheadBehindCounts::new());
+        assert!(!info.branches.iter().any(|b| b.has_remote));
+
+        let _ = std::fs::remove_dir_all(remote);
+    }
+
+    #[test]
+    fn checkout_moves_head_to_requested_branch() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "content").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.git(&["branch", "topic"]).unwrap();
+
+        checkout_branch_in(repo.path(), "topic").unwrap();
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+
+        assert_eq!(info.current.as_deref(), Some("topic"));
+    }
+
+    #[test]
+    fn create_branch_creates_and_checks_out_new_branch() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "content").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+
+        create_branch_in(repo.path(), "feature").unwrap();
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+
+        assert_eq!(info.current.as_deref(), Some("feature"));
+        assert_eq!(
+            branch_names(&info),
+            vec!["feature".to_string(), "main".to_string()]
+        );
+    }
+
+    #[test]
+    fn delete_branch_removes_branch_when_not_current() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "content").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.git(&["branch", "old"]).unwrap();
+
+        delete_branch_in(repo.path(), "old").unwrap();
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+
+        assert_eq!(branch_names(&info), vec!["main".to_string()]);
+    }
+
+    #[test]
+    fn delete_branch_fails_when_branch_is_current() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "content").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+
+        let err = delete_branch_in(repo.path(), "main").unwrap_err();
+        assert!(err.contains("delete"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn fetch_commits_lists_recent_commits() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "first").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "first"]).unwrap();
+
+        repo.write_file("file.txt", "second").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "second"]).unwrap();
+
+        let commits = fetch_commits_in(
+            repo.path(),
+            &mut LogCursor::default(),
+            100,
+            &LogFilter::default(),
+        )
+        .unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].summary, "second");
+        assert_eq!(commits[1].summary, "first");
+        assert!(!commits[0].id.is_empty());
+        assert_eq!(commits[0].branches, vec!["main".to_string()]);
+        assert_eq!(commits[0].parents, vec![commits[1].id.clone()]);
+        assert!(commits[1].parents.is_empty());
+    }
+
+    #[test]
+    fn fetch_commits_stops_at_limit() {
+        let repo = TestRepo::init().unwrap();
+        for idx in 0..3 {
+            repo.write_file("file.txt", &idx.to_string()).unwrap();
+            repo.git(&["add", "."]).unwrap();
+            repo.git(&["commit", "-m", &format!("commit {idx}")])
+                .unwrap();
+        }
+
+        let commits = fetch_commits_in(
+            repo.path(),
+            &mut LogCursor::default(),
+            2,
+            &LogFilter::default(),
+        )
+        .unwrap();
+
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["commit 2", "commit 1"]);
+        assert_eq!(commits[1].branches, vec!["main".to_string()]);
+    }
+
+    #[test]
+    fn fetch_commits_continues_from_the_cursor() {
+        let repo = TestRepo::init().unwrap();
+        for idx in 0..4 {
+            commit_file(
+                &repo,
+                "file.txt",
+                &idx.to_string(),
+                &format!("commit {idx}"),
+            );
+        }
+        repo.git(&["checkout", "-q", "-b", "feature", "HEAD~3"])
+            .unwrap();
+        commit_file(&repo, "feature.txt", "feature", "feature work");
+
+        let mut cursor = LogCursor::default();
+        let mut paged =
+            fetch_commits_in(repo.path(), &mut cursor, 2, &LogFilter::default()).unwrap();
+        paged
+            .extend(fetch_commits_in(repo.path(), &mut cursor, 10, &LogFilter::default()).unwrap());
+
+        let all = fetch_commits_in(
+            repo.path(),
+            &mut LogCursor::default(),
+            10,
+            &LogFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(cursor.skip, 5);
+        assert_eq!(paged, all);
+        assert!(paged.iter().all(|commit| !commit.branches.is_empty()));
+
+        repo.git(&["checkout", "-q", "main"]).unwrap();
+        repo.git(&["merge", "-q", "--no-ff", "-m", "merge feature", "feature"])
+            .unwrap();
+        repo.git(&["tag", "-a", "-m", "old", "old", "HEAD~2"])
+            .unwrap();
+        let mut cursor = LogCursor::default();
+        let mut one_by_one = Vec::new();
+        loop {
+            let page =
+                fetch_commits_in(repo.path(), &mut cursor, 1, &LogFilter::default()).unwrap();
+            if page.is_empty() {
+                break;
+            }
+            one_by_one.extend(page);
+        }
+        let mut ids: Vec<&str> = one_by_one.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids.len(), 6);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 6);
+    }
+
+    #[test]
+    fn fetch_commits_applies_author_and_path_filters() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("auth.rs", "v1").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "add auth"]).unwrap();
+        repo.write_file("main.rs", "fn main() {}").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&[
+            "commit",
+            "-m",
+            "add main",
+            "--author",
+            "Jane Roe <jane@example.com>",
+        ])
+        .unwrap();
+        repo.write_file("auth.rs", "v2").unwrap();
+        repo.git(&["commit", "-am", "fix auth"]).unwrap();
+
+        let by_path = LogFilter {
+            path: Some("auth.rs".to_string()),
+            ..LogFilter::default()
+        };
+        let commits =
+            fetch_commits_in(repo.path(), &mut LogCursor::default(), 100, &by_path).unwrap();
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["fix auth", "add auth"]);
+        assert_eq!(commits[0].parents, vec![commits[1].id.clone()]);
+        assert_eq!(commits[1].branches, vec!["main".to_string()]);
+
+        let by_author = LogFilter {
+            author: Some("jane".to_string()),
+            ..LogFilter::default()
+        };
+        let commits =
+            fetch_commits_in(repo.path(), &mut LogCursor::default(), 100, &by_author).unwrap();
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["add main"]);
+
+        let before_repo = LogFilter {
+            until: Some("2001-01-01".to_string()),
+            ..LogFilter::default()
+        };
+        assert!(
+            fetch_commits_in(repo.path(), &mut LogCursor::default(), 100, &before_repo)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn fetch_commits_by_author_labels_only_branch_tips() {
+        let repo = TestRepo::init().unwrap();
+        for idx in 0..4 {
+            let author = if idx % 2 == 0 {
+                "Alice <alice@example.com>"
+            } else {
+                "Bob <bob@example.com>"
+            };
+            repo.write_file("file.txt", &idx.to_string()).unwrap();
+            repo.git(&["add", "."]).unwrap();
+            repo.git(&["commit", "-m", &format!("commit {idx}"), "--author", author])
+                .unwrap();
+        }
+        repo.git(&["branch", "alice-tip", "HEAD~1"]).unwrap();
+
+        let by_author = LogFilter {
+            author: Some("alice".to_string()),
+            ..LogFilter::default()
+        };
+        assert!(by_author.hides_ancestry());
+        let commits =
+            fetch_commits_in(repo.path(), &mut LogCursor::default(), 100, &by_author).unwrap();
+
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["commit 2", "commit 0"]);
+        assert!(!commits[0].parents.contains(&commits[1].id));
+        assert_eq!(commits[0].branches, vec!["alice-tip".to_string()]);
+        assert!(commits[1].branches.is_empty());
+    }
+
+    #[test]
+    fn fetch_commits_marks_branch_tips() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "base").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "base"]).unwrap();
+
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        repo.write_file("file.txt", "feature work").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "feature work"]).unwrap();
+
+        let commits = fetch_commits_in(
+            repo.path(),
+            &mut LogCursor::default(),
+            100,
+            &LogFilter::default(),
+        )
+        .unwrap();
+
+        assert_eq!(commits[0].summary, "feature work");
+        assert_eq!(commits[0].branches, vec!["feature".to_string()]);
+        assert!(
+            commits
+                .iter()
+                .any(|c| c.summary == "base" && c.branches == vec!["main".to_string()])
+        );
+    }
+
+    #[test]
+    fn fetch_commits_lists_every_branch_containing_a_commit() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "base").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "base"]).unwrap();
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        repo.write_file("file.txt", "shared").unwrap();
+        repo.git(&["commit", "-am", "shared"]).unwrap();
+        repo.git(&["checkout", "-b", "follow-up"]).unwrap();
+        repo.write_file("file.txt", "follow-up").unwrap();
+        repo.git(&["commit", "-am", "follow-up"]).unwrap();
+        repo.git(&["checkout", "main"]).unwrap();
+        repo.write_file("other.txt", "main").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "main work"]).unwrap();
+        repo.git(&["merge", "--no-ff", "feature", "-m", "merge feature"])
+            .unwrap();
+
+        let commits = fetch_commits_in(
+            repo.path(),
+            &mut LogCursor::default(),
+            100,
+            &LogFilter::default(),
+        )
+        .unwrap();
+        let branches_of = |summary: &str| {
+            commits
+                .iter()
+                .find(|c| c.summary == summary)
+                .map(|c| c.branches.clone())
+                .unwrap()
+        };
+
+        assert_eq!(branches_of("follow-up"), vec!["follow-up".to_string()]);
+        assert_eq!(branches_of("shared"), vec!["main".to_string()]);
+        assert_eq!(branches_of("base"), vec!["main".to_string()]);
+    }
+
+    #[test]
+    fn branch_membership_propagates_from_tips_to_ancestors() {
+        let graph: Vec<(&str, &[&str])> =
+            vec![("d", &["b", "c"]), ("c", &["a"]), ("b", &["a"]), ("a", &[])];
+        let tips = vec![
+            ("merged".to_string(), "d".to_string()),
+            ("side".to_string(), "c".to_string()),
+        ];
+
+        let membership = branch_membership(&graph, &tips, &mut HashMap::new());
+
+        assert_eq!(membership[0], BTreeSet::from([0]));
+        assert_eq!(membership[1], BTreeSet::from([0, 1]));
+        assert_eq!(membership[2], BTreeSet::from([0]));
+        assert_eq!(membership[3], BTreeSet::from([0, 1]));
+    }
+
+    #[test]
+    fn repo_dirs_point_at_work_tree_and_git_dir() {
+        let repo = TestRepo::init().unwrap();
+        fs::create_dir(repo.path().join("nested")).unwrap();
+
+        let (work_dir, git_dir) = repo_dirs_in(repo.path().join("nested")).unwrap();
+
+        let root = repo.path().canonicalize().unwrap();
+        assert_eq!(work_dir.canonicalize().unwrap(), root);
+        assert_eq!(git_dir.canonicalize().unwrap(), root.join(".git"));
+    }
+
+    fn commit_file(repo: &TestRepo, name: &str, contents: &str, message: &str) {
+        repo.write_file(name, contents).unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", message]).unwrap();
+    }
+
+    #[test]
+    fn interactive_rebase_applies_the_edited_plan() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "base.txt", "base", "base");
+        commit_file(&repo, "a.txt", "a", "add a");
+        commit_file(&repo, "b.txt", "b", "add b");
+        commit_file(&repo, "a.txt", "a2", "tweak a");
+        commit_file(&repo, "c.txt", "c", "add c");
+
+        let mut steps = fetch_rebase_plan_in(repo.path(), "HEAD~4").unwrap();
+        let summaries: Vec<&str> = steps.iter().map(|step| step.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["add a", "add b", "tweak a", "add c"]);
+        assert!(steps.iter().all(|step| step.action == RebaseAction::Pick));
+
+        steps.swap(1, 2);
+        steps[1].action = RebaseAction::Fixup;
+        steps[2].action = RebaseAction::Drop;
+        steps[3].action = RebaseAction::Reword;
+        steps[3].message = Some("add c, reworded".to_string());
+
+        let outcome = run_interactive_rebase_in(repo.path(), "HEAD~4", &steps).unwrap();
+
+        assert_eq!(outcome, OperationOutcome::Finished);
+        assert_eq!(
+            git_output(&repo, &["log", "--format=%s"]).trim(),
+            "add c, reworded\nadd a\nbase"
+        );
+        assert_eq!(fs::read_to_string(repo.path().join("a.txt")).unwrap(), "a2");
+        assert!(!repo.path().join("b.txt").exists());
+        assert_eq!(fetch_repo_status_in(repo.path()).operation, None);
+    }
+
+    #[test]
+    fn rewording_keeps_the_body_and_trailers() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "base.txt", "base", "base");
+        commit_file(
+            &repo,
+            "a.txt",
+            "a",
+            "add a\n\nWhy a is needed.\n\nSigned-off-by: Dev <dev@example.com>",
+        );
+
+        let mut steps = fetch_rebase_plan_in(repo.path(), "HEAD~1").unwrap();
+        assert_eq!(
+            steps[0].original_message,
+            "add a\n\nWhy a is needed.\n\nSigned-off-by: Dev <dev@example.com>"
+        );
+        steps[0].action = RebaseAction::Reword;
+        steps[0].message = Some(steps[0].original_message.replace("add a", "add the a file"));
+
+        let outcome = run_interactive_rebase_in(repo.path(), "HEAD~1", &steps).unwrap();
+
+        assert_eq!(outcome, OperationOutcome::Finished);
+        assert_eq!(
+            git_output(&repo, &["log", "-1", "--format=%B"]).trim_end(),
+            "add the a file\n\nWhy a is needed.\n\nSigned-off-by: Dev <dev@example.com>"
+        );
+    }
+
+    #[test]
+    fn interactive_rebase_stops_on_conflicts_until_aborted() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        commit_file(&repo, "file.txt", "one", "first");
+        commit_file(&repo, "file.txt", "two", "second");
+
+        let mut steps = fetch_rebase_plan_in(repo.path(), "HEAD~2").unwrap();
+        steps.swap(0, 1);
+
+        let outcome = run_interactive_rebase_in(repo.path(), "HEAD~2", &steps).unwrap();
+
+        assert_eq!(outcome, OperationOutcome::Conflicts);
+        let operation = fetch_repo_status_in(repo.path()).operation.unwrap();
+        assert_eq!(operation.kind, OperationKind::Rebase);
+        assert_eq!(operation.label(), "rebase 1/2");
+        abort_operation_in(repo.path(), OperationKind::Rebase).unwrap();
+        assert_eq!(fetch_repo_status_in(repo.path()).operation, None);
+        assert_eq!(
+            git_output(&repo, &["log", "-1", "--format=%s"]).trim(),
+            "second"
+        );
+    }
+
+    #[test]
+    fn rebase_plan_requires_an_ancestor_of_head() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "side"]).unwrap();
+        commit_file(&repo, "side.txt", "side", "side");
+        repo.git(&["checkout", "main"]).unwrap();
+
+        let err = fetch_rebase_plan_in(repo.path(), "side").unwrap_err();
+
+        assert_eq!(err, "side is not an ancestor of HEAD");
+        assert!(fetch_rebase_plan_in(repo.path(), "HEAD").is_err());
+    }
+
+    #[test]
+    fn rebases_current_branch_onto_another() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "feature.txt", "one", "feature one");
+        commit_file(&repo, "feature.txt", "two", "feature two");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "main.txt", "main", "main work");
+        repo.git(&["checkout", "feature"]).unwrap();
+        assert_eq!(
+            ahead_behind_for_pair(repo.path(), "feature", "main"),
+            Some((2, 1))
+        );
+
+        let outcome = rebase_onto_in(repo.path(), "main").unwrap();
+
+        assert_eq!(outcome, OperationOutcome::Finished);
+        assert_eq!(
+            ahead_behind_for_pair(repo.path(), "feature", "main"),
+            Some((2, 0))
+        );
+    }
+
+    #[test]
+    fn rebase_conflicts_can_be_skipped() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "feature", "clashing change");
+        commit_file(&repo, "other.txt", "other", "independent change");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "file.txt", "main", "main change");
+        repo.git(&["checkout", "feature"]).unwrap();
+
+        assert_eq!(
+            rebase_onto_in(repo.path(), "main").unwrap(),
+            OperationOutcome::Conflicts
+        );
+        assert_eq!(
+            fetch_repo_status_in(repo.path()).operation.unwrap().label(),
+            "rebase 1/2"
+        );
+
+        assert_eq!(
+            skip_operation_in(repo.path(), OperationKind::Rebase).unwrap(),
+            OperationOutcome::Finished
+        );
+        assert_eq!(
+            git_output(&repo, &["log", "--format=%s", "-2"]).trim(),
+            "independent change\nmain change"
+        );
+    }
+
+    #[test]
+    fn parses_diff3_conflict_segments() {
+        let text = "top\n<<<<<<< ours\nmine\n||||||| base\nold\n=======\nyours\nyours 2\n>>>>>>> theirs\nbottom";
+
+        let segments = parse_conflict_segments(text);
+
+        assert_eq!(
+            segments,
+            vec![
+                ConflictSegment::Clean(vec!["top\n".to_string()]),
+                ConflictSegment::Conflict(ConflictHunk {
+                    ours: vec!["mine\n".to_string()],
+                    base: vec!["old\n".to_string()],
+                    theirs: vec!["yours\n".to_string(), "yours 2\n".to_string()],
+                }),
+                ConflictSegment::Clean(vec!["bottom".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_conflict_hunks_and_stages_the_file() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "a\nshared\nb\nshared\nc\n", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(
+            &repo,
+            "file.txt",
+            "A-theirs\nshared\nb\nshared\nC-theirs\n",
+            "feature",
+        );
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(
+            &repo,
+            "file.txt",
+            "A-ours\nshared\nb\nshared\nC-ours\n",
+            "main",
+        );
+        merge_branch_in(repo.path(), "feature", MergeMode::NoFastForward).unwrap();
+
+        let conflict = fetch_conflict_in(repo.path(), "file.txt").unwrap();
+        assert_eq!(conflict.hunk_count(), 2);
+        let first = conflict.hunks().next().unwrap();
+        assert_eq!(first.ours, vec!["A-ours\n".to_string()]);
+        assert_eq!(first.base, vec!["a\n".to_string()]);
+        assert_eq!(first.theirs, vec!["A-theirs\n".to_string()]);
+
+        let partial = conflict.resolve(&[Some(ConflictSide::Theirs), None]);
+        assert!(partial.starts_with("A-theirs\nshared\nb\nshared\n<<<<<<< ours\nC-ours\n"));
+
+        let resolved = conflict.resolve(&[Some(ConflictSide::Theirs), Some(ConflictSide::Both)]);
+        assert_eq!(resolved, "A-theirs\nshared\nb\nshared\nC-ours\nC-theirs\n");
+        resolve_conflict_in(repo.path(), "file.txt", &resolved).unwrap();
+
+        let status = fetch_repo_status_in(repo.path());
+        assert_eq!(status.operation.unwrap().kind, OperationKind::Merge);
+        assert_eq!(
+            status.changes,
+            vec![FileChange {
+                path: "file.txt".to_string(),
+                change: ChangeType::Modified,
+                staged: true,
+            }]
+        );
+    }
 
-    for (name, remote_branch) in remotes {
-        locals
-            .entry(name)
-            .and_modify(|local| {
-                local.has_remote = true;
-                if local.remote_ref.is_none() || remote_branch
-                    .remote_ref
-                    .as_deref()
-                    .map(|full| full.starts_with("origin/"))
-                    .unwrap_or(false)
-                {
-                    local.remote_ref = remote_branch.remote_ref.clone();
-                }
-            })
-            .or_insert(remote_branch);
+    #[test]
+    fn deleting_side_of_a_modify_delete_conflict_removes_the_file() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base\n", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "feature\n", "feature");
+        repo.git(&["checkout", "main"]).unwrap();
+        repo.git(&["rm", "-q", "file.txt"]).unwrap();
+        repo.git(&["commit", "-m", "drop file"]).unwrap();
+        let outcome = merge_branch_in(repo.path(), "feature", MergeMode::NoFastForward).unwrap();
+        assert_eq!(outcome, MergeOutcome::Conflicts);
+
+        let conflict = fetch_conflict_in(repo.path(), "file.txt").unwrap();
+        assert_eq!(conflict.deleted_by, Some(ConflictSide::Ours));
+        let choices = vec![Some(ConflictSide::Ours); conflict.hunk_count()];
+        assert!(conflict.deletes(&choices));
+        assert!(!conflict.deletes(&vec![Some(ConflictSide::Theirs); conflict.hunk_count()]));
+        remove_conflicted_in(repo.path(), "file.txt").unwrap();
+
+        assert!(!repo.path().join("file.txt").exists());
+        assert!(git_output(&repo, &["ls-files", "--", "file.txt"]).is_empty());
+        assert!(
+            fetch_repo_status_in(repo.path())
+                .changes
+                .iter()
+                .all(|change| change.change != ChangeType::Unmerged)
+        );
     }
 
-    let mut branches: Vec<BranchSummary> = locals.into_values().collect();
+    #[test]
+    fn resolving_keeps_edits_made_outside_the_conflict_view() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base\n", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "feature\n", "feature");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "file.txt", "main\n", "main");
+        merge_branch_in(repo.path(), "feature", MergeMode::NoFastForward).unwrap();
 
-    branches.sort_by(|a, b| a.name.cmp(&b.name));
-    let default_branch = find_main_branch_in(path);
-    for branch in branches.iter_mut() {
-        if !branch.has_local {
-            continue;
-        }
-        if let Some((ahead, behind)) =
-            branch_ahead_behind(path, &branch.name, default_branch.as_deref())
-        {
-            branch.ahead = Some(ahead);
-            branch.behind = Some(behind);
-        }
+        let conflict = fetch_conflict_in(repo.path(), "file.txt").unwrap();
+        repo.write_file("file.txt", "merged by hand\n").unwrap();
+        let resolved = conflict.resolve(&[Some(ConflictSide::Theirs)]);
+
+        assert!(resolve_conflict_in(repo.path(), "file.txt", &resolved).is_err());
+        assert_eq!(
+            fs::read_to_string(repo.path().join("file.txt")).unwrap(),
+            "merged by hand\n"
+        );
     }
 
-    Ok(BranchInfo {
-        branches,
-        current,
-        status: None,
-        hovered: None,
-        selected: None,
-    })
-}
+    #[test]
+    fn merges_in_each_mode() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "base.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "feature.txt", "feature", "feature work");
+        repo.git(&["checkout", "main"]).unwrap();
 
-fn split_remote_ref(remote_ref: &str) -> Result<(&str, &str), String> {
-    let mut parts = remote_ref.splitn(2, '/');
-    let remote = parts.next().unwrap_or("");
-    let branch = parts.next().unwrap_or("");
-    if remote.trim().is_empty() || branch.trim().is_empty() {
-        return Err("Invalid remote branch name".to_string());
+        let outcome = merge_branch_in(repo.path(), "feature", MergeMode::NoFastForward).unwrap();
+        assert_eq!(outcome, MergeOutcome::Merged);
+        assert_eq!(
+            git_output(&repo, &["log", "-1", "--format=%p"])
+                .split(' ')
+                .count(),
+            2
+        );
+        let again = merge_branch_in(repo.path(), "feature", MergeMode::FastForwardOnly).unwrap();
+        assert_eq!(again, MergeOutcome::UpToDate);
+        let again = merge_branch_in(repo.path(), "feature", MergeMode::Squash).unwrap();
+        assert_eq!(again, MergeOutcome::UpToDate);
+
+        repo.git(&["checkout", "-b", "squashed", "HEAD~1"]).unwrap();
+        let outcome = merge_branch_in(repo.path(), "feature", MergeMode::Squash).unwrap();
+        assert_eq!(outcome, MergeOutcome::Squashed);
+        let status = fetch_repo_status_in(repo.path());
+        assert_eq!(status.operation, None);
+        assert_eq!(status.changes.len(), 1);
+        assert!(status.changes[0].staged);
     }
-    Ok((remote, branch))
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::{
-        fs::{self, File},
-        io::Write,
-        path::{Path, PathBuf},
-        process::Command,
-        time::{SystemTime, UNIX_EPOCH},
-    };
+    #[test]
+    fn fast_forward_only_merge_refuses_diverged_branches() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "feature.txt", "feature", "feature work");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "main.txt", "main", "main work");
+
+        assert!(merge_branch_in(repo.path(), "feature", MergeMode::FastForwardOnly).is_err());
+        assert_eq!(
+            git_output(&repo, &["log", "-1", "--format=%s"]).trim(),
+            "main work"
+        );
+    }
 
     #[test]
-    fn fetch_lists_all_branches_and_current() {
+    fn conflicting_merge_waits_for_resolution_or_abort() {
         let repo = TestRepo::init().unwrap();
-        repo.write_file("README.md", "hello").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "init"]).unwrap();
-        repo.git(&["branch", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "feature", "feature work");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "file.txt", "main", "main work");
 
-        let info = fetch_branch_info_in(repo.path());
+        let outcome = merge_branch_in(repo.path(), "feature", MergeMode::NoFastForward).unwrap();
 
-        assert_eq!(info.current.as_deref(), Some("main"));
-        assert_eq!(
-            branch_names(&info),
-            vec!["feature".to_string(), "main".to_string()]
+        assert_eq!(outcome, MergeOutcome::Conflicts);
+        let status = fetch_repo_status_in(repo.path());
+        assert_eq!(status.operation.unwrap().kind, OperationKind::Merge);
+        assert!(
+            status
+                .changes
+                .iter()
+                .any(|change| change.change == ChangeType::Unmerged)
         );
+        abort_operation_in(repo.path(), OperationKind::Merge).unwrap();
+        assert_eq!(fetch_repo_status_in(repo.path()).operation, None);
     }
 
     #[test]
-    fn fetch_lists_remote_branches_after_fetch() {
-        let remote = create_bare_repo().unwrap();
+    fn merge_blocked_by_earlier_conflicts_is_an_error() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "feature", "feature work");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "file.txt", "main", "main work");
+        repo.git(&["checkout", "-b", "other"]).unwrap();
+        commit_file(&repo, "other.txt", "other", "other work");
+        repo.git(&["checkout", "main"]).unwrap();
+        assert!(repo.git(&["cherry-pick", "feature"]).is_err());
 
-        let source = TestRepo::init().unwrap();
-        source.write_file("file.txt", "base").unwrap();
-        source.git(&["add", "."]).unwrap();
-        source.git(&["commit", "-m", "init"]).unwrap();
-        source.add_remote("origin", &remote).unwrap();
-        source.git(&["checkout", "-b", "feature"]).unwrap();
-        source.write_file("file.txt", "feature work").unwrap();
-        source.git(&["add", "."]).unwrap();
-        source.git(&["commit", "-m", "feature"]).unwrap();
-        source.git(&["push", "origin", "feature"]).unwrap();
+        let result = merge_branch_in(repo.path(), "other", MergeMode::NoFastForward);
 
-        let fetch_repo = TestRepo::init().unwrap();
-        fetch_repo.add_remote("origin", &remote).unwrap();
-        fetch_remotes_in(fetch_repo.path()).unwrap();
-        let info = fetch_branch_info_in(fetch_repo.path());
+        assert!(result.is_err(), "unexpected outcome: {result:?}");
+        let status = fetch_repo_status_in(repo.path());
+        assert_eq!(status.operation.unwrap().kind, OperationKind::CherryPick);
+    }
 
-        let remote_branch = info
-            .branches
-            .iter()
-            .find(|b| b.has_remote && b.name == "feature")
-            .expect("remote branch");
-        assert_eq!(remote_branch.remote_ref.as_deref(), Some("origin/feature"));
+    #[test]
+    fn conflicting_squash_merge_reports_conflicts() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "feature", "feature work");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "file.txt", "main", "main work");
 
-        let _ = std::fs::remove_dir_all(remote);
+        let outcome = merge_branch_in(repo.path(), "feature", MergeMode::Squash).unwrap();
+
+        assert_eq!(outcome, MergeOutcome::Conflicts);
     }
 
     #[test]
-    fn checkout_moves_head_to_requested_branch() {
+    fn continues_a_cherry_pick_once_conflicts_are_staged() {
         let repo = TestRepo::init().unwrap();
-        repo.write_file("file.txt", "content").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "init"]).unwrap();
-        repo.git(&["branch", "topic"]).unwrap();
+        commit_file(&repo, "file.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "feature", "feature work");
+        repo.git(&["checkout", "main"]).unwrap();
+        commit_file(&repo, "file.txt", "main", "main work");
 
-        checkout_branch_in(repo.path(), "topic").unwrap();
-        let info = fetch_branch_info_in(repo.path());
+        assert!(repo.git(&["cherry-pick", "feature"]).is_err());
+        let operation = fetch_repo_status_in(repo.path()).operation.unwrap();
+        assert_eq!(operation.kind, OperationKind::CherryPick);
+        assert!(operation.can_skip());
+        assert_eq!(
+            continue_operation_in(repo.path(), OperationKind::CherryPick).unwrap(),
+            OperationOutcome::Conflicts
+        );
 
-        assert_eq!(info.current.as_deref(), Some("topic"));
+        fs::write(repo.path().join("file.txt"), "resolved").unwrap();
+        repo.git(&["add", "file.txt"]).unwrap();
+        assert_eq!(
+            continue_operation_in(repo.path(), OperationKind::CherryPick).unwrap(),
+            OperationOutcome::Finished
+        );
+        assert_eq!(fetch_repo_status_in(repo.path()).operation, None);
+        assert_eq!(
+            git_output(&repo, &["log", "-1", "--format=%s"]).trim(),
+            "feature work"
+        );
     }
 
     #[test]
-    fn create_branch_creates_and_checks_out_new_branch() {
+    fn cherry_picks_commits_with_optional_provenance() {
         let repo = TestRepo::init().unwrap();
-        repo.write_file("file.txt", "content").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "init"]).unwrap();
+        commit_file(&repo, "base.txt", "base", "base");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "one.txt", "one", "fix one");
+        commit_file(&repo, "two.txt", "two", "fix two");
+        let ids: Vec<String> = git_output(&repo, &["log", "--reverse", "--format=%h", "-2"])
+            .lines()
+            .map(str::to_string)
+            .collect();
+        repo.git(&["checkout", "main"]).unwrap();
 
-        create_branch_in(repo.path(), "feature").unwrap();
-        let info = fetch_branch_info_in(repo.path());
+        let outcome = cherry_pick_commits_in(repo.path(), &ids, true).unwrap();
 
-        assert_eq!(info.current.as_deref(), Some("feature"));
+        assert_eq!(outcome, OperationOutcome::Finished);
         assert_eq!(
-            branch_names(&info),
-            vec!["feature".to_string(), "main".to_string()]
+            git_output(&repo, &["log", "--format=%s", "-2"]).trim(),
+            "fix two\nfix one"
+        );
+        assert!(
+            git_output(&repo, &["log", "-1", "--format=%b"]).contains("(cherry picked from commit")
+        );
+
+        let outcome = revert_commits_in(repo.path(), &["HEAD".to_string()]).unwrap();
+        assert_eq!(outcome, OperationOutcome::Finished);
+        assert_eq!(
+            git_output(&repo, &["log", "-1", "--format=%s"]).trim(),
+            "Revert \"fix two\""
         );
+        assert!(!repo.path().join("two.txt").exists());
     }
 
     #[test]
-    fn delete_branch_removes_branch_when_not_current() {
+    fn reverting_a_clashing_commit_reports_its_conflicts() {
         let repo = TestRepo::init().unwrap();
-        repo.write_file("file.txt", "content").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "init"]).unwrap();
-        repo.git(&["branch", "old"]).unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        commit_file(&repo, "file.txt", "two", "second");
+        commit_file(&repo, "file.txt", "three", "third");
 
-        delete_branch_in(repo.path(), "old").unwrap();
-        let info = fetch_branch_info_in(repo.path());
+        let outcome = revert_commits_in(repo.path(), &["HEAD~1".to_string()]).unwrap();
 
-        assert_eq!(branch_names(&info), vec!["main".to_string()]);
+        assert_eq!(outcome, OperationOutcome::Conflicts);
+        let status = fetch_repo_status_in(repo.path());
+        assert_eq!(status.operation.unwrap().kind, OperationKind::Revert);
+        assert_eq!(status.conflicted_files(), vec!["file.txt".to_string()]);
+        abort_operation_in(repo.path(), OperationKind::Revert).unwrap();
     }
 
     #[test]
-    fn delete_branch_fails_when_branch_is_current() {
+    fn previews_and_resets_in_each_mode() {
         let repo = TestRepo::init().unwrap();
-        repo.write_file("file.txt", "content").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "init"]).unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        commit_file(&repo, "file.txt", "two", "second");
+        commit_file(&repo, "other.txt", "three", "third");
+        fs::write(repo.path().join("file.txt"), "edited").unwrap();
+        fs::write(repo.path().join("new.txt"), "untracked").unwrap();
+
+        let preview = fetch_reset_preview_in(repo.path(), "HEAD~2").unwrap();
+        let summaries: Vec<&str> = preview
+            .commits
+            .iter()
+            .map(|commit| commit.summary.as_str())
+            .collect();
+        assert_eq!(summaries, vec!["third", "second"]);
+        assert_eq!(preview.changes.len(), 1);
+        assert_eq!(preview.changes[0].path, "file.txt");
 
-        let err = delete_branch_in(repo.path(), "main").unwrap_err();
-        assert!(err.contains("delete"), "unexpected error: {err}");
+        reset_to_in(repo.path(), "HEAD~1", ResetMode::Soft).unwrap();
+        let staged = fetch_repo_status_in(repo.path());
+        assert!(
+            staged
+                .changes
+                .iter()
+                .any(|change| change.path == "other.txt" && change.staged)
+        );
+
+        reset_to_in(repo.path(), "HEAD", ResetMode::Mixed).unwrap();
+        assert!(
+            fetch_repo_status_in(repo.path())
+                .changes
+                .iter()
+                .all(|change| !change.staged)
+        );
+
+        reset_to_in(repo.path(), "HEAD~1", ResetMode::Hard).unwrap();
+        assert_eq!(
+            git_output(&repo, &["log", "-1", "--format=%s"]).trim(),
+            "first"
+        );
+        assert_eq!(
+            fs::read_to_string(repo.path().join("file.txt")).unwrap(),
+            "one"
+        );
+        assert!(repo.path().join("new.txt").exists());
     }
 
     #[test]
-    fn fetch_commits_lists_recent_commits() {
+    fn amends_head_and_detects_pushed_commits() {
+        let remote = create_bare_repo().unwrap();
         let repo = TestRepo::init().unwrap();
-        repo.write_file("file.txt", "first").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "first"]).unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        assert_eq!(fetch_head_message_in(repo.path()).unwrap(), "first");
+        assert_eq!(head_pushed_to_in(repo.path()), None);
 
-        repo.write_file("file.txt", "second").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "second"]).unwrap();
+        repo.add_remote("origin", &remote).unwrap();
+        repo.git(&["push", "-u", "origin", "main"]).unwrap();
+        assert_eq!(
+            head_pushed_to_in(repo.path()).as_deref(),
+            Some("origin/main")
+        );
 
-        let commits = fetch_commits_in(repo.path()).unwrap();
+        fs::write(repo.path().join("file.txt"), "two").unwrap();
+        repo.git(&["add", "file.txt"]).unwrap();
+        amend_head_in(repo.path(), "first, amended").unwrap();
 
-        assert_eq!(commits.len(), 2);
-        assert_eq!(commits[0].summary, "second");
-        assert_eq!(commits[1].summary, "first");
-        assert!(!commits[0].id.is_empty());
-        assert_eq!(commits[0].branches, vec!["main".to_string()]);
+        assert_eq!(
+            fetch_head_message_in(repo.path()).unwrap(),
+            "first, amended"
+        );
+        assert_eq!(
+            git_output(&repo, &["rev-list", "--count", "HEAD"]).trim(),
+            "1"
+        );
+        assert!(fetch_repo_status_in(repo.path()).changes.is_empty());
+        assert_eq!(head_pushed_to_in(repo.path()), None);
     }
 
     #[test]
-    fn fetch_commits_marks_branch_tips() {
+    fn bisect_can_only_be_reset() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        commit_file(&repo, "file.txt", "two", "second");
+        repo.git(&["bisect", "start", "HEAD", "HEAD~1"]).unwrap();
+
+        let operation = fetch_repo_status_in(repo.path()).operation.unwrap();
+        assert_eq!(operation.kind, OperationKind::Bisect);
+        assert!(!operation.can_continue());
+        assert!(continue_operation_in(repo.path(), OperationKind::Bisect).is_err());
+        abort_operation_in(repo.path(), OperationKind::Bisect).unwrap();
+        assert_eq!(fetch_repo_status_in(repo.path()).operation, None);
+    }
+
+    #[test]
+    fn ref_tips_change_when_a_branch_moves() {
         let repo = TestRepo::init().unwrap();
+        assert_eq!(fetch_ref_tips_in(repo.path()).unwrap(), "");
+
         repo.write_file("file.txt", "base").unwrap();
         repo.git(&["add", "."]).unwrap();
         repo.git(&["commit", "-m", "base"]).unwrap();
+        let before = fetch_ref_tips_in(repo.path()).unwrap();
+        assert_eq!(before, fetch_ref_tips_in(repo.path()).unwrap());
 
-        repo.git(&["checkout", "-b", "feature"]).unwrap();
-        repo.write_file("file.txt", "feature work").unwrap();
-        repo.git(&["add", "."]).unwrap();
-        repo.git(&["commit", "-m", "feature work"]).unwrap();
-
-        let commits = fetch_commits_in(repo.path()).unwrap();
-
-        assert_eq!(commits[0].summary, "feature work");
-        assert_eq!(commits[0].branches, vec!["feature".to_string()]);
-        assert!(
-            commits
-                .iter()
-                .any(|c| c.summary == "base" && c.branches == vec!["main".to_string()])
-        );
+        repo.git(&["branch", "feature"]).unwrap();
+        let after = fetch_ref_tips_in(repo.path()).unwrap();
+        assert_ne!(before, after);
+        assert!(after.contains("refs/heads/feature"));
     }
 
     #[test]
@@ -1241,7 +4400,7 @@ mod tests {
         repo.git(&["branch", "--set-upstream-to=main", "feature"])
             .unwrap();
 
-        let info = fetch_branch_info_in(repo.path());
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
         let feature = info
             .branches
             .iter()
@@ -1252,7 +4411,7 @@ mod tests {
     }
 
     #[test]
-    fn falls_back_to_main_when_upstream_missing() {
+    fn compares_against_main_when_upstream_missing() {
         let repo = TestRepo::init().unwrap();
         repo.write_file("file.txt", "content").unwrap();
         repo.git(&["add", "."]).unwrap();
@@ -1263,18 +4422,81 @@ mod tests {
         repo.write_file("file.txt", "feature change").unwrap();
         repo.git(&["commit", "-am", "feature work"]).unwrap();
 
-        repo.git(&["checkout", "main"]).unwrap();
-        repo.write_file("file.txt", "main change").unwrap();
-        repo.git(&["commit", "-am", "main work"]).unwrap();
+        repo.git(&["checkout", "main"]).unwrap();
+        repo.write_file("file.txt", "main change").unwrap();
+        repo.git(&["commit", "-am", "main work"]).unwrap();
+
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+        let feature = info
+            .branches
+            .iter()
+            .find(|b| b.name == "feature")
+            .expect("feature branch");
+        assert_eq!(feature.ahead, None);
+        assert_eq!(
+            feature.integration,
+            Some(AheadBehind {
+                target: "main".to_string(),
+                ahead: 1,
+                behind: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn compares_against_the_configured_integration_branch() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "one", "init");
+        repo.git(&["branch", "develop"]).unwrap();
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "two", "feature work");
+        repo.git(&["checkout", "develop"]).unwrap();
+        commit_file(&repo, "other.txt", "a", "develop one");
+        commit_file(&repo, "other.txt", "b", "develop two");
+        repo.git(&["branch", "--set-upstream-to=main", "feature"])
+            .unwrap();
+
+        set_integration_branch_in(repo.path(), Some("develop")).unwrap();
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+        assert_eq!(info.integration_branch.as_deref(), Some("develop"));
+        let feature = info.branches.iter().find(|b| b.name == "feature").unwrap();
+        assert_eq!((feature.ahead, feature.behind), (Some(1), Some(0)));
+        let integration = feature.integration.as_ref().unwrap();
+        assert_eq!(integration.target, "develop");
+        assert_eq!((integration.ahead, integration.behind), (1, 2));
+        let develop = info.branches.iter().find(|b| b.name == "develop").unwrap();
+        assert_eq!(develop.integration, None);
+
+        set_integration_branch_in(repo.path(), None).unwrap();
+        set_integration_branch_in(repo.path(), None).unwrap();
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+        assert_eq!(info.integration_branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn branch_counts_are_reused_until_a_tip_moves() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "one", "init");
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        commit_file(&repo, "file.txt", "two", "feature work");
+
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+        assert_eq!(info.counts.len(), 1);
+        let feature = |info: &BranchInfo| {
+            let feature = info.branches.iter().find(|b| b.name == "feature").unwrap();
+            let integration = feature.integration.as_ref().unwrap();
+            (integration.ahead, integration.behind)
+        };
+        assert_eq!(feature(&info), (1, 0));
+
+        let mut known = info.counts;
+        known.values_mut().for_each(|pair| *pair = (7, 7));
+        let info = fetch_branch_info_in(repo.path(), &known);
+        assert_eq!(feature(&info), (7, 7));
 
-        let info = fetch_branch_info_in(repo.path());
-        let feature = info
-            .branches
-            .iter()
-            .find(|b| b.name == "feature")
-            .expect("feature branch");
-        assert_eq!(feature.ahead, Some(1));
-        assert_eq!(feature.behind, Some(1));
+        commit_file(&repo, "file.txt", "three", "more feature work");
+        let info = fetch_branch_info_in(repo.path(), &known);
+        assert_eq!(feature(&info), (2, 0));
     }
 
     fn branch_names(info: &BranchInfo) -> Vec<String> {
@@ -1291,7 +4513,7 @@ mod tests {
         let remote = create_bare_repo().unwrap();
         repo.add_remote("origin", &remote).unwrap();
 
-        push_current_branch_in(repo.path()).unwrap();
+        push_current_branch_in(repo.path(), &CancelToken::default()).unwrap();
 
         let status = Command::new("git")
             .arg("--git-dir")
@@ -1314,7 +4536,7 @@ mod tests {
 
         let remote = create_bare_repo().unwrap();
         repo.add_remote("origin", &remote).unwrap();
-        push_current_branch_in(repo.path()).unwrap();
+        push_current_branch_in(repo.path(), &CancelToken::default()).unwrap();
 
         Command::new("git")
             .arg("--git-dir")
@@ -1353,7 +4575,7 @@ mod tests {
             .status()
             .expect("git push");
 
-        pull_current_branch_in(repo.path()).unwrap();
+        pull_current_branch_in(repo.path(), &CancelToken::default()).unwrap();
         let contents = std::fs::read_to_string(repo.path().join("file.txt")).unwrap();
         assert!(
             contents.contains("remote change"),
@@ -1396,6 +4618,16 @@ mod tests {
             }]
         );
 
+        let conflicted = parse_status_line("UU src/auth.rs");
+        assert_eq!(
+            conflicted,
+            vec![FileChange {
+                path: "src/auth.rs".to_string(),
+                change: ChangeType::Unmerged,
+                staged: false,
+            }]
+        );
+
         let dual = parse_status_line("MM src/lib.rs");
         assert_eq!(
             dual,
@@ -1491,7 +4723,7 @@ mod tests {
 
         let status = fetch_repo_status_in(repo.path());
         assert!(
-            status.changes.iter().all(|change| change.staged == false),
+            status.changes.iter().all(|change| !change.staged),
             "expected all changes to start unstaged: {:?}",
             status.changes
         );
@@ -1581,6 +4813,649 @@ mod tests {
         assert_eq!(subject, "work");
     }
 
+    #[test]
+    fn parses_hunk_headers_and_lines() {
+        let diff = parse_diff(
+            "diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1,2 +1,3 @@ fn main\n keep\n-old\n+new\n+more\n\\ No newline at end of file\n",
+        );
+
+        assert_eq!(diff.header.len(), 3);
+        assert_eq!(diff.hunks.len(), 1);
+        let hunk = &diff.hunks[0];
+        assert_eq!(
+            (
+                hunk.old_start,
+                hunk.old_lines,
+                hunk.new_start,
+                hunk.new_lines
+            ),
+            (1, 2, 1, 3)
+        );
+        let kinds: Vec<DiffLineKind> = hunk.lines.iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffLineKind::Context,
+                DiffLineKind::Removed,
+                DiffLineKind::Added,
+                DiffLineKind::Added,
+                DiffLineKind::NoNewline,
+            ]
+        );
+        assert_eq!(hunk.lines[1].content, "old");
+        assert_eq!(parse_hunk_header("@@ -0,0 +1 @@").new_lines, 1);
+    }
+
+    #[test]
+    fn diffs_staged_against_head_and_unstaged_against_index() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "one\n").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+
+        repo.write_file("file.txt", "one\ntwo\n").unwrap();
+        repo.git(&["add", "file.txt"]).unwrap();
+        repo.write_file("file.txt", "one\ntwo\nthree\n").unwrap();
+
+        let staged = fetch_change_diff_in(
+            repo.path(),
+            &FileChange {
+                path: "file.txt".into(),
+                change: ChangeType::Modified,
+                staged: true,
+            },
+        )
+        .unwrap();
+        let unstaged = fetch_change_diff_in(
+            repo.path(),
+            &FileChange {
+                path: "file.txt".into(),
+                change: ChangeType::Modified,
+                staged: false,
+            },
+        )
+        .unwrap();
+
+        let added = |diff: &FileDiff| -> Vec<String> {
+            diff.hunks
+                .iter()
+                .flat_map(|h| h.lines.iter())
+                .filter(|l| l.kind == DiffLineKind::Added)
+                .map(|l| l.content.clone())
+                .collect()
+        };
+        assert_eq!(added(&staged), vec!["two".to_string()]);
+        assert_eq!(added(&unstaged), vec!["three".to_string()]);
+    }
+
+    #[test]
+    fn diffs_untracked_file_as_new() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("seed.txt", "seed").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.write_file("new.txt", "hello\n").unwrap();
+
+        let diff = fetch_change_diff_in(
+            repo.path(),
+            &FileChange {
+                path: "new.txt".into(),
+                change: ChangeType::Untracked,
+                staged: false,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.hunks[0].lines[0].kind, DiffLineKind::Added);
+        assert_eq!(diff.hunks[0].lines[0].content, "hello");
+    }
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count).map(|n| format!("line {n}\n")).collect()
+    }
+
+    fn unstaged_file(path: &str) -> FileChange {
+        FileChange {
+            path: path.into(),
+            change: ChangeType::Modified,
+            staged: false,
+        }
+    }
+
+    fn git_output(repo: &TestRepo, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo.path())
+            .output()
+            .expect("git");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    #[test]
+    fn stages_single_hunk_of_file() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", &numbered_lines(20)).unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+
+        let edited = numbered_lines(20)
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "line eighteen\n");
+        repo.write_file("file.txt", &edited).unwrap();
+
+        let change = unstaged_file("file.txt");
+        let diff = fetch_change_diff_in(repo.path(), &change).unwrap();
+        assert_eq!(diff.hunks.len(), 2);
+        let second_hunk: HashSet<(usize, usize)> = diff.hunks[1]
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.kind != DiffLineKind::Context)
+            .map(|(idx, _)| (1, idx))
+            .collect();
+
+        apply_partial_change_in(
+            repo.path(),
+            &change,
+            &diff,
+            &second_hunk,
+            PatchAction::Stage,
+        )
+        .unwrap();
+
+        let cached = git_output(&repo, &["diff", "--cached"]);
+        assert!(cached.contains("+line eighteen"), "cached: {cached}");
+        assert!(!cached.contains("+line two"), "cached: {cached}");
+        let worktree = git_output(&repo, &["diff"]);
+        assert!(worktree.contains("+line two"), "worktree: {worktree}");
+        assert!(!worktree.contains("eighteen"), "worktree: {worktree}");
+    }
+
+    #[test]
+    fn stages_selected_lines_within_hunk() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "a\nb\nc\n").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.write_file("file.txt", "a\nB\nc\nd\n").unwrap();
+
+        let change = unstaged_file("file.txt");
+        let diff = fetch_change_diff_in(repo.path(), &change).unwrap();
+        let added_d = diff.hunks[0]
+            .lines
+            .iter()
+            .position(|l| l.kind == DiffLineKind::Added && l.content == "d")
+            .unwrap();
+
+        apply_partial_change_in(
+            repo.path(),
+            &change,
+            &diff,
+            &HashSet::from([(0, added_d)]),
+            PatchAction::Stage,
+        )
+        .unwrap();
+
+        assert_eq!(git_output(&repo, &["show", ":file.txt"]), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn unstages_and_discards_selected_lines() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "a\nb\nc\n").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.write_file("file.txt", "a\nB\nc\nd\n").unwrap();
+        repo.git(&["add", "file.txt"]).unwrap();
+
+        let staged = FileChange {
+            staged: true,
+            ..unstaged_file("file.txt")
+        };
+        let diff = fetch_change_diff_in(repo.path(), &staged).unwrap();
+        let added_d = diff.hunks[0]
+            .lines
+            .iter()
+            .position(|l| l.kind == DiffLineKind::Added && l.content == "d")
+            .unwrap();
+        apply_partial_change_in(
+            repo.path(),
+            &staged,
+            &diff,
+            &HashSet::from([(0, added_d)]),
+            PatchAction::Unstage,
+        )
+        .unwrap();
+        assert_eq!(git_output(&repo, &["show", ":file.txt"]), "a\nB\nc\n");
+
+        let unstaged = unstaged_file("file.txt");
+        let diff = fetch_change_diff_in(repo.path(), &unstaged).unwrap();
+        let all: HashSet<(usize, usize)> = (0..diff.hunks[0].lines.len()).map(|l| (0, l)).collect();
+        apply_partial_change_in(repo.path(), &unstaged, &diff, &all, PatchAction::Discard).unwrap();
+        let contents = fs::read_to_string(repo.path().join("file.txt")).unwrap();
+        assert_eq!(contents, "a\nB\nc\n");
+    }
+
+    #[test]
+    fn rejects_partial_action_on_wrong_side() {
+        let change = unstaged_file("file.txt");
+        let err = apply_partial_change_in(
+            ".",
+            &change,
+            &FileDiff::default(),
+            &HashSet::new(),
+            PatchAction::Unstage,
+        )
+        .unwrap_err();
+        assert!(err.contains("staged"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn parses_stash_list_lines() {
+        let wip = parse_stash_line("stash@{0}\tWIP on main: abc1234 init\t5 minutes ago").unwrap();
+        assert_eq!(wip.index, 0);
+        assert_eq!(wip.branch.as_deref(), Some("main"));
+        assert_eq!(wip.message, "abc1234 init");
+        assert_eq!(wip.age, "5 minutes ago");
+
+        let named = parse_stash_line("stash@{3}\tOn feature/x: my notes\t2 days ago").unwrap();
+        assert_eq!(named.index, 3);
+        assert_eq!(named.branch.as_deref(), Some("feature/x"));
+        assert_eq!(named.message, "my notes");
+
+        assert!(parse_stash_line("garbage").is_none());
+    }
+
+    #[test]
+    fn creates_lists_pops_and_drops_stashes() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "content").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+
+        assert_eq!(
+            create_stash_in(repo.path(), StashMode::TrackedChanges).unwrap_err(),
+            "No local changes to stash"
+        );
+
+        repo.write_file("file.txt", "first").unwrap();
+        create_stash_in(repo.path(), StashMode::TrackedChanges).unwrap();
+        repo.write_file("file.txt", "second").unwrap();
+        create_stash_in(repo.path(), StashMode::TrackedChanges).unwrap();
+
+        let stashes = fetch_stashes_in(repo.path()).unwrap();
+        assert_eq!(stashes.len(), 2);
+        assert_eq!(stashes[0].index, 0);
+        assert_eq!(stashes[0].branch.as_deref(), Some("main"));
+
+        pop_stash_in(repo.path(), 1).unwrap();
+        let contents = fs::read_to_string(repo.path().join("file.txt")).unwrap();
+        assert_eq!(contents, "first");
+
+        drop_stash_in(repo.path(), 0).unwrap();
+        assert!(fetch_stashes_in(repo.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn creates_lists_pushes_and_deletes_tags() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        let first = git_output(&repo, &["rev-parse", "--short", "HEAD"])
+            .trim()
+            .to_string();
+        commit_file(&repo, "file.txt", "two", "second");
+        let remote = create_bare_repo().unwrap();
+        repo.add_remote("origin", &remote).unwrap();
+
+        create_tag_in(repo.path(), "v1.0", &first, None).unwrap();
+        create_tag_in(repo.path(), "v2.0", "HEAD", Some("Second release")).unwrap();
+        assert_eq!(
+            create_tag_in(repo.path(), " ", "HEAD", None).unwrap_err(),
+            "Tag name cannot be empty"
+        );
+
+        let mut tags = fetch_tags_in(repo.path()).unwrap();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(tags[0].name, "v1.0");
+        assert_eq!(tags[0].target, first);
+        assert_eq!(tags[0].annotation, None);
+        assert_eq!(tags[1].annotation.as_deref(), Some("Second release"));
+        assert_eq!(
+            tags[1].target,
+            git_output(&repo, &["rev-parse", "--short", "HEAD"]).trim()
+        );
+
+        let commits = fetch_commits_in(
+            repo.path(),
+            &mut LogCursor::default(),
+            10,
+            &LogFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(commits[0].tags, vec!["v2.0"]);
+        assert_eq!(commits[1].tags, vec!["v1.0"]);
+
+        let cancel = CancelToken::default();
+        assert_eq!(push_tag_in(repo.path(), "v2.0", &cancel).unwrap(), "origin");
+        let remote_tags = || {
+            let output = Command::new("git")
+                .arg("--git-dir")
+                .arg(&remote)
+                .args(["tag", "--list"])
+                .output()
+                .expect("git tag --list");
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        assert_eq!(remote_tags(), "v2.0");
+
+        delete_remote_tag_in(repo.path(), "v2.0", &cancel).unwrap();
+        assert_eq!(remote_tags(), "");
+        delete_tag_in(repo.path(), "v1.0").unwrap();
+        let names: Vec<String> = fetch_tags_in(repo.path())
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        assert_eq!(names, vec!["v2.0"]);
+        let _ = std::fs::remove_dir_all(remote);
+    }
+
+    #[test]
+    fn sets_lists_and_unsets_upstreams() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        repo.git(&["branch", "feature"]).unwrap();
+        let remote = create_bare_repo().unwrap();
+        repo.add_remote("origin", &remote).unwrap();
+        repo.git(&["push", "origin", "main", "feature"]).unwrap();
+        repo.git(&["remote", "set-head", "origin", "main"]).unwrap();
+
+        assert_eq!(
+            list_remote_branches_in(repo.path()).unwrap(),
+            vec!["origin/feature", "origin/main"]
+        );
+
+        set_upstream_in(repo.path(), "feature", "origin/main").unwrap();
+        let info = try_fetch_branch_info(repo.path(), &AheadBehindCounts::new()).unwrap();
+        let feature = info.branches.iter().find(|b| b.name == "feature").unwrap();
+        assert_eq!(feature.upstream.as_deref(), Some("origin/main"));
+
+        unset_upstream_in(repo.path(), "feature").unwrap();
+        let info = try_fetch_branch_info(repo.path(), &AheadBehindCounts::new()).unwrap();
+        let feature = info.branches.iter().find(|b| b.name == "feature").unwrap();
+        assert_eq!(feature.upstream, None);
+        let _ = std::fs::remove_dir_all(remote);
+    }
+
+    #[test]
+    fn creates_branches_from_a_start_point_and_renames_them_with_their_remote() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        let first = git_output(&repo, &["rev-parse", "--short", "HEAD"])
+            .trim()
+            .to_string();
+        commit_file(&repo, "file.txt", "two", "second");
+        let remote = create_bare_repo().unwrap();
+        repo.add_remote("origin", &remote).unwrap();
+
+        create_branch_from_in(repo.path(), "old-topic", &first).unwrap();
+        assert_eq!(
+            git_output(&repo, &["rev-parse", "--short", "HEAD"]).trim(),
+            first
+        );
+        repo.git(&["push", "-u", "origin", "old-topic"]).unwrap();
+
+        rename_branch_in(repo.path(), "old-topic", "topic").unwrap();
+        assert_eq!(
+            upstream_for_branch(repo.path(), "topic").as_deref(),
+            Some("origin/old-topic")
+        );
+
+        let new_upstream = rename_upstream_branch_in(
+            repo.path(),
+            "topic",
+            "origin/old-topic",
+            &CancelToken::default(),
+        )
+        .unwrap();
+        assert_eq!(new_upstream, "origin/topic");
+        assert_eq!(
+            upstream_for_branch(repo.path(), "topic").as_deref(),
+            Some("origin/topic")
+        );
+        let remote_branches = Command::new("git")
+            .arg("--git-dir")
+            .arg(&remote)
+            .args(["branch", "--format=%(refname:short)"])
+            .output()
+            .expect("git branch");
+        assert_eq!(
+            String::from_utf8_lossy(&remote_branches.stdout).trim(),
+            "topic"
+        );
+        let _ = std::fs::remove_dir_all(remote);
+    }
+
+    #[test]
+    fn parses_remote_urls() {
+        let remotes = parse_remotes(
+            "origin\tgit@host:me/repo.git (fetch)\norigin\tgit@host:me/repo.git (push)\n\
+             upstream\thttps://host/them/repo.git (fetch)\nupstream\tno-push (push)\n",
+        );
+
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(remotes[0].name, "origin");
+        assert_eq!(remotes[0].fetch_url, "git@host:me/repo.git");
+        assert_eq!(remotes[0].push_url, None);
+        assert_eq!(remotes[1].fetch_url, "https://host/them/repo.git");
+        assert_eq!(remotes[1].push_url.as_deref(), Some("no-push"));
+    }
+
+    #[test]
+    fn adds_renames_edits_fetches_and_removes_remotes() {
+        let repo = TestRepo::init().unwrap();
+        commit_file(&repo, "file.txt", "one", "first");
+        let remote = create_bare_repo().unwrap();
+        let url = remote.to_str().unwrap();
+        let cancel = CancelToken::default();
+
+        add_remote_in(repo.path(), "upstream", url).unwrap();
+        assert_eq!(
+            add_remote_in(repo.path(), "origin", " ").unwrap_err(),
+            "Remote URL cannot be empty"
+        );
+        repo.git(&["push", "upstream", "main"]).unwrap();
+        fetch_remote_in(repo.path(), "upstream", &cancel).unwrap();
+        assert!(
+            repo.git(&["rev-parse", "--verify", "upstream/main"])
+                .is_ok()
+        );
+
+        rename_remote_in(repo.path(), "upstream", "origin").unwrap();
+        assert!(repo.git(&["rev-parse", "--verify", "origin/main"]).is_ok());
+
+        set_remote_url_in(repo.path(), "origin", "https://example.com/push.git", true).unwrap();
+        let remotes = list_remotes_in(repo.path()).unwrap();
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(remotes[0].name, "origin");
+        assert_eq!(remotes[0].fetch_url, url);
+        assert_eq!(
+            remotes[0].push_url.as_deref(),
+            Some("https://example.com/push.git")
+        );
+
+        remove_remote_in(repo.path(), "origin").unwrap();
+        assert!(list_remotes_in(repo.path()).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(remote);
+    }
+
+    #[test]
+    fn parses_tag_decorations() {
+        assert_eq!(
+            parse_tag_decorations("tag: v1.0, tag: latest"),
+            vec!["v1.0", "latest"]
+        );
+        assert!(parse_tag_decorations("").is_empty());
+    }
+
+    #[test]
+    fn stashes_untracked_and_staged_only_changes() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "content").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+
+        repo.write_file("new.txt", "untracked").unwrap();
+        create_stash_in(repo.path(), StashMode::IncludeUntracked).unwrap();
+        assert!(!repo.path().join("new.txt").exists());
+        apply_stash_in(repo.path(), 0).unwrap();
+        assert!(repo.path().join("new.txt").exists());
+        assert_eq!(fetch_stashes_in(repo.path()).unwrap().len(), 1);
+
+        repo.write_file("file.txt", "staged").unwrap();
+        repo.git(&["add", "file.txt"]).unwrap();
+        create_stash_in(repo.path(), StashMode::StagedOnly).unwrap();
+        let status = fetch_repo_status_in(repo.path());
+        assert!(status.changes.iter().all(|c| c.path != "file.txt"));
+        assert!(status.changes.iter().any(|c| c.path == "new.txt"));
+    }
+
+    #[test]
+    fn creates_branch_from_stash() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "content").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.write_file("file.txt", "stashed").unwrap();
+        create_stash_in(repo.path(), StashMode::TrackedChanges).unwrap();
+
+        branch_from_stash_in(repo.path(), "from-stash", 0).unwrap();
+
+        let info = fetch_branch_info_in(repo.path(), &AheadBehindCounts::new());
+        assert_eq!(info.current.as_deref(), Some("from-stash"));
+        let contents = fs::read_to_string(repo.path().join("file.txt")).unwrap();
+        assert_eq!(contents, "stashed");
+        assert!(fetch_stashes_in(repo.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_raw_and_numstat_commit_files() {
+        let files = parse_commit_files(
+            ":100644 100644 abc def M\tsrc/lib.rs\n:000000 100644 000 123 A\tlogo.png\n3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n",
+        );
+
+        assert_eq!(
+            files,
+            vec![
+                CommitFile {
+                    path: "src/lib.rs".into(),
+                    change: ChangeType::Modified,
+                    additions: Some(3),
+                    deletions: Some(1),
+                },
+                CommitFile {
+                    path: "logo.png".into(),
+                    change: ChangeType::Added,
+                    additions: None,
+                    deletions: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fetches_commit_details_with_files() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "one\ntwo\n").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        let root = git_output(&repo, &["rev-parse", "HEAD"]).trim().to_string();
+        repo.write_file("file.txt", "one\nthree\n").unwrap();
+        repo.write_file("new.txt", "new\n").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "second", "-m", "with a body"])
+            .unwrap();
+
+        let details = fetch_commit_details_in(repo.path(), "HEAD").unwrap();
+
+        assert_eq!(details.parents, vec![root.clone()]);
+        assert_eq!(details.message, "second\n\nwith a body");
+        assert!(!details.author.is_empty());
+        let summary: Vec<(&str, ChangeType, Option<usize>, Option<usize>)> = details
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.change, f.additions, f.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("file.txt", ChangeType::Modified, Some(1), Some(1)),
+                ("new.txt", ChangeType::Added, Some(1), Some(0)),
+            ]
+        );
+
+        let root_details = fetch_commit_details_in(repo.path(), &root).unwrap();
+        assert!(root_details.parents.is_empty());
+        assert_eq!(root_details.files.len(), 1);
+        assert_eq!(root_details.files[0].change, ChangeType::Added);
+    }
+
+    #[test]
+    fn diffs_commit_file_against_chosen_parent() {
+        let repo = TestRepo::init().unwrap();
+        repo.write_file("file.txt", "base\n").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "init"]).unwrap();
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        repo.write_file("other.txt", "feature\n").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "feature"]).unwrap();
+        repo.git(&["checkout", "main"]).unwrap();
+        repo.write_file("file.txt", "main\n").unwrap();
+        repo.git(&["commit", "-am", "main"]).unwrap();
+        repo.git(&["merge", "--no-ff", "feature", "-m", "merge"])
+            .unwrap();
+
+        let details = fetch_commit_details_in(repo.path(), "HEAD").unwrap();
+        assert_eq!(details.parents.len(), 2);
+        assert_eq!(details.files.len(), 1);
+        assert_eq!(details.files[0].path, "other.txt");
+
+        let diff = fetch_commit_file_diff_in(
+            repo.path(),
+            &details.id,
+            Some(&details.parents[0]),
+            "other.txt",
+        )
+        .unwrap();
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.hunks[0].lines[0].kind, DiffLineKind::Added);
+
+        let files =
+            fetch_commit_files_in(repo.path(), &details.id, Some(&details.parents[1])).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "file.txt");
+        let diff = fetch_commit_file_diff_in(
+            repo.path(),
+            &details.id,
+            Some(&details.parents[1]),
+            "file.txt",
+        )
+        .unwrap();
+        let changed: Vec<&str> = diff.hunks[0]
+            .lines
+            .iter()
+            .map(|line| line.content.as_str())
+            .collect();
+        assert_eq!(changed, vec!["base", "main"]);
+
+        let root = details.parents[0].clone();
+        let root = fetch_commit_details_in(repo.path(), &format!("{root}~1")).unwrap();
+        let diff = fetch_commit_file_diff_in(repo.path(), &root.id, None, "file.txt").unwrap();
+        assert_eq!(diff.hunks[0].new_lines, 1);
+    }
+
     fn create_bare_repo() -> Result<PathBuf, String> {
         let path = unique_path("remote");
         let status = Command::new("git")
@@ -0,0 +1,199 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::git::CancelToken;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+pub type JobId = u64;
+
+#[derive(Debug)]
+pub struct RunningJob {
+    pub id: JobId,
+    pub label: String,
+    pub started: Instant,
+    /// Quiet jobs, like the periodic refresh, run without showing up in the status box.
+    pub quiet: bool,
+    cancel: Option<CancelToken>,
+}
+
+impl RunningJob {
+    pub fn cancellable(&self) -> bool {
+        self.cancel.is_some()
+    }
+
+    pub fn spinner(&self) -> char {
+        spinner_frame(self.started.elapsed())
+    }
+}
+
+/// Runs git work on background threads and hands the results back to the UI thread.
+#[derive(Debug)]
+pub struct Jobs<T> {
+    sender: Sender<(JobId, T)>,
+    receiver: Receiver<(JobId, T)>,
+    running: Vec<RunningJob>,
+    next_id: JobId,
+}
+
+impl<T> Default for Jobs<T> {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            running: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<T: Send + 'static> Jobs<T> {
+    /// Starts `work` on its own thread. Cancellable jobs receive a token that
+    /// [`Jobs::cancel_all`] trips; the others get one that never fires.
+    pub fn spawn<F>(&mut self, label: impl Into<String>, cancellable: bool, work: F) -> JobId
+    where
+        F: FnOnce(&CancelToken) -> T + Send + 'static,
+    {
+        self.start(label.into(), cancellable, false, work)
+    }
+
+    pub fn spawn_quiet<F>(&mut self, label: impl Into<String>, work: F) -> JobId
+    where
+        F: FnOnce(&CancelToken) -> T + Send + 'static,
+    {
+        self.start(label.into(), false, true, work)
+    }
+
+    fn start<F>(&mut self, label: String, cancellable: bool, quiet: bool, work: F) -> JobId
+    where
+        F: FnOnce(&CancelToken) -> T + Send + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let token = CancelToken::default();
+        let sender = self.sender.clone();
+        let worker_token = token.clone();
+        thread::spawn(move || {
+            let output = work(&worker_token);
+            let _ = sender.send((id, output));
+        });
+
+        self.running.push(RunningJob {
+            id,
+            label,
+            started: Instant::now(),
+            quiet,
+            cancel: cancellable.then_some(token),
+        });
+        id
+    }
+
+    /// Collects the output of every job that finished since the last call, without blocking.
+    pub fn poll(&mut self) -> Vec<T> {
+        let mut finished = Vec::new();
+        while let Ok((id, output)) = self.receiver.try_recv() {
+            self.running.retain(|job| job.id != id);
+            finished.push(output);
+        }
+        finished
+    }
+}
+
+impl<T> Jobs<T> {
+    pub fn is_running(&self, id: JobId) -> bool {
+        self.running.iter().any(|job| job.id == id)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// Jobs worth showing to the user, oldest first.
+    pub fn visible(&self) -> impl Iterator<Item = &RunningJob> {
+        self.running.iter().filter(|job| !job.quiet)
+    }
+
+    /// Asks every cancellable job to stop and returns how many were asked.
+    pub fn cancel_all(&mut self) -> usize {
+        let tokens: Vec<&CancelToken> = self
+            .running
+            .iter()
+            .filter_map(|job| job.cancel.as_ref())
+            .filter(|token| !token.is_cancelled())
+            .collect();
+        for token in &tokens {
+            token.cancel();
+        }
+        tokens.len()
+    }
+}
+
+fn spinner_frame(elapsed: Duration) -> char {
+    SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for<T: Send + 'static>(jobs: &mut Jobs<T>) -> Vec<T> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let finished = jobs.poll();
+            if !finished.is_empty() || Instant::now() > deadline {
+                return finished;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn delivers_output_and_forgets_finished_jobs() {
+        let mut jobs = Jobs::default();
+        let id = jobs.spawn("Pushing", false, |_| 42);
+        assert!(jobs.is_running(id));
+
+        let finished = wait_for(&mut jobs);
+
+        assert_eq!(finished, vec![42]);
+        assert!(!jobs.is_running(id));
+        assert!(jobs.is_idle());
+    }
+
+    #[test]
+    fn cancel_all_trips_only_cancellable_jobs() {
+        let mut jobs = Jobs::default();
+        let (release, gate) = mpsc::channel::<()>();
+        jobs.spawn("Fetching", true, move |cancel| {
+            while !cancel.is_cancelled() {
+                thread::sleep(Duration::from_millis(5));
+            }
+            "cancelled"
+        });
+        jobs.spawn_quiet("Refreshing", move |_| {
+            let _ = gate.recv();
+            "refreshed"
+        });
+
+        assert_eq!(jobs.visible().count(), 1);
+        assert_eq!(jobs.cancel_all(), 1);
+        assert_eq!(jobs.cancel_all(), 0);
+
+        let finished = wait_for(&mut jobs);
+        assert_eq!(finished, vec!["cancelled"]);
+        release.send(()).unwrap();
+        assert_eq!(wait_for(&mut jobs), vec!["refreshed"]);
+    }
+
+    #[test]
+    fn spinner_advances_every_tenth_of_a_second() {
+        assert_eq!(spinner_frame(Duration::from_millis(0)), '⠋');
+        assert_eq!(spinner_frame(Duration::from_millis(150)), '⠙');
+        assert_eq!(spinner_frame(Duration::from_millis(1000)), '⠋');
+    }
+}
@@ -1,5 +1,5 @@
 use branch_input::BranchInput;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     DefaultTerminal, Frame,
     buffer::Buffer,
@@ -13,15 +13,17 @@ use std::{
 
 use crate::git::{BranchInfo, RepoStatus};
 use crate::regions::Region;
-use crate::ui::{branches, commits, details, popup, stashes, status};
+use crate::ui::{branches, commits, details, diff, popup, stashes, status, tags};
 use notification::{Notification, render_notification};
 
 mod app;
 mod branch_input;
 mod git;
+mod jobs;
 mod notification;
 mod regions;
 mod ui;
+mod watcher;
 
 fn main() -> io::Result<()> {
     let mut terminal = ratatui::init();
@@ -36,17 +38,45 @@ pub struct App {
     exit: bool,
     selected_branch: BranchInfo,
     commits: commits::CommitsState,
+    stashes: stashes::StashesState,
+    tags: tags::TagsState,
     hovered_commit_id: Option<String>,
+    details: details::DetailsState,
     branch_input: Option<BranchInput>,
+    rebase_plan: Option<ui::rebase::RebasePlan>,
+    merge_prompt: Option<ui::merge::MergePrompt>,
+    rebase_onto: Option<ui::rebase::RebaseOntoPrompt>,
+    conflicts: Option<ui::conflicts::ConflictsState>,
+    abort_prompt: Option<ui::operation::AbortPrompt>,
+    pick_prompt: Option<ui::cherry_pick::PickPrompt>,
+    reset_prompt: Option<ui::reset::ResetPrompt>,
+    tag_input: Option<tags::TagInput>,
+    remote_tag_delete_prompt: Option<tags::RemoteTagDeletePrompt>,
+    remotes: Option<ui::remotes::RemotesState>,
+    upstream_picker: Option<ui::upstream::UpstreamPicker>,
     repo_status: RepoStatus,
     last_refresh: Instant,
+    /// Timer refresh; short when no file watcher is available, otherwise only a fallback.
     refresh_interval: Duration,
+    watcher: Option<watcher::RepoWatcher>,
     notification: Option<Notification>,
     show_changes_popup: bool,
     popup_region: Region,
     selected_change: Option<usize>,
-    commit_input: ui::input::TextInput,
+    change_diff: diff::DiffState,
+    commit_input: ui::message_editor::MessageEditor,
     commit_message_editing: bool,
+    commit_amend: Option<popup::AmendState>,
+    jobs: jobs::Jobs<app::jobs::JobOutput>,
+    refresh_job: Option<jobs::JobId>,
+    refresh_queued: bool,
+    commit_job: Option<jobs::JobId>,
+    /// Bumped by every synchronous refresh and every change the app makes, so slower
+    /// background refreshes that read the older state are dropped and read again.
+    refresh_generation: u64,
+    /// Bumped when the commit list is reloaded on the UI thread, which only
+    /// makes a background refresh skip the commits it read.
+    commits_generation: u64,
 }
 
 impl Default for App {
@@ -56,19 +86,46 @@ impl Default for App {
             exit: false,
             selected_branch: BranchInfo::default(),
             commits: commits::CommitsState::default(),
+            stashes: stashes::StashesState::default(),
+            tags: tags::TagsState::default(),
             hovered_commit_id: None,
+            details: details::DetailsState::default(),
             branch_input: None,
+            rebase_plan: None,
+            merge_prompt: None,
+            pick_prompt: None,
+            reset_prompt: None,
+            tag_input: None,
+            remote_tag_delete_prompt: None,
+            remotes: None,
+            upstream_picker: None,
+            rebase_onto: None,
+            conflicts: None,
+            abort_prompt: None,
             repo_status: RepoStatus::default(),
             last_refresh: Instant::now(),
             refresh_interval: Duration::from_millis(1000),
+            watcher: None,
             notification: None,
             show_changes_popup: false,
             popup_region: Region::Changes,
             selected_change: None,
-            commit_input: ui::input::TextInput::default(),
+            change_diff: diff::DiffState::default(),
+            commit_input: ui::message_editor::MessageEditor::default(),
             commit_message_editing: false,
+            commit_amend: None,
+            jobs: jobs::Jobs::default(),
+            refresh_job: None,
+            refresh_queued: false,
+            commit_job: None,
+            refresh_generation: 0,
+            commits_generation: 0,
         };
         app.refresh_all();
+        if let Ok(watcher) = watcher::RepoWatcher::start(".") {
+            app.watcher = Some(watcher);
+            app.refresh_interval = Duration::from_secs(30);
+        }
         app
     }
 }
@@ -76,6 +133,7 @@ impl Default for App {
 impl App {
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
         while !self.exit {
+            self.poll_jobs();
             self.refresh_if_due();
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_events()?;
@@ -89,39 +147,131 @@ impl App {
 
     fn refresh_if_due(&mut self) {
         self.clear_expired_notification();
-        if self.last_refresh.elapsed() >= self.refresh_interval {
-            self.refresh_all();
-            self.last_refresh = Instant::now();
+        let changed = self.watcher.as_mut().is_some_and(|watcher| watcher.poll());
+        if changed || self.last_refresh.elapsed() >= self.refresh_interval {
+            self.refresh_now();
         }
     }
 
+    fn refresh_now(&mut self) {
+        self.spawn_refresh();
+        self.last_refresh = Instant::now();
+    }
+
     fn refresh_all(&mut self) {
         self.refresh_branches();
         self.refresh_status();
+        self.refresh_stashes();
+        self.refresh_tags();
         self.last_refresh = Instant::now();
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
-        if !event::poll(self.refresh_interval)? {
+        // Wake up often while jobs run so the spinner moves and results land promptly,
+        // and often enough to pick up file changes once they settle.
+        let timeout = if !self.jobs.is_idle()
+            || self
+                .watcher
+                .as_ref()
+                .is_some_and(|watcher| watcher.is_pending())
+        {
+            Duration::from_millis(100)
+        } else if self.watcher.is_some() {
+            Duration::from_millis(250)
+        } else {
+            self.refresh_interval
+        };
+        if !event::poll(timeout)? {
             return Ok(());
         }
 
-        if let Event::Key(key_event) = event::read()? {
-            if should_handle_key(&key_event) {
-                self.handle_key_event(key_event);
-            }
+        if let Event::Key(key_event) = event::read()?
+            && should_handle_key(&key_event)
+        {
+            self.handle_key_event(key_event);
         }
         Ok(())
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.code == KeyCode::Char('c')
+            && key_event.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            self.cancel_jobs();
+            return;
+        }
+
         if self.branch_input.is_some() {
             self.handle_branch_input_key(key_event);
             return;
         }
 
+        if self.abort_prompt.is_some() {
+            self.handle_abort_prompt_key(key_event.code);
+            return;
+        }
+
+        if self.conflicts.is_some() {
+            self.handle_conflicts_key(key_event.code);
+            return;
+        }
+
+        if self.merge_prompt.is_some() {
+            self.handle_merge_prompt_key(key_event.code);
+            return;
+        }
+
+        if self.pick_prompt.is_some() {
+            self.handle_pick_prompt_key(key_event.code);
+            return;
+        }
+
+        if self.reset_prompt.is_some() {
+            self.handle_reset_prompt_key(key_event.code);
+            return;
+        }
+
+        if self.tag_input.is_some() {
+            self.handle_tag_input_key(key_event.code);
+            return;
+        }
+
+        if self.remote_tag_delete_prompt.is_some() {
+            self.handle_remote_tag_delete_prompt_key(key_event.code);
+            return;
+        }
+
+        if self.remotes.is_some() {
+            self.handle_remotes_key(key_event.code);
+            return;
+        }
+
+        if self.upstream_picker.is_some() {
+            self.handle_upstream_picker_key(key_event.code);
+            return;
+        }
+
+        if self.rebase_onto.is_some() {
+            self.handle_rebase_onto_key(key_event.code);
+            return;
+        }
+
+        if self.rebase_plan.is_some() {
+            self.handle_rebase_plan_key(key_event);
+            return;
+        }
+
+        if self.commits.is_searching() {
+            self.handle_commit_search_key(key_event.code);
+            return;
+        }
+
         if self.show_changes_popup {
-            self.handle_popup_keys(key_event.code);
+            self.handle_popup_keys(key_event);
+            return;
+        }
+
+        if self.handle_operation_key(key_event.code) {
             return;
         }
 
@@ -131,14 +281,16 @@ impl App {
             KeyCode::Char('c') => self.select_region(Region::Commits),
             KeyCode::Char('d') => self.select_region(Region::Details),
             KeyCode::Char('s') => self.select_region(Region::Stashes),
-            KeyCode::Char('l') => {
-                self.show_changes_popup = true;
-                self.popup_region = Region::Changes;
-                self.ensure_change_selection();
-            }
+            KeyCode::Char('g') => self.select_region(Region::Tags),
+            KeyCode::Char('o') => self.open_remotes(),
+            KeyCode::Char('R') => self.refresh_now(),
+            KeyCode::Char('l') => self.open_changes_popup(),
             code => {
                 self.handle_branch_region_keys(code);
                 self.handle_commits_region_keys(code);
+                self.handle_details_region_keys(code);
+                self.handle_stashes_region_keys(code);
+                self.handle_tags_region_keys(code);
             }
         }
     }
@@ -151,12 +303,23 @@ impl App {
         self.selected_region = region;
     }
 
+    fn open_changes_popup(&mut self) {
+        self.show_changes_popup = true;
+        self.popup_region = Region::Changes;
+        self.ensure_change_selection();
+    }
+
     fn refresh_status(&mut self) {
+        self.refresh_generation += 1;
+        self.apply_status(git::fetch_repo_status());
+    }
+
+    fn apply_status(&mut self, status: RepoStatus) {
         let previous_selection = self
             .selected_change
             .and_then(|idx| self.repo_status.changes.get(idx))
-            .map(|change| change.path.clone());
-        self.repo_status = git::fetch_repo_status();
+            .map(|change| (change.path.clone(), change.staged));
+        self.repo_status = status;
         self.reselect_change(previous_selection);
     }
 
@@ -168,10 +331,10 @@ impl App {
     }
 
     fn clear_expired_notification(&mut self) {
-        if let Some(notification) = &self.notification {
-            if Instant::now() >= notification.expires_at {
-                self.notification = None;
-            }
+        if let Some(notification) = &self.notification
+            && Instant::now() >= notification.expires_at
+        {
+            self.notification = None;
         }
     }
 }
@@ -186,7 +349,9 @@ impl Widget for &App {
             .direction(Direction::Vertical)
             .constraints(vec![Constraint::Percentage(10), Constraint::Percentage(90)])
             .split(area);
-        status::StatusBox::new(&self.repo_status, self.selected_region).render(layout[0], buf);
+        status::StatusBox::new(&self.repo_status, self.selected_region)
+            .with_jobs(self.jobs.visible().collect())
+            .render(layout[0], buf);
 
         let outer_layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -195,7 +360,11 @@ impl Widget for &App {
 
         let left_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Percentage(70), Constraint::Percentage(30)])
+            .constraints(vec![
+                Constraint::Percentage(50),
+                Constraint::Percentage(20),
+                Constraint::Percentage(30),
+            ])
             .split(outer_layout[0]);
 
         let right_layout = Layout::default()
@@ -208,16 +377,21 @@ impl Widget for &App {
             &self.selected_branch,
         )
         .render(left_layout[0], buf);
+        tags::panel_with_child(
+            self.selected_region == Region::Tags,
+            tags::TagsView::new(&self.tags),
+        )
+        .render(left_layout[1], buf);
         stashes::panel_with_child(
             self.selected_region == Region::Stashes,
-            stashes::StashesView,
+            stashes::StashesView::new(&self.stashes),
         )
-        .render(left_layout[1], buf);
+        .render(left_layout[2], buf);
         commits::panel(self.selected_region == Region::Commits, &self.commits)
             .render(right_layout[0], buf);
         details::panel_with_child(
             self.selected_region == Region::Details,
-            details::DetailsView::new(self.commits.hovered_commit()),
+            details::DetailsView::new(&self.details, self.selected_region == Region::Details),
         )
         .render(right_layout[1], buf);
 
@@ -230,13 +404,61 @@ impl Widget for &App {
                 area,
                 buf,
                 self.popup_region,
-                &self.repo_status,
-                self.selected_change,
-                &self.commit_input,
-                self.commit_message_editing,
+                &popup::PopupContent {
+                    status: &self.repo_status,
+                    selected_change: self.selected_change,
+                    diff: &self.change_diff,
+                    commit_input: &self.commit_input,
+                    commit_message_editing: self.commit_message_editing,
+                    amend: self.commit_amend.as_ref(),
+                },
             );
         }
 
+        if let Some(conflicts) = &self.conflicts {
+            ui::conflicts::render_conflicts_popup(area, buf, conflicts);
+        }
+
+        if let Some(prompt) = &self.merge_prompt {
+            ui::merge::render_merge_popup(area, buf, prompt);
+        }
+
+        if let Some(prompt) = &self.rebase_onto {
+            ui::rebase::render_rebase_onto_popup(area, buf, prompt);
+        }
+
+        if let Some(prompt) = &self.pick_prompt {
+            ui::cherry_pick::render_pick_popup(area, buf, prompt);
+        }
+
+        if let Some(prompt) = &self.reset_prompt {
+            ui::reset::render_reset_popup(area, buf, prompt);
+        }
+
+        if let Some(input) = &self.tag_input {
+            tags::render_tag_popup(area, buf, input);
+        }
+
+        if let Some(prompt) = &self.remote_tag_delete_prompt {
+            tags::render_remote_tag_delete_popup(area, buf, prompt);
+        }
+
+        if let Some(remotes) = &self.remotes {
+            ui::remotes::render_remotes_popup(area, buf, remotes);
+        }
+
+        if let Some(picker) = &self.upstream_picker {
+            ui::upstream::render_upstream_popup(area, buf, picker);
+        }
+
+        if let Some(plan) = &self.rebase_plan {
+            ui::rebase::render_rebase_popup(area, buf, plan);
+        }
+
+        if let Some(prompt) = &self.abort_prompt {
+            ui::operation::render_abort_popup(area, buf, prompt);
+        }
+
         if let Some(notification) = &self.notification {
             render_notification(area, buf, notification);
         }
@@ -244,45 +466,35 @@ impl Widget for &App {
 }
 
 impl App {
-    fn handle_popup_keys(&mut self, code: KeyCode) {
+    fn handle_popup_keys(&mut self, key_event: KeyEvent) {
+        let code = key_event.code;
+        if self.popup_region == Region::CommitMessage && self.commit_message_editing {
+            match code {
+                KeyCode::Esc => self.commit_message_editing = false,
+                _ => self.handle_commit_message_key(key_event),
+            }
+            return;
+        }
+
         if let KeyCode::Char('q') = code {
             self.show_changes_popup = false;
             return;
         }
 
         if self.popup_region == Region::CommitMessage {
-            if self.commit_message_editing {
-                match code {
-                    KeyCode::Esc => {
-                        self.commit_message_editing = false;
-                        return;
-                    }
-                    _ => {
-                        self.handle_commit_message_key(code);
-                        return;
-                    }
-                }
-            } else {
-                match code {
-                    KeyCode::Char('m') => {
-                        self.commit_message_editing = true;
-                        return;
-                    }
-                    KeyCode::Char('c') => {
-                        self.popup_region = Region::Changes;
-                        return;
-                    }
-                    KeyCode::Char('v') => {
-                        self.popup_region = Region::ChangeViewer;
-                        return;
-                    }
-                    KeyCode::Esc => {
-                        self.show_changes_popup = false;
-                        return;
-                    }
-                    _ => return,
-                }
+            match code {
+                KeyCode::Char('m') => self.commit_message_editing = true,
+                KeyCode::Char('a') => self.toggle_amend(),
+                KeyCode::Char('c') => self.popup_region = Region::Changes,
+                KeyCode::Char('v') => self.popup_region = Region::ChangeViewer,
+                KeyCode::Esc => self.show_changes_popup = false,
+                _ => {}
             }
+            return;
+        }
+
+        if self.handle_operation_key(code) {
+            return;
         }
 
         match code {
@@ -299,8 +511,10 @@ impl App {
             _ => {}
         }
 
-        if self.popup_region == Region::Changes {
-            self.handle_changes_popup_key(code);
+        match self.popup_region {
+            Region::Changes => self.handle_changes_popup_key(code),
+            Region::ChangeViewer => self.handle_change_viewer_key(code),
+            _ => {}
         }
     }
 }
@@ -6,6 +6,7 @@ pub enum Region {
     Commits,
     Details,
     Branches,
+    Tags,
     Stashes,
     Changes,
     ChangeViewer,
@@ -18,6 +19,7 @@ impl Region {
             Region::Commits => "[c] Commits",
             Region::Branches => "[b] Branches",
             Region::Details => "[d] Details",
+            Region::Tags => "[g] Tags",
             Region::Stashes => "[s] Stashes",
             Region::Changes => "[c] Changes",
             Region::ChangeViewer => "[v] Change viewer",
@@ -32,13 +34,71 @@ impl Region {
                 "[Enter] checkout",
                 "[u] update",
                 "[p] push",
+                "[m] merge",
+                "[r] rebase onto",
                 "[a] add",
+                "[B] branch from hovered",
+                "[n] rename",
                 "[x] delete",
+                "[t] set upstream",
+                "[I] compare against",
+            ],
+            Region::Commits => vec![
+                "[↑↓] move",
+                "[PgUp/PgDn] page",
+                "[Home/End] first/last",
+                "[/] search",
+                "[n/N] next/prev match",
+                "[Esc] clear marks/search",
+                "[i] rebase onto",
+                "[Space] mark",
+                "[p] cherry-pick",
+                "[v] revert",
+                "[X] reset to here",
+                "[t] tag",
+                "[B] branch here",
+            ],
+            Region::Changes => vec![
+                "[↑↓] move",
+                "[Enter] stage/unstage or resolve conflict",
+                "[x] discard",
+            ],
+            Region::ChangeViewer => vec![
+                "[↑↓] move",
+                "[n/p] hunk",
+                "[Space] mark lines",
+                "[Enter] stage/unstage",
+                "[x] discard",
+            ],
+            Region::CommitMessage => vec![
+                "[m] edit",
+                "[Enter] new line",
+                "[Ctrl+S] commit",
+                "[Esc] stop",
+                "[a] toggle amend",
+            ],
+            Region::Tags => vec![
+                "[↑↓] move",
+                "[p] push",
+                "[x] delete",
+                "[X] delete locally and on remote",
+            ],
+            Region::Stashes => vec![
+                "[↑↓] move",
+                "[Enter] apply",
+                "[p] pop",
+                "[x] drop",
+                "[n] stash",
+                "[u] stash +untracked",
+                "[i] stash staged",
+                "[t] to branch",
+            ],
+            Region::Details => vec![
+                "[↑↓] move/scroll",
+                "[Enter] file diff",
+                "[Esc] back",
+                "[p] next parent",
             ],
-            Region::Commits => vec!["[↑↓] move"],
-            Region::Changes => vec!["[↑↓] move", "[Enter] stage/unstage", "[x] discard"],
-            Region::CommitMessage => vec!["[Enter] commit", "[Esc] stop"],
-            Region::Details | Region::Stashes | Region::ChangeViewer => Vec::new(),
         }
     }
 
@@ -1,5 +1,3 @@
-use std::mem;
-
 use crossterm::event::KeyCode;
 use ratatui::{
     buffer::Buffer,
@@ -11,14 +9,7 @@ use ratatui::{
 
 use crate::git::{self, BranchInfo, BranchSummary};
 use crate::regions::Region;
-
-use super::panel::PanelBlock;
-
-pub type BranchesPanel<W = super::panel::Empty> = PanelBlock<W>;
-
-pub fn panel_with_child<W: Widget>(selected: bool, child: W) -> BranchesPanel<W> {
-    PanelBlock::with_child(Region::Branches, selected, child)
-}
+use crate::ui::layout::viewport;
 
 pub fn panel(selected: bool, info: &BranchInfo) -> BranchPanel<'_> {
     BranchPanel { info, selected }
@@ -36,7 +27,7 @@ impl Widget for BranchPanel<'_> {
             .border_set(ratatui::symbols::border::THICK);
         let inner = base_block.inner(area);
 
-        let title = if let (Some(hovered), true) = (
+        let mut title = if let (Some(hovered), true) = (
             self.info.hovered,
             self.info.branches.len() > inner.height as usize,
         ) {
@@ -49,6 +40,9 @@ impl Widget for BranchPanel<'_> {
         } else {
             Region::Branches.as_str().to_string()
         };
+        if let Some(integration) = &self.info.integration_branch {
+            title.push_str(&format!(" · vs {integration}"));
+        }
 
         let block = base_block.title(title);
         block.render(area, buf);
@@ -68,14 +62,17 @@ pub fn handle_key(info: &mut BranchInfo, key: KeyCode) -> Option<String> {
         }
         KeyCode::Enter => checkout_hovered(info),
         KeyCode::Char('x') | KeyCode::Delete => delete_hovered(info),
-        KeyCode::Char('u') => update_branches(info),
-        KeyCode::Char('p') => push_current_branch(info),
         _ => None,
     }
 }
 
 pub fn refresh(prev: BranchInfo) -> BranchInfo {
-    let mut current = git::fetch_branch_info();
+    let current = git::fetch_branch_info(&prev.counts);
+    apply(prev, current)
+}
+
+/// Carries the hover and selection of `prev` over to freshly fetched branch info.
+pub fn apply(prev: BranchInfo, mut current: BranchInfo) -> BranchInfo {
     current.hovered = preferred_hover_index(&current, prev.hovered);
     current.selected = prev.selected.filter(|selected| {
         current
@@ -109,185 +106,97 @@ fn move_hover_down(info: &mut BranchInfo) {
 }
 
 fn checkout_hovered(info: &mut BranchInfo) -> Option<String> {
-    if let Some(index) = info.hovered {
-        if let Some(branch) = info.branches.get(index).cloned() {
-            let checkout_result = if branch.has_local {
-                git::checkout_branch(&branch.name).map(|_| branch.name.clone())
-            } else if branch.has_remote {
-                if let Some(remote_ref) = branch.remote_ref.as_ref() {
-                    git::checkout_remote_branch(remote_ref)
-                } else {
-                    return Some("Missing remote reference".to_string());
-                }
+    if let Some(index) = info.hovered
+        && let Some(branch) = info.branches.get(index).cloned()
+    {
+        let checkout_result = if branch.has_local {
+            git::checkout_branch(&branch.name).map(|_| branch.name.clone())
+        } else if branch.has_remote {
+            if let Some(remote_ref) = branch.remote_ref.as_ref() {
+                git::checkout_remote_branch(remote_ref)
             } else {
-                return Some("Missing branch reference".to_string());
-            };
-
-            match checkout_result {
-                Ok(_) => {
-                    let previous = std::mem::take(info);
-                    let mut refreshed = refresh(previous);
-                    refreshed.selected = refreshed.current.clone();
-                    *info = refreshed;
-                    return info
-                        .current
-                        .as_ref()
-                        .map(|name| format!("Switched to {name}"));
-                }
-                Err(err) => return Some(format!("Checkout failed: {err}")),
+                return Some("Missing remote reference".to_string());
             }
-        }
-    }
-
-    None
-}
-
-fn delete_hovered(info: &mut BranchInfo) -> Option<String> {
-    if let Some(index) = info.hovered {
-        if let Some(branch) = info.branches.get(index).cloned() {
-            if branch.has_remote {
-                let (remote_ref, local_name) = if let Some(remote_ref) = branch.remote_ref.as_deref()
-                {
-                    let Some((_, local_name)) = split_remote_ref(remote_ref) else {
-                        return Some("Invalid remote branch name".to_string());
-                    };
-                    (Some(remote_ref), local_name)
-                } else {
-                    (None, branch.name.as_str())
-                };
-                let remote_label = remote_ref.unwrap_or("remote");
-
-                if info.current.as_deref() == Some(local_name) {
-                    return Some("Cannot delete the current branch".to_string());
-                }
-
-                let mut remote_missing = false;
-                if let Some(remote_ref) = remote_ref {
-                    if let Err(err) = git::delete_remote_branch(remote_ref) {
-                        let err_lower = err.to_lowercase();
-                        remote_missing = err_lower.contains("remote ref does not exist");
-                        if !remote_missing {
-                            return Some(format!("Delete failed: {err}"));
-                        }
-                    }
-                    let _ = git::delete_remote_tracking_ref(remote_ref);
-                } else {
-                    remote_missing = true;
-                }
-
-                let mut deleted_local = false;
-                let has_local = branch.has_local || git::local_branch_exists(local_name);
-                if has_local {
-                    if let Err(err) = git::delete_branch(local_name) {
-                        return Some(format!(
-                            "Deleted {remote_label}, but failed to delete {local_name}: {err}"
-                        ));
-                    }
-                    deleted_local = true;
-                }
+        } else {
+            return Some("Missing branch reference".to_string());
+        };
 
+        match checkout_result {
+            Ok(_) => {
                 let previous = std::mem::take(info);
-                *info = refresh(previous);
-                if remote_missing {
-                    if deleted_local {
-                        return Some(format!("Remote already gone; deleted {local_name}"));
-                    }
-                    return Some("Remote already gone".to_string());
-                }
-                if deleted_local {
-                    if let Some(_) = remote_ref {
-                        return Some(format!("Deleted {remote_label} and {local_name}"));
-                    }
-                    return Some(format!("Deleted {local_name}"));
-                }
-                if let Some(_) = remote_ref {
-                    return Some(format!("Deleted {remote_label}"));
-                }
-                return Some(format!("Deleted {local_name}"));
-            }
-
-            if info.current.as_deref() == Some(branch.name.as_str()) {
-                return Some("Cannot delete the current branch".to_string());
-            }
-
-            match git::delete_branch(&branch.name) {
-                Ok(()) => {
-                    let previous = std::mem::take(info);
-                    *info = refresh(previous);
-                    return Some(format!("Deleted {}", branch.name));
-                }
-                Err(err) => {
-                    return Some(format!("Delete failed: {err}"));
-                }
+                let mut refreshed = refresh(previous);
+                refreshed.selected = refreshed.current.clone();
+                *info = refreshed;
+                return info
+                    .current
+                    .as_ref()
+                    .map(|name| format!("Switched to {name}"));
             }
+            Err(err) => return Some(format!("Checkout failed: {err}")),
         }
     }
 
     None
 }
 
-fn split_remote_ref(remote_ref: &str) -> Option<(&str, &str)> {
-    let mut parts = remote_ref.splitn(2, '/');
-    let remote = parts.next()?;
-    let branch = parts.next()?;
-    if remote.trim().is_empty() || branch.trim().is_empty() {
-        return None;
-    }
-    Some((remote, branch))
+/// Deleting a branch that lives on a remote pushes the delete, which runs as a
+/// job; see [`hovered_remote_delete`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteDelete {
+    pub remote_ref: String,
+    pub local_name: String,
+    /// Whether a local branch is known to go along with the remote one.
+    pub has_local: bool,
 }
 
-fn update_branches(info: &mut BranchInfo) -> Option<String> {
-    let fetch_result = git::fetch_remotes();
-    let mut previous = mem::take(info);
-    previous.status = None;
-    *info = refresh(previous);
-
-    if let Err(err) = fetch_result {
-        return Some(err);
-    }
-
-    if info.current.is_some() {
-        pull_current_branch(info)
-    } else {
-        Some("Fetched remote branches".to_string())
+/// The remote delete the hovered branch needs, or `None` when deleting it
+/// stays local and [`handle_key`] does it right away.
+pub fn hovered_remote_delete(info: &BranchInfo) -> Option<Result<RemoteDelete, String>> {
+    let branch = info.hovered.and_then(|index| info.branches.get(index))?;
+    let remote_ref = branch.remote_ref.as_deref().filter(|_| branch.has_remote)?;
+    let Some((_, local_name)) = split_remote_ref(remote_ref) else {
+        return Some(Err("Invalid remote branch name".to_string()));
+    };
+    if info.current.as_deref() == Some(local_name) {
+        return Some(Err("Cannot delete the current branch".to_string()));
     }
+    Some(Ok(RemoteDelete {
+        remote_ref: remote_ref.to_string(),
+        local_name: local_name.to_string(),
+        has_local: branch.has_local,
+    }))
 }
 
-fn pull_current_branch(info: &mut BranchInfo) -> Option<String> {
-    let Some(current) = info.current.clone() else {
-        return Some("No current branch to update".to_string());
-    };
+fn delete_hovered(info: &mut BranchInfo) -> Option<String> {
+    let branch = info
+        .hovered
+        .and_then(|index| info.branches.get(index))?
+        .clone();
+    if info.current.as_deref() == Some(branch.name.as_str()) {
+        return Some("Cannot delete the current branch".to_string());
+    }
 
-    match git::pull_current_branch() {
-        Ok(()) => refresh_after_remote_action(info),
-        Err(err) => {
-            let message = format!("Update {current} failed: {err}");
-            Some(message)
-        }
+    // A remote branch without a remote ref left is already gone there.
+    if branch.has_remote && !branch.has_local && !git::local_branch_exists(&branch.name) {
+        return Some("Remote already gone".to_string());
     }
+    let message = match git::delete_branch(&branch.name) {
+        Ok(()) if branch.has_remote => format!("Remote already gone; deleted {}", branch.name),
+        Ok(()) => format!("Deleted {}", branch.name),
+        Err(err) => return Some(format!("Delete failed: {err}")),
+    };
+    let previous = std::mem::take(info);
+    *info = refresh(previous);
+    Some(message)
 }
 
-fn push_current_branch(info: &mut BranchInfo) -> Option<String> {
-    let Some(current) = info.current.clone() else {
-        return Some("No current branch to push".to_string());
-    };
+fn split_remote_ref(remote_ref: &str) -> Option<(&str, &str)> {
+    let (remote, branch) = remote_ref.split_once('/')?;
 
-    match git::push_current_branch() {
-        Ok(()) => refresh_after_remote_action(info),
-        Err(err) => {
-            let message = format!("Push {current} failed: {err}");
-            Some(message)
-        }
+    if remote.trim().is_empty() || branch.trim().is_empty() {
+        return None;
     }
-}
-
-fn refresh_after_remote_action(info: &mut BranchInfo) -> Option<String> {
-    let mut previous = mem::take(info);
-    previous.status = None;
-    *info = refresh(previous);
-    info.current
-        .as_ref()
-        .map(|branch| format!("Updated {}", branch))
+    Some((remote, branch))
 }
 
 fn preferred_hover_index(info: &BranchInfo, previous: Option<usize>) -> Option<usize> {
@@ -299,14 +208,13 @@ fn preferred_hover_index(info: &BranchInfo, previous: Option<usize>) -> Option<u
         return Some(previous.min(info.branches.len().saturating_sub(1)));
     }
 
-    if let Some(current_name) = &info.current {
-        if let Some(index) = info
+    if let Some(current_name) = &info.current
+        && let Some(index) = info
             .branches
             .iter()
             .position(|branch| &branch.name == current_name)
-        {
-            return Some(index);
-        }
+    {
+        return Some(index);
     }
 
     Some(0)
@@ -317,6 +225,7 @@ pub struct BranchList<'a> {
     current: Option<&'a str>,
     hovered: Option<usize>,
     selected: Option<&'a str>,
+    status: Option<&'a str>,
 }
 
 impl<'a> BranchList<'a> {
@@ -326,12 +235,20 @@ impl<'a> BranchList<'a> {
             current: info.current.as_deref(),
             hovered: info.hovered,
             selected: info.selected.as_deref(),
+            status: info.status.as_deref(),
         }
     }
 }
 
 impl Widget for BranchList<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(status) = self.status {
+            Paragraph::new(status)
+                .style(Style::default().fg(Color::Red))
+                .render(area, buf);
+            return;
+        }
+
         if self.branches.is_empty() {
             Paragraph::new("No branches found").render(area, buf);
             return;
@@ -356,7 +273,7 @@ impl Widget for BranchList<'_> {
                 let indicator_len = visible_width(&indicator);
                 let width = area.width as usize;
                 let prefix_len = visible_width(&prefix);
-                let available_name = width.saturating_sub(prefix_len + indicator_len + 2).max(0);
+                let available_name = width.saturating_sub(prefix_len + indicator_len + 2);
                 let display_name = truncate_with_ellipsis(&branch.name, available_name);
                 let name_len = visible_width(&display_name);
                 let padding = " ".repeat(available_name.saturating_sub(name_len));
@@ -394,21 +311,6 @@ impl Widget for BranchList<'_> {
     }
 }
 
-fn viewport(len: usize, hovered: Option<usize>, height: u16) -> (usize, usize) {
-    if len == 0 || height == 0 {
-        return (0, 0);
-    }
-    let visible = height as usize;
-    let focus = hovered.unwrap_or(0).min(len.saturating_sub(1));
-    if len <= visible {
-        return (0, len);
-    }
-    let max_start = len - visible;
-    let start = focus.saturating_sub(visible / 2).min(max_start);
-    let end = start + visible;
-    (start, end)
-}
-
 fn visible_width(text: &str) -> usize {
     text.chars().count()
 }
@@ -432,15 +334,32 @@ fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
     truncated
 }
 
+/// Ahead/behind counts against the upstream, then against the integration branch.
 fn format_indicator(branch: &BranchSummary) -> String {
     let ahead = branch.ahead.unwrap_or(0);
     let behind = branch.behind.unwrap_or(0);
-    format!("↑{ahead} ↓{behind}")
+    let upstream = match &branch.upstream {
+        Some(upstream) => Some(format!("{upstream} ↑{ahead} ↓{behind}")),
+        None if branch.ahead.is_none() && branch.integration.is_some() => None,
+        None => Some(format!("↑{ahead} ↓{behind}")),
+    };
+    let integration = branch.integration.as_ref().map(|integration| {
+        format!(
+            "{} ↑{} ↓{}",
+            integration.target, integration.ahead, integration.behind
+        )
+    });
+    [upstream, integration]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" · ")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::AheadBehind;
 
     fn make_info(names: &[&str], current: Option<&str>) -> BranchInfo {
         BranchInfo {
@@ -453,15 +372,43 @@ mod tests {
                     has_local: true,
                     has_remote: false,
                     remote_ref: None,
+                    upstream: None,
+                    integration: None,
                 })
                 .collect(),
             current: current.map(str::to_string),
+            integration_branch: None,
             status: None,
             hovered: None,
             selected: None,
+            counts: Default::default(),
         }
     }
 
+    #[test]
+    fn only_branches_on_a_remote_need_a_remote_delete() {
+        let mut info = make_info(&["main", "feature"], Some("main"));
+        info.hovered = Some(1);
+        assert_eq!(hovered_remote_delete(&info), None);
+
+        info.branches[1].has_remote = true;
+        info.branches[1].remote_ref = Some("origin/feature".into());
+        assert_eq!(
+            hovered_remote_delete(&info),
+            Some(Ok(RemoteDelete {
+                remote_ref: "origin/feature".into(),
+                local_name: "feature".into(),
+                has_local: true,
+            }))
+        );
+
+        info.current = Some("feature".into());
+        assert_eq!(
+            hovered_remote_delete(&info),
+            Some(Err("Cannot delete the current branch".to_string()))
+        );
+    }
+
     #[test]
     fn truncates_and_adds_ellipsis() {
         assert_eq!(
@@ -481,6 +428,8 @@ mod tests {
             has_local: true,
             has_remote: false,
             remote_ref: None,
+            upstream: None,
+            integration: None,
         };
         assert_eq!(format_indicator(&branch), "↑2 ↓1");
 
@@ -493,6 +442,48 @@ mod tests {
         assert_eq!(format_indicator(&branch), "↑0 ↓0");
     }
 
+    #[test]
+    fn indicator_names_the_upstream() {
+        let branch = BranchSummary {
+            name: "feature".into(),
+            ahead: Some(3),
+            behind: Some(0),
+            has_local: true,
+            has_remote: true,
+            remote_ref: Some("origin/feature".into()),
+            upstream: Some("upstream/main".into()),
+            integration: None,
+        };
+        assert_eq!(format_indicator(&branch), "upstream/main ↑3 ↓0");
+    }
+
+    #[test]
+    fn indicator_shows_upstream_and_integration_counts() {
+        let mut branch = BranchSummary {
+            name: "feature".into(),
+            ahead: Some(1),
+            behind: Some(0),
+            has_local: true,
+            has_remote: true,
+            remote_ref: Some("origin/feature".into()),
+            upstream: Some("origin/feature".into()),
+            integration: Some(AheadBehind {
+                target: "develop".into(),
+                ahead: 4,
+                behind: 2,
+            }),
+        };
+        assert_eq!(
+            format_indicator(&branch),
+            "origin/feature ↑1 ↓0 · develop ↑4 ↓2"
+        );
+
+        branch.upstream = None;
+        branch.ahead = None;
+        branch.behind = None;
+        assert_eq!(format_indicator(&branch), "develop ↑4 ↓2");
+    }
+
     #[test]
     fn indicator_defaults_for_remote_branch() {
         let branch = BranchSummary {
@@ -502,6 +493,8 @@ mod tests {
             has_local: false,
             has_remote: true,
             remote_ref: Some("origin/feature".into()),
+            upstream: None,
+            integration: None,
         };
         assert_eq!(format_indicator(&branch), "↑1 ↓1");
     }
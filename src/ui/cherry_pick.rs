@@ -0,0 +1,94 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::Commit;
+use crate::ui::layout::centered_rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickAction {
+    CherryPick,
+    Revert,
+}
+
+/// Confirms applying or reverting `commits` (oldest first) on the current branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickPrompt {
+    pub action: PickAction,
+    pub commits: Vec<Commit>,
+    pub target: String,
+    /// Whether cherry-picked messages record the original commit, as `-x` does.
+    pub record_origin: bool,
+}
+
+impl PickPrompt {
+    pub fn ids(&self) -> Vec<String> {
+        self.commits
+            .iter()
+            .map(|commit| commit.id.clone())
+            .collect()
+    }
+
+    pub fn count_label(&self) -> String {
+        if self.commits.len() == 1 {
+            "1 commit".to_string()
+        } else {
+            format!("{} commits", self.commits.len())
+        }
+    }
+}
+
+pub fn render_pick_popup(area: Rect, buf: &mut Buffer, prompt: &PickPrompt) {
+    let popup_area = centered_rect(60, 40, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let title = match prompt.action {
+        PickAction::CherryPick => "Cherry-pick",
+        PickAction::Revert => "Revert",
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw(format!("{title} {} on ", prompt.count_label())),
+            Span::styled(prompt.target.clone(), Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(""),
+    ];
+    lines.extend(prompt.commits.iter().map(|commit| {
+        Line::from(vec![
+            Span::styled(
+                format!("{} ", commit.id),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(commit.summary.clone(), Style::default().fg(Color::White)),
+        ])
+    }));
+
+    let mut keys = vec![Span::styled("[Enter] Run", key), Span::raw("   ")];
+    if prompt.action == PickAction::CherryPick {
+        let state = if prompt.record_origin { "on" } else { "off" };
+        keys.extend([
+            Span::styled(format!("[x] -x provenance: {state}"), key),
+            Span::raw("   "),
+        ]);
+    }
+    keys.push(Span::styled("[Esc] Cancel", key));
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(Line::from(Span::styled(title, key)))
+                .title_bottom(Line::from(keys))
+                .borders(Borders::ALL)
+                .border_set(border::THICK)
+                .style(Style::default().fg(Color::Green)),
+        )
+        .render(popup_area, buf);
+}
@@ -0,0 +1,123 @@
+use std::ops::Range;
+
+use crate::git::{Commit, LogFilter};
+
+/// A `/` search over the commit list. `author:`, `path:`, `since:` and `until:`
+/// terms narrow the log itself; the remaining words are matched against commit
+/// summaries and hash prefixes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CommitQuery {
+    /// The query as typed, shown in the panel title and when searching again.
+    pub source: String,
+    pub text: Option<String>,
+    pub filter: LogFilter,
+}
+
+impl CommitQuery {
+    pub fn parse(input: &str) -> Self {
+        let mut filter = LogFilter::default();
+        let mut words = Vec::new();
+        for word in input.split_whitespace() {
+            let (slot, value) = match word.split_once(':') {
+                Some(("author", value)) => (&mut filter.author, value),
+                Some(("path", value)) => (&mut filter.path, value),
+                Some(("since", value)) => (&mut filter.since, value),
+                Some(("until", value)) => (&mut filter.until, value),
+                _ => {
+                    words.push(word);
+                    continue;
+                }
+            };
+            if !value.is_empty() {
+                *slot = Some(value.to_string());
+            }
+        }
+        Self {
+            source: input.trim().to_string(),
+            text: (!words.is_empty()).then(|| words.join(" ")),
+            filter,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none() && self.filter.is_empty()
+    }
+
+    pub fn matches(&self, commit: &Commit) -> bool {
+        self.id_matches(&commit.id) || !self.summary_matches(&commit.summary).is_empty()
+    }
+
+    pub fn id_matches(&self, id: &str) -> bool {
+        self.text
+            .as_deref()
+            .is_some_and(|text| !text.contains(' ') && starts_with_ignore_case(id, text))
+    }
+
+    /// Byte ranges of `summary` matching the search text, ignoring ASCII case.
+    pub fn summary_matches(&self, summary: &str) -> Vec<Range<usize>> {
+        let Some(text) = self.text.as_deref() else {
+            return Vec::new();
+        };
+        let haystack = summary.to_ascii_lowercase();
+        let needle = text.to_ascii_lowercase();
+        haystack
+            .match_indices(&needle)
+            .map(|(start, found)| start..start + found.len())
+            .collect()
+    }
+}
+
+fn starts_with_ignore_case(value: &str, prefix: &str) -> bool {
+    value
+        .get(..prefix.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(id: &str, summary: &str) -> Commit {
+        Commit {
+            id: id.to_string(),
+            summary: summary.to_string(),
+            branches: Vec::new(),
+            parents: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_filter_terms_and_free_text() {
+        let query =
+            CommitQuery::parse("  login fix author:jane path:src/auth.rs since:1.week.ago ");
+
+        assert_eq!(
+            query.source,
+            "login fix author:jane path:src/auth.rs since:1.week.ago"
+        );
+        assert_eq!(query.text.as_deref(), Some("login fix"));
+        assert_eq!(query.filter.author.as_deref(), Some("jane"));
+        assert_eq!(query.filter.path.as_deref(), Some("src/auth.rs"));
+        assert_eq!(query.filter.since.as_deref(), Some("1.week.ago"));
+        assert_eq!(query.filter.until, None);
+        assert!(CommitQuery::parse("   ").is_empty());
+        assert!(CommitQuery::parse("path:").is_empty());
+    }
+
+    #[test]
+    fn matches_summary_text_and_hash_prefix() {
+        let query = CommitQuery::parse("Auth");
+
+        assert!(query.matches(&commit("1234abc", "Fix auth token refresh")));
+        assert_eq!(
+            query.summary_matches("auth: keep AUTH header"),
+            vec![0..4, 11..15]
+        );
+        assert!(!query.matches(&commit("1234abc", "Update readme")));
+
+        let by_hash = CommitQuery::parse("12AB");
+        assert!(by_hash.matches(&commit("12abcde", "Update readme")));
+        assert!(!by_hash.matches(&commit("912abcd", "Update readme")));
+    }
+}
@@ -1,52 +1,183 @@
+use std::{cell::Cell, ops::Range};
+
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{List, ListItem, Paragraph, Widget},
 };
 
-use crate::git::{self, Commit};
+use crate::git::{self, Commit, LogCursor, LogFilter};
 use crate::regions::Region;
+use crate::ui::layout::viewport;
 
-use super::panel::PanelBlock;
-
-pub type CommitsPanel<W = super::panel::Empty> = PanelBlock<W>;
-
-pub fn panel_with_child<W: Widget>(selected: bool, child: W) -> CommitsPanel<W> {
-    PanelBlock::with_child(Region::Commits, selected, child)
-}
+use super::commit_search::CommitQuery;
+use super::graph::{GraphLanes, GraphRow, graph_spans};
+use super::input::TextInput;
 
 pub fn panel(selected: bool, state: &CommitsState) -> CommitsPanelWidget<'_> {
     CommitsPanelWidget { state, selected }
 }
 
-#[derive(Debug, Default)]
+/// Commits read from the log per page.
+pub const PAGE_SIZE: usize = 200;
+/// The next page loads once the hover gets this close to the last loaded commit.
+const LOAD_AHEAD: usize = 50;
+/// Pages a single `n` loads looking for the next match before giving up.
+pub const SEARCH_PAGES: usize = 5;
+
+#[derive(Debug)]
 pub struct CommitsState {
     pub commits: Vec<Commit>,
     pub status: Option<String>,
     pub hovered: Option<usize>,
+    /// Ref tips the commit list was built from, see [`git::fetch_ref_tips`].
+    pub ref_tips: Option<String>,
+    /// Graph lanes for each commit, laid out a page at a time. Empty when the
+    /// filter hides ancestry, see [`LogFilter::hides_ancestry`].
+    pub graph: Vec<GraphRow>,
+    /// The lanes still open below the last loaded commit.
+    lanes: GraphLanes,
+    /// Where the next page of the log starts.
+    cursor: LogCursor,
+    /// How many commits were requested from the log; grows a page at a time.
+    pub limit: usize,
+    /// The applied `/` search; its filter terms shape the log that was loaded.
+    pub query: CommitQuery,
+    /// The search prompt while it is open.
+    pub search_input: Option<TextInput>,
+    /// Ids of the commits marked for a cherry-pick or revert.
+    pub marked: Vec<String>,
+    /// Rows the list showed when last drawn, which is how far a page moves.
+    list_height: Cell<usize>,
+}
+
+impl Default for CommitsState {
+    fn default() -> Self {
+        Self {
+            commits: Vec::new(),
+            status: None,
+            hovered: None,
+            ref_tips: None,
+            graph: Vec::new(),
+            lanes: GraphLanes::default(),
+            cursor: LogCursor::default(),
+            limit: PAGE_SIZE,
+            query: CommitQuery::default(),
+            search_input: None,
+            marked: Vec::new(),
+            list_height: Cell::new(0),
+        }
+    }
+}
+
+/// Commit data read from git, possibly off the UI thread. `commits` is `None`
+/// when the ref tips still match the ones the current list was built from.
+#[derive(Debug)]
+pub struct CommitsFetch {
+    ref_tips: Option<String>,
+    limit: usize,
+    commits: Option<Result<Vec<Commit>, String>>,
+    cursor: LogCursor,
+    /// Whether `commits` follow on from the current list rather than replace it.
+    next_page: bool,
+}
+
+impl CommitsFetch {
+    pub fn load(known_ref_tips: Option<&str>, limit: usize, filter: &LogFilter) -> Self {
+        let ref_tips = git::fetch_ref_tips().ok();
+        let mut cursor = LogCursor::default();
+        let commits = if ref_tips.is_some() && ref_tips.as_deref() == known_ref_tips {
+            None
+        } else {
+            Some(git::fetch_commits(&mut cursor, limit, filter))
+        };
+        Self {
+            ref_tips,
+            limit,
+            commits,
+            cursor,
+            next_page: false,
+        }
+    }
+
+    /// Reads the page after the commits in `state`. When a ref moved since,
+    /// the list no longer lines up with the log and is read again instead.
+    pub fn next_page(state: &CommitsState) -> Self {
+        let limit = state.limit + PAGE_SIZE;
+        let ref_tips = git::fetch_ref_tips().ok();
+        if ref_tips.is_none() || ref_tips != state.ref_tips {
+            return Self::load(None, limit, &state.query.filter);
+        }
+        let mut cursor = state.cursor.clone();
+        let commits = git::fetch_commits(&mut cursor, PAGE_SIZE, &state.query.filter);
+        Self {
+            ref_tips,
+            limit,
+            commits: Some(commits),
+            cursor,
+            next_page: true,
+        }
+    }
 }
 
 impl CommitsState {
-    pub fn refresh(previous_hovered_id: Option<&str>) -> Self {
-        match git::fetch_commits() {
-            Ok(commits) => {
+    /// Rebuilds the commit list from `fetched`, reusing `previous` while no ref
+    /// has moved, or appends the page it read.
+    pub fn apply(
+        previous: CommitsState,
+        fetched: CommitsFetch,
+        previous_hovered_id: Option<&str>,
+    ) -> Self {
+        match fetched.commits {
+            None => Self {
+                hovered: preferred_hover_index(&previous.commits, previous_hovered_id),
+                ..previous
+            },
+            Some(Ok(page)) if fetched.next_page => {
+                let mut state = previous;
+                state.append(page);
+                state.cursor = fetched.cursor;
+                state.limit = fetched.limit;
+                state
+            }
+            Some(Ok(commits)) => {
                 let hovered = preferred_hover_index(&commits, previous_hovered_id);
-                Self {
-                    commits,
-                    status: None,
+                let mut marked = previous.marked;
+                marked.retain(|id| commits.iter().any(|commit| &commit.id == id));
+                let mut state = Self {
                     hovered,
-                }
+                    ref_tips: fetched.ref_tips,
+                    cursor: fetched.cursor,
+                    limit: fetched.limit,
+                    query: previous.query,
+                    search_input: previous.search_input,
+                    marked,
+                    list_height: previous.list_height,
+                    ..Self::default()
+                };
+                state.append(commits);
+                state
             }
-            Err(err) => Self {
-                commits: Vec::new(),
+            Some(Err(err)) => Self {
                 status: Some(err),
-                hovered: None,
+                query: previous.query,
+                search_input: previous.search_input,
+                list_height: previous.list_height,
+                ..Self::default()
             },
         }
     }
 
+    fn append(&mut self, commits: Vec<Commit>) {
+        // Lanes would wait forever for parents a filter left out.
+        if !self.query.filter.hides_ancestry() {
+            self.graph.extend(self.lanes.extend(&commits));
+        }
+        self.commits.extend(commits);
+    }
+
     pub fn move_hover_up(&mut self) {
         self.update_hover(|idx, len| (idx + len - 1) % len);
     }
@@ -55,6 +186,68 @@ impl CommitsState {
         self.update_hover(|idx, len| (idx + 1) % len);
     }
 
+    pub fn page_up(&mut self) {
+        let rows = self.page_rows();
+        self.update_hover(|idx, _| idx.saturating_sub(rows));
+    }
+
+    pub fn page_down(&mut self) {
+        let rows = self.page_rows();
+        self.update_hover(|idx, len| (idx + rows).min(len - 1));
+    }
+
+    fn page_rows(&self) -> usize {
+        self.list_height.get().max(1)
+    }
+
+    pub fn move_to_top(&mut self) {
+        self.update_hover(|_, _| 0);
+    }
+
+    /// Jumps to the last loaded commit, which in turn asks for the next page.
+    pub fn move_to_bottom(&mut self) {
+        self.update_hover(|_, len| len - 1);
+    }
+
+    /// Whether the log may hold commits beyond the ones loaded so far.
+    pub fn has_more(&self) -> bool {
+        self.status.is_none() && self.commits.len() >= self.limit
+    }
+
+    pub fn wants_next_page(&self) -> bool {
+        self.has_more()
+            && self
+                .hovered
+                .is_some_and(|idx| idx + LOAD_AHEAD >= self.commits.len())
+    }
+
+    pub fn start_search(&mut self) {
+        let value = self.query.source.clone();
+        self.search_input = Some(TextInput {
+            cursor: value.len(),
+            value,
+        });
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search_input.is_some()
+    }
+
+    /// The first loaded match at or after `start`. It does not wrap, so `n` can
+    /// tell when to load further pages first.
+    pub fn match_from(&self, start: usize) -> Option<usize> {
+        (start..self.commits.len()).find(|&idx| self.query.matches(&self.commits[idx]))
+    }
+
+    /// The closest match above the hovered commit, wrapping to the last loaded one.
+    pub fn previous_match(&self) -> Option<usize> {
+        let hovered = self.hovered.unwrap_or(0).min(self.commits.len());
+        (0..hovered)
+            .rev()
+            .chain((hovered..self.commits.len()).rev())
+            .find(|&idx| self.query.matches(&self.commits[idx]))
+    }
+
     fn update_hover<F: FnOnce(usize, usize) -> usize>(&mut self, next: F) {
         let len = self.commits.len();
         if len == 0 {
@@ -67,15 +260,43 @@ impl CommitsState {
         });
     }
 
-    pub fn hovered_commit_id(&self) -> Option<&str> {
-        self.hovered
-            .and_then(|idx| self.commits.get(idx))
-            .map(|c| c.id.as_str())
+    pub fn toggle_mark(&mut self) {
+        let Some(id) = self.hovered_commit_id().map(str::to_string) else {
+            return;
+        };
+        if let Some(pos) = self.marked.iter().position(|marked| *marked == id) {
+            self.marked.remove(pos);
+        } else {
+            self.marked.push(id);
+        }
+    }
+
+    pub fn is_marked(&self, id: &str) -> bool {
+        self.marked.iter().any(|marked| marked == id)
+    }
+
+    /// The marked commits, or the hovered one when none are marked, oldest
+    /// first so they apply in history order.
+    pub fn picked_commits(&self) -> Vec<Commit> {
+        let picked: Vec<Commit> = if self.marked.is_empty() {
+            self.hovered_commit().cloned().into_iter().collect()
+        } else {
+            self.commits
+                .iter()
+                .filter(|commit| self.is_marked(&commit.id))
+                .cloned()
+                .collect()
+        };
+        picked.into_iter().rev().collect()
     }
 
     pub fn hovered_commit(&self) -> Option<&Commit> {
         self.hovered.and_then(|idx| self.commits.get(idx))
     }
+
+    pub fn hovered_commit_id(&self) -> Option<&str> {
+        self.hovered_commit().map(|c| c.id.as_str())
+    }
 }
 
 pub struct CommitList<'a> {
@@ -100,19 +321,24 @@ impl Widget for CommitsPanelWidget<'_> {
             .border_set(ratatui::symbols::border::THICK);
         let inner = base_block.inner(area);
 
-        let title = if let (Some(hovered), true) = (
+        let mut title = Region::Commits.as_str().to_string();
+        if !self.state.query.is_empty() {
+            title.push_str(&format!(" /{}", self.state.query.source));
+        }
+        if !self.state.marked.is_empty() {
+            title.push_str(&format!(" [{} marked]", self.state.marked.len()));
+        }
+        if let (Some(hovered), true) = (
             self.state.hovered,
             self.state.commits.len() > inner.height as usize,
         ) {
-            format!(
-                "{} ({}/{})",
-                Region::Commits.as_str(),
+            title.push_str(&format!(
+                " ({}/{}{})",
                 hovered + 1,
-                self.state.commits.len()
-            )
-        } else {
-            Region::Commits.as_str().to_string()
-        };
+                self.state.commits.len(),
+                if self.state.has_more() { "+" } else { "" }
+            ));
+        }
 
         let block = base_block.title(title);
         block.render(area, buf);
@@ -136,21 +362,34 @@ impl Widget for CommitList<'_> {
                 .render(chunks[0], buf);
         }
 
-        let list_area = if self.state.status.is_some() {
+        let mut list_area = if self.state.status.is_some() {
             chunks[1]
         } else {
             area
         };
 
+        if let Some(input) = &self.state.search_input {
+            let prompt_area = Rect {
+                y: list_area.bottom().saturating_sub(1),
+                height: list_area.height.min(1),
+                ..list_area
+            };
+            list_area.height = list_area.height.saturating_sub(1);
+            Paragraph::new(Line::from(input.render_line("/"))).render(prompt_area, buf);
+        }
+
         if self.state.commits.is_empty() {
             Paragraph::new("No commits found").render(list_area, buf);
             return;
         }
+        self.state.list_height.set(list_area.height as usize);
         let (start, end) = viewport(
             self.state.commits.len(),
             self.state.hovered,
             list_area.height,
         );
+        let graph = self.state.graph.get(start..end).unwrap_or_default();
+        let graph_width = graph.iter().map(Vec::len).max().unwrap_or(0);
         let items: Vec<ListItem> = self.state.commits[start..end]
             .iter()
             .enumerate()
@@ -163,14 +402,35 @@ impl Widget for CommitList<'_> {
                 if is_hovered {
                     style = style.fg(Color::Black).bg(Color::Cyan);
                 }
-                let line = Line::from(vec![
+                let mut spans = Vec::new();
+                if !self.state.marked.is_empty() {
+                    spans.push(if self.state.is_marked(&commit.id) {
+                        Span::styled("✚ ", Style::default().fg(Color::Green))
+                    } else {
+                        Span::raw("  ")
+                    });
+                }
+                spans.extend([
                     Span::styled(padded, Style::default().fg(Color::Cyan)),
                     Span::raw(" "),
-                    Span::styled(commit.id.clone(), style),
-                    Span::raw(" "),
-                    Span::styled(commit.summary.clone(), style),
                 ]);
-                ListItem::new(line)
+                if let Some(row) = graph.get(offset) {
+                    spans.extend(graph_spans(row, graph_width));
+                }
+                let query = &self.state.query;
+                let id_style = if query.id_matches(&commit.id) {
+                    style.patch(match_style())
+                } else {
+                    style
+                };
+                spans.extend([Span::styled(commit.id.clone(), id_style), Span::raw(" ")]);
+                spans.extend(tag_spans(&commit.tags));
+                spans.extend(highlight_matches(
+                    &commit.summary,
+                    &query.summary_matches(&commit.summary),
+                    style,
+                ));
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
@@ -178,6 +438,43 @@ impl Widget for CommitList<'_> {
     }
 }
 
+fn match_style() -> Style {
+    Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+}
+
+/// Splits `text` into spans, styling the `matches` byte ranges on top of `style`.
+fn highlight_matches(text: &str, matches: &[Range<usize>], style: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::with_capacity(matches.len() * 2 + 1);
+    let mut end = 0;
+    for range in matches {
+        if range.start > end {
+            spans.push(Span::styled(text[end..range.start].to_string(), style));
+        }
+        spans.push(Span::styled(
+            text[range.clone()].to_string(),
+            style.patch(match_style()),
+        ));
+        end = range.end;
+    }
+    if end < text.len() || spans.is_empty() {
+        spans.push(Span::styled(text[end..].to_string(), style));
+    }
+    spans
+}
+
+/// Labels the tags pointing at a commit, shown ahead of its summary.
+fn tag_spans(tags: &[String]) -> Vec<Span<'static>> {
+    let label = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    tags.iter()
+        .flat_map(|tag| [Span::styled(format!(" {tag} "), label), Span::raw(" ")])
+        .collect()
+}
+
 fn format_branch_label(branches: &[String]) -> String {
     if branches.is_empty() {
         "-".to_string()
@@ -206,29 +503,14 @@ fn preferred_hover_index(commits: &[Commit], previous_id: Option<&str>) -> Optio
     if commits.is_empty() {
         return None;
     }
-    if let Some(id) = previous_id {
-        if let Some(idx) = commits.iter().position(|c| c.id == id) {
-            return Some(idx);
-        }
+    if let Some(id) = previous_id
+        && let Some(idx) = commits.iter().position(|c| c.id == id)
+    {
+        return Some(idx);
     }
     Some(0)
 }
 
-fn viewport(len: usize, hovered: Option<usize>, height: u16) -> (usize, usize) {
-    if len == 0 || height == 0 {
-        return (0, 0);
-    }
-    let visible = height as usize;
-    let focus = hovered.unwrap_or(0).min(len.saturating_sub(1));
-    if len <= visible {
-        return (0, len);
-    }
-    let max_start = len - visible;
-    let start = focus.saturating_sub(visible / 2).min(max_start);
-    let end = start + visible;
-    (start, end)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,6 +520,8 @@ mod tests {
             id: id.to_string(),
             summary: summary.to_string(),
             branches: branches.iter().map(|b| b.to_string()).collect(),
+            parents: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
@@ -262,6 +546,182 @@ mod tests {
         assert_eq!(hovered, Some(0));
     }
 
+    #[test]
+    fn picks_marked_commits_oldest_first_or_the_hovered_one() {
+        let mut state = CommitsState {
+            commits: vec![
+                make_commit("c3", "third", &["main"]),
+                make_commit("c2", "second", &["main"]),
+                make_commit("c1", "first", &["main"]),
+            ],
+            hovered: Some(1),
+            ..CommitsState::default()
+        };
+        let ids = |state: &CommitsState| {
+            state
+                .picked_commits()
+                .into_iter()
+                .map(|commit| commit.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&state), vec!["c2"]);
+        state.move_hover_up();
+        state.toggle_mark();
+        state.move_to_bottom();
+        state.toggle_mark();
+        assert_eq!(ids(&state), vec!["c1", "c3"]);
+        state.toggle_mark();
+        assert_eq!(ids(&state), vec!["c3"]);
+    }
+
+    #[test]
+    fn paging_moves_hover_and_requests_next_page_near_the_end() {
+        let commits: Vec<Commit> = (0..100)
+            .map(|idx| make_commit(&format!("c{idx}"), "work", &["main"]))
+            .collect();
+        let mut state = CommitsState {
+            commits,
+            hovered: Some(0),
+            limit: 100,
+            list_height: Cell::new(12),
+            ..CommitsState::default()
+        };
+
+        state.page_down();
+        assert_eq!(state.hovered, Some(12));
+        assert!(!state.wants_next_page());
+        state.page_up();
+        state.page_up();
+        assert_eq!(state.hovered, Some(0));
+
+        state.move_to_bottom();
+        assert_eq!(state.hovered, Some(99));
+        assert!(state.wants_next_page());
+        state.page_down();
+        assert_eq!(state.hovered, Some(99));
+
+        state.limit = 200;
+        assert!(!state.has_more());
+        assert!(!state.wants_next_page());
+        state.move_to_top();
+        assert_eq!(state.hovered, Some(0));
+    }
+
+    #[test]
+    fn author_filtered_log_has_no_graph() {
+        let commits = vec![
+            Commit {
+                parents: vec!["b2".to_string()],
+                ..make_commit("c3", "third", &[])
+            },
+            Commit {
+                parents: vec!["a0".to_string()],
+                ..make_commit("a1", "first", &[])
+            },
+        ];
+        let fetched = |commits: &Vec<Commit>| CommitsFetch {
+            ref_tips: None,
+            limit: PAGE_SIZE,
+            commits: Some(Ok(commits.clone())),
+            cursor: LogCursor::default(),
+            next_page: false,
+        };
+
+        let state = CommitsState::apply(CommitsState::default(), fetched(&commits), None);
+        assert_eq!(state.graph.len(), 2);
+
+        let filtered = CommitsState {
+            query: CommitQuery::parse("author:alice"),
+            ..CommitsState::default()
+        };
+        let state = CommitsState::apply(filtered, fetched(&commits), None);
+        assert!(state.graph.is_empty());
+        assert_eq!(state.commits.len(), 2);
+    }
+
+    #[test]
+    fn next_page_appends_commits_and_continues_the_graph() {
+        let fetched = |commits: Vec<Commit>, next_page| CommitsFetch {
+            ref_tips: None,
+            limit: PAGE_SIZE,
+            commits: Some(Ok(commits)),
+            cursor: LogCursor::default(),
+            next_page,
+        };
+        let first = vec![
+            Commit {
+                parents: vec!["a1".to_string(), "f1".to_string()],
+                ..make_commit("m1", "merge", &["main"])
+            },
+            Commit {
+                parents: vec!["a1".to_string()],
+                ..make_commit("f1", "feature", &["main"])
+            },
+        ];
+        let state = CommitsState::apply(CommitsState::default(), fetched(first, false), None);
+        let state = CommitsState {
+            hovered: Some(1),
+            ..state
+        };
+
+        let second = vec![make_commit("a1", "base", &["main"])];
+        let state = CommitsState::apply(state, fetched(second, true), Some("f1"));
+
+        let ids: Vec<&str> = state.commits.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["m1", "f1", "a1"]);
+        assert_eq!(state.hovered, Some(1));
+        assert_eq!(state.graph.len(), 3);
+        assert_eq!(state.graph[2][1].glyph, '╯');
+    }
+
+    #[test]
+    fn search_matches_step_forward_and_back_with_wrap() {
+        let commits = vec![
+            make_commit("a1", "fix login", &["main"]),
+            make_commit("b2", "docs", &["main"]),
+            make_commit("c3", "Login timeout", &["main"]),
+            make_commit("d4", "bump version", &["main"]),
+        ];
+        let mut state = CommitsState {
+            commits,
+            hovered: Some(1),
+            query: CommitQuery::parse("login"),
+            ..CommitsState::default()
+        };
+
+        assert_eq!(state.match_from(1), Some(2));
+        assert_eq!(state.match_from(3), None);
+        assert_eq!(state.previous_match(), Some(0));
+        state.hovered = Some(0);
+        assert_eq!(state.previous_match(), Some(2));
+
+        state.query = CommitQuery::parse("nothing");
+        assert_eq!(state.match_from(0), None);
+        assert_eq!(state.previous_match(), None);
+    }
+
+    #[test]
+    fn highlight_matches_splits_summary_around_matches() {
+        let base = Style::default();
+        let spans = highlight_matches("fix auth in auth.rs", &[4..8, 12..16], base);
+
+        let texts: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(texts, vec!["fix ", "auth", " in ", "auth", ".rs"]);
+        assert_eq!(spans[1].style, base.patch(match_style()));
+        assert_eq!(spans[0].style, base);
+    }
+
+    #[test]
+    fn tag_spans_label_each_tag() {
+        let spans = tag_spans(&["v1.0".to_string(), "latest".to_string()]);
+        let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+
+        assert_eq!(text, " v1.0   latest  ");
+        assert_eq!(spans[0].style.bg, Some(Color::Yellow));
+        assert!(tag_spans(&[]).is_empty());
+    }
+
     #[test]
     fn format_branch_label_handles_various_cases() {
         assert_eq!(format_branch_label(&[]), "-");
@@ -288,6 +748,7 @@ mod tests {
             commits,
             status: None,
             hovered: None,
+            ..CommitsState::default()
         };
 
         state.move_hover_down();
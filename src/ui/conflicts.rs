@@ -0,0 +1,302 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::{self, ConflictFile, ConflictHunk, ConflictSide};
+use crate::ui::layout::centered_rect;
+
+/// The conflict view: conflicted files on the left, the hovered file's hunks
+/// with ours, base and theirs side by side on the right.
+#[derive(Debug, Default)]
+pub struct ConflictsState {
+    pub files: Vec<String>,
+    pub hovered: usize,
+    pub conflict: Option<Result<ConflictFile, String>>,
+    /// The side picked for each hunk of the hovered file.
+    pub choices: Vec<Option<ConflictSide>>,
+    pub hunk: usize,
+}
+
+impl ConflictsState {
+    pub fn open(files: Vec<String>, selected: &str) -> Self {
+        let hovered = files.iter().position(|file| file == selected).unwrap_or(0);
+        let mut state = Self {
+            files,
+            hovered,
+            ..Self::default()
+        };
+        state.load();
+        state
+    }
+
+    /// Reads the hovered file's conflict, forgetting the picks made so far.
+    pub fn load(&mut self) {
+        self.conflict = self.hovered_file().map(git::fetch_conflict);
+        let hunks = self.hunk_count();
+        self.choices = vec![None; hunks];
+        self.hunk = 0;
+    }
+
+    pub fn hovered_file(&self) -> Option<&str> {
+        self.files.get(self.hovered).map(String::as_str)
+    }
+
+    pub fn move_file_up(&mut self) {
+        if self.hovered > 0 {
+            self.hovered -= 1;
+            self.load();
+        }
+    }
+
+    pub fn move_file_down(&mut self) {
+        if self.hovered + 1 < self.files.len() {
+            self.hovered += 1;
+            self.load();
+        }
+    }
+
+    pub fn next_hunk(&mut self) {
+        if self.hunk + 1 < self.hunk_count() {
+            self.hunk += 1;
+        }
+    }
+
+    pub fn previous_hunk(&mut self) {
+        self.hunk = self.hunk.saturating_sub(1);
+    }
+
+    /// Resolves the current hunk to `side` and moves on to the next open one.
+    pub fn pick(&mut self, side: ConflictSide) {
+        let Some(choice) = self.choices.get_mut(self.hunk) else {
+            return;
+        };
+        *choice = Some(side);
+        if let Some(next) =
+            (self.hunk + 1..self.choices.len()).find(|&idx| self.choices[idx].is_none())
+        {
+            self.hunk = next;
+        }
+    }
+
+    pub fn clear_pick(&mut self) {
+        if let Some(choice) = self.choices.get_mut(self.hunk) {
+            *choice = None;
+        }
+    }
+
+    pub fn unresolved(&self) -> usize {
+        self.choices
+            .iter()
+            .filter(|choice| choice.is_none())
+            .count()
+    }
+
+    /// The hovered file with every hunk resolved, or why it cannot be written yet.
+    pub fn resolved_contents(&self) -> Result<String, String> {
+        let conflict = match &self.conflict {
+            Some(Ok(conflict)) => conflict,
+            Some(Err(err)) => return Err(err.clone()),
+            None => return Err("No conflicted file selected".to_string()),
+        };
+        match self.unresolved() {
+            0 => Ok(conflict.resolve(&self.choices)),
+            1 => Err("1 conflict is still unresolved".to_string()),
+            count => Err(format!("{count} conflicts are still unresolved")),
+        }
+    }
+
+    /// Whether the picks take the side that deleted the hovered file throughout.
+    pub fn deletes_file(&self) -> bool {
+        matches!(&self.conflict, Some(Ok(conflict)) if conflict.deletes(&self.choices))
+    }
+
+    /// Forgets `file` once it is resolved and moves to the next conflicted file.
+    pub fn remove_file(&mut self, file: &str) {
+        self.files.retain(|candidate| candidate != file);
+        self.hovered = self.hovered.min(self.files.len().saturating_sub(1));
+        self.load();
+    }
+
+    fn hunk_count(&self) -> usize {
+        match &self.conflict {
+            Some(Ok(conflict)) => conflict.hunk_count(),
+            _ => 0,
+        }
+    }
+
+    fn current_hunk(&self) -> Option<&ConflictHunk> {
+        match &self.conflict {
+            Some(Ok(conflict)) => conflict.hunks().nth(self.hunk),
+            _ => None,
+        }
+    }
+}
+
+pub fn render_conflicts_popup(area: Rect, buf: &mut Buffer, state: &ConflictsState) {
+    let popup_area = centered_rect(90, 85, area);
+    Clear.render(popup_area, buf);
+
+    let frame = Block::default()
+        .title("Conflicts  ·  [↑↓] file  [n/p] hunk  [o] ours  [t] theirs  [b] both  [u] undo  [Enter] mark resolved  [Esc] close")
+        .borders(Borders::ALL)
+        .border_set(border::THICK)
+        .style(Style::default().fg(Color::Green));
+    let inner = frame.inner(popup_area);
+    frame.render(popup_area, buf);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Percentage(25), Constraint::Percentage(75)])
+        .split(inner);
+
+    let files: Vec<Line> = state
+        .files
+        .iter()
+        .enumerate()
+        .map(|(idx, file)| {
+            let line = Line::from(Span::styled(
+                file.clone(),
+                Style::default().fg(Color::Magenta),
+            ));
+            if idx == state.hovered {
+                line.style(Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+    Paragraph::new(files)
+        .block(Block::default().borders(Borders::RIGHT))
+        .render(columns[0], buf);
+
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
+        .split(columns[1]);
+
+    let conflict = match &state.conflict {
+        Some(Ok(conflict)) => conflict,
+        Some(Err(err)) => {
+            Paragraph::new(err.as_str())
+                .style(Style::default().fg(Color::Red))
+                .render(right[0], buf);
+            return;
+        }
+        None => {
+            Paragraph::new("No conflicted files").render(right[0], buf);
+            return;
+        }
+    };
+
+    let Some(hunk) = state.current_hunk() else {
+        Paragraph::new(format!(
+            "{}: no conflicting hunks; [Enter] stages the work tree file as it is",
+            conflict.path
+        ))
+        .render(right[0], buf);
+        return;
+    };
+
+    let choice = state.choices.get(state.hunk).copied().flatten();
+    let picked = match choice {
+        Some(ConflictSide::Ours) => "ours",
+        Some(ConflictSide::Theirs) => "theirs",
+        Some(ConflictSide::Both) => "both",
+        None => "unresolved",
+    };
+    Paragraph::new(format!(
+        " {}  ·  conflict {}/{} ({picked})  ·  {} unresolved",
+        conflict.path,
+        state.hunk + 1,
+        state.choices.len(),
+        state.unresolved()
+    ))
+    .render(right[0], buf);
+
+    let sides = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, 3); 3])
+        .split(right[1]);
+    let ours_picked = matches!(choice, Some(ConflictSide::Ours | ConflictSide::Both));
+    let theirs_picked = matches!(choice, Some(ConflictSide::Theirs | ConflictSide::Both));
+    render_side(sides[0], buf, "Ours", &hunk.ours, ours_picked);
+    render_side(sides[1], buf, "Base", &hunk.base, false);
+    render_side(sides[2], buf, "Theirs", &hunk.theirs, theirs_picked);
+}
+
+fn render_side(area: Rect, buf: &mut Buffer, title: &str, lines: &[String], picked: bool) {
+    let color = if picked { Color::Green } else { Color::Yellow };
+    let lines: Vec<Line> = lines
+        .iter()
+        .map(|line| Line::from(line.trim_end_matches(['\n', '\r']).to_string()))
+        .collect();
+    Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(color)),
+        )
+        .render(area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ConflictSegment;
+
+    fn hunk(ours: &str, theirs: &str) -> ConflictSegment {
+        ConflictSegment::Conflict(ConflictHunk {
+            ours: vec![format!("{ours}\n")],
+            base: Vec::new(),
+            theirs: vec![format!("{theirs}\n")],
+        })
+    }
+
+    fn state() -> ConflictsState {
+        let conflict = ConflictFile {
+            path: "file.txt".to_string(),
+            segments: vec![
+                hunk("a-ours", "a-theirs"),
+                ConflictSegment::Clean(vec!["middle\n".to_string()]),
+                hunk("b-ours", "b-theirs"),
+            ],
+            deleted_by: None,
+        };
+        ConflictsState {
+            files: vec!["file.txt".to_string()],
+            choices: vec![None; 2],
+            conflict: Some(Ok(conflict)),
+            ..ConflictsState::default()
+        }
+    }
+
+    #[test]
+    fn picking_advances_to_the_next_open_hunk() {
+        let mut state = state();
+
+        state.pick(ConflictSide::Theirs);
+        assert_eq!(state.hunk, 1);
+        assert_eq!(state.unresolved(), 1);
+        assert_eq!(
+            state.resolved_contents().unwrap_err(),
+            "1 conflict is still unresolved"
+        );
+
+        state.pick(ConflictSide::Both);
+        assert_eq!(
+            state.resolved_contents().unwrap(),
+            "a-theirs\nmiddle\nb-ours\nb-theirs\n"
+        );
+
+        state.previous_hunk();
+        state.clear_pick();
+        assert_eq!(state.unresolved(), 1);
+    }
+}
@@ -1,35 +1,429 @@
-use ratatui::{buffer::Buffer, layout::Rect, text::Line, widgets::Paragraph, widgets::Widget};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
 
-use crate::{git::Commit, regions::Region};
+use crate::{
+    git::{self, ChangeType, CommitDetails, CommitFile, FileDiff},
+    regions::Region,
+    ui::layout::viewport,
+};
 
-use super::panel::PanelBlock;
+use super::{diff::diff_lines, panel::PanelBlock};
 
 pub type DetailsPanel<W = super::panel::Empty> = PanelBlock<W>;
 
-pub fn panel(selected: bool) -> DetailsPanel {
-    PanelBlock::new(Region::Details, selected)
-}
-
 pub fn panel_with_child<W: Widget>(selected: bool, child: W) -> DetailsPanel<W> {
     PanelBlock::with_child(Region::Details, selected, child)
 }
 
+const PAGE_SIZE: usize = 10;
+
+#[derive(Debug, Default)]
+pub struct DetailsState {
+    pub commit_id: Option<String>,
+    pub details: Option<CommitDetails>,
+    pub status: Option<String>,
+    pub hovered_file: Option<usize>,
+    /// Index into the commit's parents that files and diffs are compared against.
+    pub parent: usize,
+    pub file_diff: Option<FileDiff>,
+    pub diff_scroll: usize,
+}
+
+impl DetailsState {
+    /// Loads details for `commit_id`, reusing `previous` when it already describes
+    /// that commit so the hovered commit is only queried once.
+    pub fn refresh(previous: DetailsState, commit_id: Option<&str>) -> Self {
+        let Some(commit_id) = commit_id else {
+            return Self::default();
+        };
+
+        if previous.commit_id.as_deref() == Some(commit_id) {
+            return previous;
+        }
+
+        match git::fetch_commit_details(commit_id) {
+            Ok(details) => Self {
+                commit_id: Some(commit_id.to_string()),
+                hovered_file: (!details.files.is_empty()).then_some(0),
+                details: Some(details),
+                ..Self::default()
+            },
+            Err(err) => Self {
+                commit_id: Some(commit_id.to_string()),
+                status: Some(err),
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn move_hover_up(&mut self) {
+        self.update_hover(|idx, len| (idx + len - 1) % len);
+    }
+
+    pub fn move_hover_down(&mut self) {
+        self.update_hover(|idx, len| (idx + 1) % len);
+    }
+
+    fn update_hover<F: FnOnce(usize, usize) -> usize>(&mut self, next: F) {
+        let len = self
+            .details
+            .as_ref()
+            .map_or(0, |details| details.files.len());
+        if len == 0 {
+            self.hovered_file = None;
+            return;
+        }
+        self.hovered_file = Some(match self.hovered_file {
+            Some(idx) => next(idx, len),
+            None => 0,
+        });
+    }
+
+    pub fn hovered_file(&self) -> Option<&CommitFile> {
+        let details = self.details.as_ref()?;
+        self.hovered_file.and_then(|idx| details.files.get(idx))
+    }
+
+    /// The parent the commit is currently compared against, `None` for root commits.
+    pub fn compared_parent(&self) -> Option<&str> {
+        self.details
+            .as_ref()
+            .and_then(|details| details.parents.get(self.parent))
+            .map(String::as_str)
+    }
+
+    pub fn open_file_diff(&mut self) -> Result<(), String> {
+        let (Some(details), Some(file)) = (self.details.as_ref(), self.hovered_file()) else {
+            return Err("No file selected".into());
+        };
+        let diff = git::fetch_commit_file_diff(&details.id, self.compared_parent(), &file.path)?;
+        self.file_diff = Some(diff);
+        self.diff_scroll = 0;
+        Ok(())
+    }
+
+    pub fn close_file_diff(&mut self) {
+        self.file_diff = None;
+        self.diff_scroll = 0;
+    }
+
+    /// Compares a merge commit against its next parent, reloading the file list.
+    pub fn cycle_parent(&mut self) -> Result<(), String> {
+        let Some(details) = self.details.as_mut() else {
+            return Ok(());
+        };
+        if details.parents.len() < 2 {
+            return Err("Commit has a single parent".into());
+        }
+
+        let parent = (self.parent + 1) % details.parents.len();
+        details.files = git::fetch_commit_files(&details.id, Some(&details.parents[parent]))?;
+        self.parent = parent;
+        self.hovered_file = (!details.files.is_empty()).then_some(0);
+        self.close_file_diff();
+        Ok(())
+    }
+
+    pub fn scroll_diff_up(&mut self, amount: usize) {
+        self.diff_scroll = self.diff_scroll.saturating_sub(amount);
+    }
+
+    pub fn scroll_diff_down(&mut self, amount: usize) {
+        let max = self
+            .file_diff
+            .as_ref()
+            .map_or(0, |diff| diff.line_count().saturating_sub(1));
+        self.diff_scroll = (self.diff_scroll + amount).min(max);
+    }
+
+    pub fn page_diff_up(&mut self) {
+        self.scroll_diff_up(PAGE_SIZE);
+    }
+
+    pub fn page_diff_down(&mut self) {
+        self.scroll_diff_down(PAGE_SIZE);
+    }
+}
+
 pub struct DetailsView<'a> {
-    commit: Option<&'a Commit>,
+    state: &'a DetailsState,
+    focused: bool,
 }
 
 impl<'a> DetailsView<'a> {
-    pub fn new(commit: Option<&'a Commit>) -> Self {
-        Self { commit }
+    pub fn new(state: &'a DetailsState, focused: bool) -> Self {
+        Self { state, focused }
     }
 }
 
 impl Widget for DetailsView<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let message = match self.commit {
-            Some(commit) => format!("Details for \"{}\" are not implemented yet", commit.summary),
-            None => "Select a commit to view details".to_string(),
+        if let Some(status) = self.state.status.as_deref() {
+            Paragraph::new(status)
+                .style(Style::default().fg(Color::Red))
+                .render(area, buf);
+            return;
+        }
+
+        let Some(details) = self.state.details.as_ref() else {
+            Paragraph::new(Line::from("Select a commit to view details")).render(area, buf);
+            return;
+        };
+
+        if let (Some(diff), Some(file)) = (self.state.file_diff.as_ref(), self.state.hovered_file())
+        {
+            let mut lines = vec![diff_title(file, self.state.compared_parent())];
+            lines.extend(
+                diff_lines(diff)
+                    .into_iter()
+                    .skip(self.state.diff_scroll)
+                    .take((area.height as usize).saturating_sub(1)),
+            );
+            Paragraph::new(lines).render(area, buf);
+            return;
+        }
+
+        let mut lines = detail_lines(details, self.state.parent);
+        let files_start = lines.len() - details.files.len();
+        if self.focused
+            && let Some(idx) = self.state.hovered_file
+            && let Some(line) = lines.get_mut(files_start + idx)
+        {
+            *line = line
+                .clone()
+                .style(Style::default().add_modifier(Modifier::REVERSED));
+        }
+
+        let focus = self.state.hovered_file.map(|idx| files_start + idx);
+        let (start, end) = viewport(lines.len(), focus, area.height);
+        let visible: Vec<Line> = lines.drain(start..end).collect();
+        Paragraph::new(visible).render(area, buf);
+    }
+}
+
+fn diff_title(file: &CommitFile, parent: Option<&str>) -> Line<'static> {
+    let against = parent.map_or_else(|| "empty tree".to_string(), short_id);
+    Line::from(vec![
+        Span::styled(
+            file.path.clone(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" against {against}"),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+fn detail_lines(details: &CommitDetails, parent: usize) -> Vec<Line<'static>> {
+    let label = Style::default().fg(Color::Yellow);
+    let value = Style::default().fg(Color::Reset);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("commit    ", label),
+            Span::styled(
+                details.id.clone(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Author    ", label),
+            Span::styled(
+                format!(
+                    "{} <{}>  {}",
+                    details.author, details.author_email, details.author_date
+                ),
+                value,
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Committer ", label),
+            Span::styled(
+                format!(
+                    "{} <{}>  {}",
+                    details.committer, details.committer_email, details.commit_date
+                ),
+                value,
+            ),
+        ]),
+    ];
+
+    let parents = if details.parents.is_empty() {
+        "(root commit)".to_string()
+    } else {
+        details
+            .parents
+            .iter()
+            .enumerate()
+            .map(|(idx, id)| {
+                let id = short_id(id);
+                if details.parents.len() > 1 && idx == parent {
+                    format!("[{id}]")
+                } else {
+                    id
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    lines.push(Line::from(vec![
+        Span::styled("Parents   ", label),
+        Span::styled(parents, value),
+    ]));
+
+    lines.push(Line::from(""));
+    lines.extend(
+        details
+            .message
+            .lines()
+            .map(|line| Line::from(Span::styled(format!("    {line}"), value))),
+    );
+    lines.push(Line::from(""));
+
+    lines.push(Line::from(Span::styled(
+        files_summary(&details.files),
+        label,
+    )));
+    lines.extend(details.files.iter().map(file_line));
+    lines
+}
+
+fn short_id(id: &str) -> String {
+    id.chars().take(7).collect()
+}
+
+fn files_summary(files: &[CommitFile]) -> String {
+    let additions: usize = files.iter().filter_map(|file| file.additions).sum();
+    let deletions: usize = files.iter().filter_map(|file| file.deletions).sum();
+    let noun = if files.len() == 1 { "file" } else { "files" };
+    format!("{} {noun} changed, +{additions} -{deletions}", files.len())
+}
+
+fn file_line(file: &CommitFile) -> Line<'static> {
+    let (flag, color) = match file.change {
+        ChangeType::Added => ("A", Color::Green),
+        ChangeType::Deleted => ("D", Color::Red),
+        ChangeType::Renamed => ("R", Color::Cyan),
+        ChangeType::Copied => ("C", Color::Cyan),
+        ChangeType::TypeChange => ("T", Color::Magenta),
+        ChangeType::Modified => ("M", Color::Yellow),
+        _ => ("?", Color::DarkGray),
+    };
+
+    let stats = match (file.additions, file.deletions) {
+        (Some(additions), Some(deletions)) => vec![
+            Span::styled(format!("+{additions}"), Style::default().fg(Color::Green)),
+            Span::raw(" "),
+            Span::styled(format!("-{deletions}"), Style::default().fg(Color::Red)),
+        ],
+        _ => vec![Span::styled("binary", Style::default().fg(Color::DarkGray))],
+    };
+
+    let mut spans = vec![
+        Span::styled(format!(" {flag} "), Style::default().fg(color)),
+        Span::styled(file.path.clone(), Style::default().fg(Color::Reset)),
+        Span::raw("  "),
+    ];
+    spans.extend(stats);
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_details() -> CommitDetails {
+        CommitDetails {
+            id: "0123456789abcdef".into(),
+            author: "Ada".into(),
+            author_email: "ada@example.com".into(),
+            author_date: "2024-01-01 10:00:00 +0000".into(),
+            committer: "Ada".into(),
+            committer_email: "ada@example.com".into(),
+            commit_date: "2024-01-01 10:00:00 +0000".into(),
+            parents: vec!["fedcba9876543210".into()],
+            message: "Summary\n\nBody line".into(),
+            files: vec![
+                CommitFile {
+                    path: "src/lib.rs".into(),
+                    change: ChangeType::Modified,
+                    additions: Some(3),
+                    deletions: Some(1),
+                },
+                CommitFile {
+                    path: "logo.png".into(),
+                    change: ChangeType::Added,
+                    additions: None,
+                    deletions: None,
+                },
+            ],
+        }
+    }
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn renders_metadata_message_and_files() {
+        let lines: Vec<String> = detail_lines(&sample_details(), 0)
+            .iter()
+            .map(text)
+            .collect();
+
+        assert_eq!(lines[0], "commit    0123456789abcdef");
+        assert!(lines[1].contains("Ada <ada@example.com>"));
+        assert_eq!(lines[3], "Parents   fedcba9");
+        assert!(lines.contains(&"    Body line".to_string()));
+        assert!(lines.contains(&"2 files changed, +3 -1".to_string()));
+        assert!(lines.contains(&" M src/lib.rs  +3 -1".to_string()));
+        assert!(lines.contains(&" A logo.png  binary".to_string()));
+    }
+
+    #[test]
+    fn marks_compared_parent_of_merges() {
+        let mut details = sample_details();
+        details.parents.push("aaaaaaa1111111".into());
+
+        let lines: Vec<String> = detail_lines(&details, 1).iter().map(text).collect();
+
+        assert_eq!(lines[3], "Parents   fedcba9 [aaaaaaa]");
+    }
+
+    #[test]
+    fn file_hover_wraps_and_diff_scroll_is_bounded() {
+        let mut state = DetailsState {
+            details: Some(sample_details()),
+            hovered_file: Some(0),
+            file_diff: Some(FileDiff {
+                header: vec!["diff --git a/f b/f".into()],
+                hunks: Vec::new(),
+            }),
+            ..DetailsState::default()
         };
-        Paragraph::new(Line::from(message)).render(area, buf);
+
+        state.move_hover_up();
+        assert_eq!(
+            state.hovered_file().map(|f| f.path.as_str()),
+            Some("logo.png")
+        );
+        state.move_hover_down();
+        assert_eq!(state.hovered_file, Some(0));
+
+        state.page_diff_down();
+        assert_eq!(state.diff_scroll, 0);
+        state.close_file_diff();
+        assert!(state.file_diff.is_none());
+        assert_eq!(state.compared_parent(), Some("fedcba9876543210"));
     }
 }
@@ -0,0 +1,415 @@
+use std::collections::HashSet;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+use crate::git::{self, DiffLineKind, FileChange, FileDiff};
+use crate::ui::layout::viewport;
+
+const PAGE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffRow {
+    Header,
+    Hunk(usize),
+    Line(usize, usize),
+}
+
+#[derive(Debug, Default)]
+pub struct DiffState {
+    pub change: Option<FileChange>,
+    pub diff: Option<FileDiff>,
+    pub status: Option<String>,
+    pub cursor: usize,
+    pub anchor: Option<usize>,
+}
+
+impl DiffState {
+    pub fn refresh(previous: DiffState, change: Option<FileChange>) -> Self {
+        let Some(change) = change else {
+            return Self::default();
+        };
+
+        let same_change = previous.change.as_ref() == Some(&change);
+        let cursor = if same_change { previous.cursor } else { 0 };
+
+        let mut state = match git::fetch_change_diff(&change) {
+            Ok(diff) => {
+                let unchanged = same_change && previous.diff.as_ref() == Some(&diff);
+                Self {
+                    change: Some(change),
+                    diff: Some(diff),
+                    status: None,
+                    cursor,
+                    anchor: previous.anchor.filter(|_| unchanged),
+                }
+            }
+            Err(err) => Self {
+                change: Some(change),
+                diff: None,
+                status: Some(err),
+                cursor: 0,
+                anchor: None,
+            },
+        };
+        state.clamp_cursor();
+        state
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.diff.as_ref().map_or(0, FileDiff::line_count)
+    }
+
+    pub fn move_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        self.cursor = self.cursor.saturating_add(1);
+        self.clamp_cursor();
+    }
+
+    pub fn page_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(PAGE_SIZE);
+    }
+
+    pub fn page_down(&mut self) {
+        self.cursor = self.cursor.saturating_add(PAGE_SIZE);
+        self.clamp_cursor();
+    }
+
+    pub fn move_to_top(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_to_bottom(&mut self) {
+        self.cursor = self.line_count().saturating_sub(1);
+    }
+
+    pub fn next_hunk(&mut self) {
+        let starts = self.hunk_rows();
+        if let Some(next) = starts.into_iter().find(|row| *row > self.cursor) {
+            self.cursor = next;
+        }
+    }
+
+    pub fn previous_hunk(&mut self) {
+        let starts = self.hunk_rows();
+        if let Some(previous) = starts.into_iter().rev().find(|row| *row < self.cursor) {
+            self.cursor = previous;
+        }
+    }
+
+    pub fn toggle_anchor(&mut self) {
+        self.anchor = match self.anchor {
+            Some(_) => None,
+            None => Some(self.cursor),
+        };
+    }
+
+    /// Changed lines the next stage/unstage/discard acts on: the marked range when
+    /// one is active, otherwise the whole hunk under the cursor.
+    pub fn selected_lines(&self) -> HashSet<(usize, usize)> {
+        let Some(diff) = self.diff.as_ref() else {
+            return HashSet::new();
+        };
+
+        let rows = match self.anchor {
+            Some(anchor) => anchor.min(self.cursor)..=anchor.max(self.cursor),
+            None => match row_at(diff, self.cursor) {
+                Some(DiffRow::Hunk(hunk)) | Some(DiffRow::Line(hunk, _)) => {
+                    let start = hunk_row(diff, hunk);
+                    start..=start + diff.hunks[hunk].lines.len()
+                }
+                _ => return HashSet::new(),
+            },
+        };
+
+        rows.filter_map(|row| match row_at(diff, row) {
+            Some(DiffRow::Line(hunk, line)) => Some((hunk, line)),
+            _ => None,
+        })
+        .filter(|(hunk, line)| {
+            matches!(
+                diff.hunks[*hunk].lines[*line].kind,
+                DiffLineKind::Added | DiffLineKind::Removed
+            )
+        })
+        .collect()
+    }
+
+    fn hunk_rows(&self) -> Vec<usize> {
+        let Some(diff) = self.diff.as_ref() else {
+            return Vec::new();
+        };
+        (0..diff.hunks.len())
+            .map(|hunk| hunk_row(diff, hunk))
+            .collect()
+    }
+
+    fn clamp_cursor(&mut self) {
+        let max = self.line_count().saturating_sub(1);
+        self.cursor = self.cursor.min(max);
+        self.anchor = self.anchor.map(|anchor| anchor.min(max));
+    }
+}
+
+fn hunk_row(diff: &FileDiff, hunk: usize) -> usize {
+    diff.header.len()
+        + diff.hunks[..hunk]
+            .iter()
+            .map(|hunk| hunk.lines.len() + 1)
+            .sum::<usize>()
+}
+
+fn row_at(diff: &FileDiff, row: usize) -> Option<DiffRow> {
+    if row < diff.header.len() {
+        return Some(DiffRow::Header);
+    }
+    let mut offset = diff.header.len();
+    for (idx, hunk) in diff.hunks.iter().enumerate() {
+        if row == offset {
+            return Some(DiffRow::Hunk(idx));
+        }
+        if row <= offset + hunk.lines.len() {
+            return Some(DiffRow::Line(idx, row - offset - 1));
+        }
+        offset += hunk.lines.len() + 1;
+    }
+    None
+}
+
+pub struct DiffView<'a> {
+    state: &'a DiffState,
+    focused: bool,
+}
+
+impl<'a> DiffView<'a> {
+    pub fn new(state: &'a DiffState, focused: bool) -> Self {
+        Self { state, focused }
+    }
+}
+
+impl Widget for DiffView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.state.change.is_none() {
+            Paragraph::new("Select a change to view").render(area, buf);
+            return;
+        }
+
+        if let Some(status) = self.state.status.as_deref() {
+            Paragraph::new(status)
+                .style(Style::default().fg(Color::Red))
+                .render(area, buf);
+            return;
+        }
+
+        let Some(diff) = self.state.diff.as_ref().filter(|diff| !diff.is_empty()) else {
+            Paragraph::new("No differences").render(area, buf);
+            return;
+        };
+
+        let mut lines = diff_lines(diff);
+        if self.focused {
+            let selected = self.state.selected_lines();
+            let selection_style = Style::default().bg(Color::Rgb(50, 50, 70));
+            for (row, line) in lines.iter_mut().enumerate() {
+                let in_selection = matches!(
+                    row_at(diff, row),
+                    Some(DiffRow::Line(hunk, idx)) if selected.contains(&(hunk, idx))
+                );
+                if in_selection {
+                    *line = line.clone().patch_style(selection_style);
+                }
+                if row == self.state.cursor {
+                    *line = line
+                        .clone()
+                        .patch_style(Style::default().add_modifier(Modifier::REVERSED));
+                }
+            }
+        }
+
+        let (start, end) = viewport(lines.len(), Some(self.state.cursor), area.height);
+        let visible: Vec<Line> = lines.drain(start..end).collect();
+        Paragraph::new(visible).render(area, buf);
+    }
+}
+
+pub fn diff_lines(diff: &FileDiff) -> Vec<Line<'static>> {
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let hunk_style = Style::default().fg(Color::Cyan);
+
+    let mut lines: Vec<Line<'static>> = diff
+        .header
+        .iter()
+        .map(|line| Line::from(Span::styled(line.clone(), header_style)))
+        .collect();
+
+    for hunk in &diff.hunks {
+        lines.push(Line::from(Span::styled(hunk.header.clone(), hunk_style)));
+        for line in &hunk.lines {
+            let (prefix, style) = match line.kind {
+                DiffLineKind::Added => ("+", Style::default().fg(Color::Green)),
+                DiffLineKind::Removed => ("-", Style::default().fg(Color::Red)),
+                DiffLineKind::Context => (" ", Style::default().fg(Color::Reset)),
+                DiffLineKind::NoNewline => ("", Style::default().fg(Color::DarkGray)),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("{prefix}{}", line.content),
+                style,
+            )));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{ChangeType, DiffHunk, DiffLine};
+
+    fn line(kind: DiffLineKind, content: &str) -> DiffLine {
+        DiffLine {
+            kind,
+            content: content.into(),
+        }
+    }
+
+    fn sample_diff() -> FileDiff {
+        FileDiff {
+            header: vec!["diff --git a/file.rs b/file.rs".into()],
+            hunks: vec![
+                DiffHunk {
+                    header: "@@ -1,2 +1,2 @@".into(),
+                    old_start: 1,
+                    old_lines: 2,
+                    new_start: 1,
+                    new_lines: 2,
+                    lines: vec![
+                        line(DiffLineKind::Context, "keep"),
+                        line(DiffLineKind::Removed, "old"),
+                        line(DiffLineKind::Added, "new"),
+                    ],
+                },
+                DiffHunk {
+                    header: "@@ -10,1 +10,2 @@".into(),
+                    old_start: 10,
+                    old_lines: 1,
+                    new_start: 10,
+                    new_lines: 2,
+                    lines: vec![
+                        line(DiffLineKind::Context, "tail"),
+                        line(DiffLineKind::Added, "extra"),
+                    ],
+                },
+            ],
+        }
+    }
+
+    fn sample_state() -> DiffState {
+        DiffState {
+            change: Some(FileChange {
+                path: "file.rs".into(),
+                change: ChangeType::Modified,
+                staged: false,
+            }),
+            diff: Some(sample_diff()),
+            status: None,
+            cursor: 0,
+            anchor: None,
+        }
+    }
+
+    #[test]
+    fn colors_added_and_removed_lines() {
+        let lines = diff_lines(&sample_diff());
+
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[1].spans[0].style.fg, Some(Color::Cyan));
+        assert_eq!(lines[3].spans[0].content, "-old");
+        assert_eq!(lines[3].spans[0].style.fg, Some(Color::Red));
+        assert_eq!(lines[4].spans[0].content, "+new");
+        assert_eq!(lines[4].spans[0].style.fg, Some(Color::Green));
+    }
+
+    #[test]
+    fn cursor_stays_within_diff() {
+        let mut state = sample_state();
+
+        state.move_up();
+        assert_eq!(state.cursor, 0);
+
+        state.page_down();
+        assert_eq!(state.cursor, 7);
+
+        state.move_down();
+        assert_eq!(state.cursor, 7);
+
+        state.move_to_top();
+        assert_eq!(state.cursor, 0);
+    }
+
+    #[test]
+    fn jumps_between_hunks() {
+        let mut state = sample_state();
+
+        state.next_hunk();
+        assert_eq!(state.cursor, 1);
+        state.next_hunk();
+        assert_eq!(state.cursor, 5);
+        state.next_hunk();
+        assert_eq!(state.cursor, 5);
+        state.previous_hunk();
+        assert_eq!(state.cursor, 1);
+    }
+
+    #[test]
+    fn selects_hunk_under_cursor_without_anchor() {
+        let mut state = sample_state();
+        assert!(state.selected_lines().is_empty());
+
+        state.cursor = 2;
+        let selected = state.selected_lines();
+        assert_eq!(selected, HashSet::from([(0, 1), (0, 2)]));
+
+        state.cursor = 6;
+        assert_eq!(state.selected_lines(), HashSet::from([(1, 1)]));
+    }
+
+    #[test]
+    fn selects_marked_line_range() {
+        let mut state = sample_state();
+        state.cursor = 4;
+        state.toggle_anchor();
+        state.move_down();
+        state.move_down();
+        state.move_down();
+
+        assert_eq!(state.selected_lines(), HashSet::from([(0, 2), (1, 1)]));
+
+        state.toggle_anchor();
+        assert_eq!(state.anchor, None);
+    }
+
+    #[test]
+    fn keeps_cursor_in_view() {
+        let area = Rect::new(0, 0, 20, 2);
+        let mut buf = Buffer::empty(area);
+        let mut state = sample_state();
+        state.cursor = 4;
+
+        DiffView::new(&state, true).render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].symbol(), "-");
+        assert_eq!(buf[(0, 1)].symbol(), "+");
+        assert!(buf[(0, 1)].modifier.contains(Modifier::REVERSED));
+    }
+}
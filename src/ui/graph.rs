@@ -0,0 +1,270 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+};
+
+use crate::git::Commit;
+
+const LANE_COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Cyan,
+    Color::Red,
+];
+
+/// One lane position of a graph row: the glyph drawn in the lane and whether a
+/// horizontal edge continues into the gap on its right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphCell {
+    pub glyph: char,
+    pub lane: usize,
+    pub joins_right: bool,
+}
+
+pub type GraphRow = Vec<GraphCell>;
+
+/// The lanes left open after the rows laid out so far, each waiting for a
+/// parent further down the log, so the next page continues where this one ends.
+#[derive(Debug, Default, Clone)]
+pub struct GraphLanes(Vec<Option<String>>);
+
+impl GraphLanes {
+    /// Lays out `commits` (children before parents) into lanes, one row per
+    /// commit, below the rows laid out before.
+    pub fn extend(&mut self, commits: &[Commit]) -> Vec<GraphRow> {
+        let carried = std::mem::take(&mut self.0);
+        let mut lanes: Vec<Option<&str>> = carried.iter().map(Option::as_deref).collect();
+        let rows = layout_rows(&mut lanes, commits);
+        self.0 = lanes
+            .into_iter()
+            .map(|lane| lane.map(str::to_string))
+            .collect();
+        rows
+    }
+}
+
+fn layout_rows<'a>(lanes: &mut Vec<Option<&'a str>>, commits: &'a [Commit]) -> Vec<GraphRow> {
+    let mut rows = Vec::with_capacity(commits.len());
+
+    for commit in commits {
+        let id = commit.id.as_str();
+        let before = lanes.clone();
+        let waiting: Vec<usize> = (0..lanes.len())
+            .filter(|&idx| lanes[idx] == Some(id))
+            .collect();
+        let col = match waiting.first() {
+            Some(&col) => col,
+            None => free_lane(lanes, &before),
+        };
+
+        let converging: Vec<usize> = waiting.iter().copied().filter(|&idx| idx != col).collect();
+        for &idx in &converging {
+            lanes[idx] = None;
+        }
+        lanes[col] = commit.parents.first().map(String::as_str);
+
+        let mut opened = Vec::new();
+        let mut joined = Vec::new();
+        for parent in commit.parents.iter().skip(1) {
+            if let Some(idx) =
+                (0..lanes.len()).find(|&idx| idx != col && lanes[idx] == Some(parent.as_str()))
+            {
+                joined.push(idx);
+            } else {
+                let idx = free_lane(lanes, &before);
+                lanes[idx] = Some(parent.as_str());
+                opened.push(idx);
+            }
+        }
+
+        rows.push(build_row(
+            &before,
+            lanes,
+            col,
+            &converging,
+            &opened,
+            &joined,
+        ));
+
+        while lanes.last() == Some(&None) {
+            lanes.pop();
+        }
+    }
+
+    rows
+}
+
+/// Picks a lane that is empty and was already empty before this row, so a lane
+/// ending here is never reused on the same row.
+fn free_lane<'a>(lanes: &mut Vec<Option<&'a str>>, before: &[Option<&'a str>]) -> usize {
+    let free = (0..lanes.len())
+        .find(|&idx| lanes[idx].is_none() && before.get(idx).is_none_or(|lane| lane.is_none()));
+    match free {
+        Some(idx) => idx,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+fn build_row(
+    before: &[Option<&str>],
+    after: &[Option<&str>],
+    col: usize,
+    converging: &[usize],
+    opened: &[usize],
+    joined: &[usize],
+) -> GraphRow {
+    let targets = converging.iter().chain(opened).chain(joined);
+    let span_start = targets.clone().copied().chain([col]).min().unwrap_or(col);
+    let span_end = targets.copied().chain([col]).max().unwrap_or(col);
+    let width = before.len().max(after.len()).max(col + 1);
+    let active = |lanes: &[Option<&str>], idx: usize| lanes.get(idx).is_some_and(Option::is_some);
+
+    let mut row: GraphRow = (0..width)
+        .map(|idx| {
+            let glyph = if idx == col {
+                '●'
+            } else if converging.contains(&idx) {
+                if idx > col { '╯' } else { '╰' }
+            } else if opened.contains(&idx) {
+                if idx > col { '╮' } else { '╭' }
+            } else if joined.contains(&idx) {
+                if idx > col { '┤' } else { '├' }
+            } else if (span_start..=span_end).contains(&idx) {
+                if active(before, idx) && active(after, idx) {
+                    '┼'
+                } else {
+                    '─'
+                }
+            } else if active(before, idx) && active(after, idx) {
+                '│'
+            } else {
+                ' '
+            };
+            GraphCell {
+                glyph,
+                lane: idx,
+                joins_right: idx >= span_start && idx < span_end,
+            }
+        })
+        .collect();
+
+    while row.last().is_some_and(|cell| cell.glyph == ' ') {
+        row.pop();
+    }
+    row
+}
+
+/// Renders `row` padded to `width` lanes so commits line up across the viewport.
+pub fn graph_spans(row: &GraphRow, width: usize) -> Vec<Span<'static>> {
+    let mut spans = Vec::with_capacity(width);
+    for idx in 0..width {
+        let Some(cell) = row.get(idx) else {
+            spans.push(Span::raw("  "));
+            continue;
+        };
+        let color = LANE_COLORS[cell.lane % LANE_COLORS.len()];
+        let gap = if cell.joins_right { '─' } else { ' ' };
+        spans.push(Span::styled(
+            format!("{}{gap}", cell.glyph),
+            Style::default().fg(color),
+        ));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(id: &str, parents: &[&str]) -> Commit {
+        Commit {
+            id: id.to_string(),
+            summary: id.to_string(),
+            branches: Vec::new(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn graph_rows(commits: &[Commit]) -> Vec<GraphRow> {
+        GraphLanes::default().extend(commits)
+    }
+
+    fn render(rows: &[GraphRow]) -> Vec<String> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        rows.iter()
+            .map(|row| {
+                graph_spans(row, width)
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn linear_history_stays_in_one_lane() {
+        let rows = graph_rows(&[commit("c", &["b"]), commit("b", &["a"]), commit("a", &[])]);
+
+        assert_eq!(render(&rows), vec!["●", "●", "●"]);
+    }
+
+    #[test]
+    fn merge_opens_and_closes_a_lane() {
+        let rows = graph_rows(&[
+            commit("m", &["a", "f"]),
+            commit("f", &["b"]),
+            commit("a", &["b"]),
+            commit("b", &[]),
+        ]);
+
+        assert_eq!(render(&rows), vec!["●─╮", "│ ●", "● │", "●─╯"]);
+    }
+
+    #[test]
+    fn divergent_branch_tips_get_their_own_lanes() {
+        let rows = graph_rows(&[
+            commit("x", &["b"]),
+            commit("y", &["b"]),
+            commit("b", &["a"]),
+            commit("a", &[]),
+        ]);
+
+        assert_eq!(render(&rows), vec!["●", "│ ●", "●─╯", "●"]);
+    }
+
+    #[test]
+    fn merge_into_an_existing_lane_joins_it() {
+        let rows = graph_rows(&[
+            commit("t", &["b"]),
+            commit("m", &["a", "b"]),
+            commit("a", &["b"]),
+            commit("b", &[]),
+        ]);
+
+        assert_eq!(render(&rows), vec!["●", "├─●", "│ ●", "●─╯"]);
+    }
+
+    #[test]
+    fn later_pages_continue_the_open_lanes() {
+        let commits = [
+            commit("m", &["a", "f"]),
+            commit("f", &["b"]),
+            commit("a", &["b"]),
+            commit("b", &[]),
+        ];
+        let mut lanes = GraphLanes::default();
+
+        let mut rows = lanes.extend(&commits[..2]);
+        rows.extend(lanes.extend(&commits[2..]));
+
+        assert_eq!(rows, graph_rows(&commits));
+    }
+}
@@ -11,6 +11,14 @@ pub struct TextInput {
 }
 
 impl TextInput {
+    /// Starts with `value`, the cursor at its end.
+    pub fn new(value: String) -> Self {
+        Self {
+            cursor: value.len(),
+            value,
+        }
+    }
+
     pub fn handle_key(&mut self, code: KeyCode) {
         match code {
             KeyCode::Backspace => self.remove_prev(),
@@ -20,6 +20,22 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         .split(vertical[1])[1]
 }
 
+/// The `start..end` rows of a `len`-row list shown in `height` rows, keeping
+/// `focus` near the middle once the list no longer fits.
+pub fn viewport(len: usize, focus: Option<usize>, height: u16) -> (usize, usize) {
+    if len == 0 || height == 0 {
+        return (0, 0);
+    }
+    let visible = height as usize;
+    let focus = focus.unwrap_or(0).min(len.saturating_sub(1));
+    if len <= visible {
+        return (0, len);
+    }
+    let max_start = len - visible;
+    let start = focus.saturating_sub(visible / 2).min(max_start);
+    (start, start + visible)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
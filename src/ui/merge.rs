@@ -0,0 +1,58 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::ui::layout::centered_rect;
+
+/// Asks how to merge `branch` into the current branch `target`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePrompt {
+    pub branch: String,
+    pub target: String,
+}
+
+pub fn render_merge_popup(area: Rect, buf: &mut Buffer, prompt: &MergePrompt) {
+    let popup_area = centered_rect(50, 30, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Merge "),
+            Span::styled(prompt.branch.clone(), Style::default().fg(Color::Cyan)),
+            Span::raw(" into "),
+            Span::styled(prompt.target.clone(), Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[f] ", key),
+            Span::raw("fast-forward only"),
+        ]),
+        Line::from(vec![
+            Span::styled("[n] ", key),
+            Span::raw("merge commit (no fast-forward)"),
+        ]),
+        Line::from(vec![
+            Span::styled("[s] ", key),
+            Span::raw("squash into staged changes"),
+        ]),
+    ];
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(Line::from(Span::styled("Merge Branch", key)))
+                .title_bottom(Line::from(Span::styled("[Esc] Cancel", key)))
+                .borders(Borders::ALL)
+                .border_set(border::THICK)
+                .style(Style::default().fg(Color::Green)),
+        )
+        .render(popup_area, buf);
+}
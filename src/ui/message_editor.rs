@@ -0,0 +1,310 @@
+use std::ops::Range;
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+/// Subject lines longer than this get flagged, as git tooling truncates them.
+pub const SUBJECT_GUIDE: usize = 50;
+/// Body lines (and badly overlong subjects) past this column get flagged.
+pub const BODY_GUIDE: usize = 72;
+
+/// A multi-line commit message: a subject line, a blank line, then the body.
+#[derive(Debug, Default, Clone)]
+pub struct MessageEditor {
+    pub value: String,
+    /// Byte offset into `value`.
+    pub cursor: usize,
+}
+
+impl MessageEditor {
+    pub fn new(value: String) -> Self {
+        Self {
+            cursor: value.len(),
+            value,
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.insert_char('\n'),
+            KeyCode::Backspace => self.remove_prev(),
+            KeyCode::Delete => self.remove_next(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+            KeyCode::Up => self.move_vertically(false),
+            KeyCode::Down => self.move_vertically(true),
+            KeyCode::Home => self.cursor = self.line_start(),
+            KeyCode::End => self.cursor = self.line_end(),
+            KeyCode::Char(c) if !c.is_control() => self.insert_char(c),
+            _ => {}
+        }
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.value.trim().is_empty()
+    }
+
+    pub fn subject(&self) -> &str {
+        self.value.lines().next().unwrap_or("")
+    }
+
+    pub fn has_body(&self) -> bool {
+        self.value
+            .lines()
+            .skip(1)
+            .any(|line| !line.trim().is_empty())
+    }
+
+    /// The cursor's line and column, counted in characters.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let before = &self.value[..self.cursor];
+        let row = before.matches('\n').count();
+        let col = before[self.line_start()..].chars().count();
+        (row, col)
+    }
+
+    fn insert_char(&mut self, c: char) {
+        self.value.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    fn move_left(&mut self) {
+        if let Some(prev) = self.value[..self.cursor].chars().next_back() {
+            self.cursor -= prev.len_utf8();
+        }
+    }
+
+    fn move_right(&mut self) {
+        if let Some(next) = self.value[self.cursor..].chars().next() {
+            self.cursor += next.len_utf8();
+        }
+    }
+
+    fn remove_prev(&mut self) {
+        if let Some(prev) = self.value[..self.cursor].chars().next_back() {
+            let start = self.cursor - prev.len_utf8();
+            self.value.drain(start..self.cursor);
+            self.cursor = start;
+        }
+    }
+
+    fn remove_next(&mut self) {
+        if let Some(next) = self.value[self.cursor..].chars().next() {
+            let end = self.cursor + next.len_utf8();
+            self.value.drain(self.cursor..end);
+        }
+    }
+
+    fn line_start(&self) -> usize {
+        self.value[..self.cursor]
+            .rfind('\n')
+            .map_or(0, |idx| idx + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        self.value[self.cursor..]
+            .find('\n')
+            .map_or(self.value.len(), |idx| self.cursor + idx)
+    }
+
+    /// Moves to the same column of the next or previous line, or its end when shorter.
+    fn move_vertically(&mut self, down: bool) {
+        let (_, col) = self.cursor_position();
+        let target_start = if down {
+            let end = self.line_end();
+            if end == self.value.len() {
+                return;
+            }
+            end + 1
+        } else {
+            let start = self.line_start();
+            if start == 0 {
+                return;
+            }
+            self.value[..start - 1].rfind('\n').map_or(0, |idx| idx + 1)
+        };
+        let line = self.value[target_start..]
+            .split('\n')
+            .next()
+            .unwrap_or_default();
+        let offset = line
+            .char_indices()
+            .nth(col)
+            .map_or(line.len(), |(idx, _)| idx);
+        self.cursor = target_start + offset;
+    }
+
+    /// Word-wraps the message to `width` columns, styling characters past the
+    /// 50/72 guides, and returns the rows with the row holding the cursor.
+    pub fn render_rows(&self, width: usize, show_cursor: bool) -> (Vec<Line<'static>>, usize) {
+        let (cursor_line, cursor_col) = self.cursor_position();
+        let mut rows = Vec::new();
+        let mut cursor_row = 0;
+        for (line_idx, line) in self.value.split('\n').enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            let wrapped = wrap_line(&chars, width.max(1));
+            let last = wrapped.len() - 1;
+            for (wrap_idx, range) in wrapped.into_iter().enumerate() {
+                let has_cursor = show_cursor
+                    && line_idx == cursor_line
+                    && range.start <= cursor_col
+                    && (cursor_col < range.end || wrap_idx == last);
+                if has_cursor {
+                    cursor_row = rows.len();
+                }
+                let cursor = has_cursor.then_some(cursor_col);
+                rows.push(styled_row(&chars, range, line_idx, cursor));
+            }
+        }
+        (rows, cursor_row)
+    }
+}
+
+/// Splits a line into rows of `width` characters, breaking after the last
+/// space that fits (a space may hang past the edge) and only mid-word when a
+/// word is longer than a row.
+fn wrap_line(chars: &[char], width: usize) -> Vec<Range<usize>> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    while chars.len() - start > width {
+        let limit = start + width;
+        let split = (start + 1..=limit + 1)
+            .rev()
+            .find(|&idx| chars[idx - 1] == ' ')
+            .unwrap_or(limit);
+        rows.push(start..split);
+        start = split;
+    }
+    rows.push(start..chars.len());
+    rows
+}
+
+fn styled_row(
+    chars: &[char],
+    range: Range<usize>,
+    line_idx: usize,
+    cursor: Option<usize>,
+) -> Line<'static> {
+    let cursor_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+    let style_at = |col: usize| match line_idx {
+        0 if col >= BODY_GUIDE => Style::default().fg(Color::Red),
+        0 if col >= SUBJECT_GUIDE => Style::default().fg(Color::Yellow),
+        0 => Style::default().fg(Color::White),
+        // The line after the subject should stay blank.
+        1 => Style::default().fg(Color::Red),
+        _ if col >= BODY_GUIDE => Style::default().fg(Color::Red),
+        _ => Style::default().fg(Color::White),
+    };
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut text = String::new();
+    let mut text_style = None;
+    for col in range.clone() {
+        let style = if cursor == Some(col) {
+            cursor_style
+        } else {
+            style_at(col)
+        };
+        if text_style != Some(style) && !text.is_empty() {
+            spans.push(Span::styled(
+                std::mem::take(&mut text),
+                text_style.unwrap_or(style),
+            ));
+        }
+        text_style = Some(style);
+        text.push(chars[col]);
+    }
+    if let Some(style) = text_style
+        && !text.is_empty()
+    {
+        spans.push(Span::styled(text, style));
+    }
+    if cursor.is_some_and(|col| col >= range.end) {
+        spans.push(Span::styled("█", Style::default().fg(Color::Cyan)));
+    }
+    Line::from(spans)
+}
+
+/// Draws faint markers at the 50 and 72 columns wherever the row is empty.
+pub fn render_guides(area: Rect, buf: &mut Buffer) {
+    for guide in [SUBJECT_GUIDE, BODY_GUIDE] {
+        let x = area.x.saturating_add(guide as u16);
+        if x >= area.right() {
+            continue;
+        }
+        for y in area.top()..area.bottom() {
+            if let Some(cell) = buf.cell_mut((x, y))
+                && cell.symbol() == " "
+            {
+                cell.set_symbol("┊");
+                cell.set_style(Style::default().fg(Color::DarkGray));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(editor: &mut MessageEditor, text: &str) {
+        for c in text.chars() {
+            editor.handle_key(if c == '\n' {
+                KeyCode::Enter
+            } else {
+                KeyCode::Char(c)
+            });
+        }
+    }
+
+    #[test]
+    fn enter_inserts_newlines_and_arrows_move_between_lines() {
+        let mut editor = MessageEditor::default();
+        type_text(&mut editor, "Fix login\n\nLonger body text\nend");
+
+        assert_eq!(editor.subject(), "Fix login");
+        assert!(editor.has_body());
+        assert_eq!(editor.cursor_position(), (3, 3));
+
+        editor.handle_key(KeyCode::Up);
+        assert_eq!(editor.cursor_position(), (2, 3));
+        editor.handle_key(KeyCode::End);
+        editor.handle_key(KeyCode::Up);
+        assert_eq!(editor.cursor_position(), (1, 0));
+        editor.handle_key(KeyCode::Up);
+        assert_eq!(editor.cursor_position(), (0, 0));
+        editor.handle_key(KeyCode::Down);
+        editor.handle_key(KeyCode::Down);
+        editor.handle_key(KeyCode::Backspace);
+        assert_eq!(editor.value, "Fix login\nLonger body text\nend");
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        let chars: Vec<char> = "alpha beta gamma".chars().collect();
+        assert_eq!(wrap_line(&chars, 11), vec![0..11, 11..16]);
+        assert_eq!(wrap_line(&chars, 8), vec![0..6, 6..11, 11..16]);
+        assert_eq!(
+            wrap_line(&chars, 4),
+            vec![0..4, 4..6, 6..11, 11..15, 15..16]
+        );
+        assert_eq!(wrap_line(&[], 10), vec![0..0]);
+    }
+
+    #[test]
+    fn flags_subjects_past_the_guides() {
+        let editor = MessageEditor::new(format!("{}\nnot blank", "s".repeat(60)));
+
+        let (rows, _) = editor.render_rows(100, false);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].spans[0].content.len(), SUBJECT_GUIDE);
+        assert_eq!(rows[0].spans[1].style.fg, Some(Color::Yellow));
+        assert_eq!(rows[1].spans[0].style.fg, Some(Color::Red));
+    }
+}
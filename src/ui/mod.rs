@@ -1,9 +1,22 @@
 pub mod branches;
+pub mod cherry_pick;
+pub mod commit_search;
 pub mod commits;
+pub mod conflicts;
 pub mod details;
+pub mod diff;
+pub mod graph;
 pub mod input;
 pub mod layout;
+pub mod merge;
+pub mod message_editor;
+pub mod operation;
 pub mod panel;
 pub mod popup;
+pub mod rebase;
+pub mod remotes;
+pub mod reset;
 pub mod stashes;
 pub mod status;
+pub mod tags;
+pub mod upstream;
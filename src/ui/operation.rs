@@ -0,0 +1,67 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::git::OperationKind;
+use crate::ui::layout::centered_rect;
+
+/// Confirms abandoning the operation in progress, which throws away any
+/// conflict resolution done so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbortPrompt {
+    pub kind: OperationKind,
+}
+
+pub fn render_abort_popup(area: Rect, buf: &mut Buffer, prompt: &AbortPrompt) {
+    let popup_area = centered_rect(50, 30, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let (question, consequence, action) = if prompt.kind == OperationKind::Bisect {
+        (
+            "Reset the bisect in progress?".to_string(),
+            "HEAD goes back to where the bisect started.",
+            "reset",
+        )
+    } else {
+        (
+            format!("Abort the {} in progress?", prompt.kind.as_str()),
+            "HEAD goes back to where it started and conflict resolutions made so far are lost.",
+            "abort",
+        )
+    };
+    let lines = vec![
+        Line::from(question),
+        Line::from(""),
+        Line::from(Span::styled(consequence, Style::default().fg(Color::Red))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[y] ", key),
+            Span::raw(format!("{action}   ")),
+            Span::styled("[n] ", key),
+            Span::raw("keep going"),
+        ]),
+    ];
+
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(Line::from(Span::styled(
+                    format!("{} in progress", prompt.kind.title()),
+                    key,
+                )))
+                .title_bottom(Line::from(Span::styled("[Esc] Cancel", key)))
+                .borders(Borders::ALL)
+                .border_set(border::THICK)
+                .style(Style::default().fg(Color::Green)),
+        )
+        .render(popup_area, buf);
+}
@@ -9,18 +9,35 @@ use ratatui::{
 };
 
 use crate::git::{ChangeType, FileChange, RepoStatus};
-use crate::ui::layout::centered_rect;
+use crate::ui::diff::{DiffState, DiffView};
+use crate::ui::layout::{centered_rect, viewport};
+use crate::ui::message_editor::{MessageEditor, SUBJECT_GUIDE, render_guides};
 pub struct CompartmentPopup;
 
+/// Set while the commit message slot amends HEAD instead of creating a commit.
+#[derive(Debug, Default, Clone)]
+pub struct AmendState {
+    /// The message typed before amending was switched on, restored when it is switched off.
+    pub draft: MessageEditor,
+    /// The upstream that already contains HEAD, if any.
+    pub pushed_to: Option<String>,
+}
+
+pub struct PopupContent<'a> {
+    pub status: &'a RepoStatus,
+    pub selected_change: Option<usize>,
+    pub diff: &'a DiffState,
+    pub commit_input: &'a MessageEditor,
+    pub commit_message_editing: bool,
+    pub amend: Option<&'a AmendState>,
+}
+
 impl CompartmentPopup {
     pub fn render(
         area: Rect,
         buf: &mut Buffer,
         focus: crate::regions::Region,
-        status: &RepoStatus,
-        selected_change: Option<usize>,
-        commit_input: &crate::ui::input::TextInput,
-        commit_message_editing: bool,
+        content: &PopupContent,
     ) {
         let popup_area = centered_rect(80, 80, area);
 
@@ -28,7 +45,7 @@ impl CompartmentPopup {
         Clear.render(popup_area, buf);
 
         let frame = Block::default()
-            .title(keys_hint_line(focus))
+            .title(keys_hint_line(focus, content.status))
             .borders(Borders::ALL)
             .border_set(border::THICK)
             .style(Style::default().fg(Color::Green));
@@ -41,9 +58,18 @@ impl CompartmentPopup {
             .constraints(vec![Constraint::Percentage(30), Constraint::Percentage(70)])
             .split(inner);
 
+        // The message slot grows while focused so a subject and body fit.
+        let message_height = if focus == crate::regions::Region::CommitMessage {
+            45
+        } else {
+            15
+        };
         let right = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Percentage(85), Constraint::Percentage(15)])
+            .constraints(vec![
+                Constraint::Percentage(100 - message_height),
+                Constraint::Percentage(message_height),
+            ])
             .split(columns[1]);
 
         render_slot(
@@ -51,30 +77,21 @@ impl CompartmentPopup {
             buf,
             crate::regions::Region::Changes,
             matches!(focus, crate::regions::Region::Changes),
-            status,
-            selected_change,
-            commit_input,
-            commit_message_editing,
+            content,
         );
         render_slot(
             right[0],
             buf,
             crate::regions::Region::ChangeViewer,
             matches!(focus, crate::regions::Region::ChangeViewer),
-            status,
-            selected_change,
-            commit_input,
-            commit_message_editing,
+            content,
         );
         render_slot(
             right[1],
             buf,
             crate::regions::Region::CommitMessage,
             matches!(focus, crate::regions::Region::CommitMessage),
-            status,
-            None,
-            commit_input,
-            commit_message_editing,
+            content,
         );
     }
 }
@@ -105,13 +122,21 @@ fn dim_background(area: Rect, popup_area: Rect, buf: &mut Buffer) {
     }
 }
 
-fn keys_hint_line(region: crate::regions::Region) -> String {
+fn keys_hint_line(region: crate::regions::Region, status: &RepoStatus) -> String {
     let mut parts = vec!["[q] close".to_string()];
     let instructions = region.instructions();
     if !instructions.is_empty() {
         parts.push("|".to_string());
         parts.extend(instructions.into_iter().map(|s| s.to_string()));
     }
+    if let Some(operation) = &status.operation {
+        parts.push("|".to_string());
+        parts.push(format!(
+            "{}: {}",
+            operation.label(),
+            crate::ui::status::operation_keys(operation)
+        ));
+    }
     format!("Local changes  ·  {}", parts.join("  "))
 }
 
@@ -120,10 +145,7 @@ fn render_slot(
     buf: &mut Buffer,
     region: crate::regions::Region,
     focused: bool,
-    status: &RepoStatus,
-    selected_change: Option<usize>,
-    commit_input: &crate::ui::input::TextInput,
-    commit_message_editing: bool,
+    content: &PopupContent,
 ) {
     let title = match region {
         crate::regions::Region::Changes => Line::from(vec![
@@ -145,13 +167,19 @@ fn render_slot(
     block.render(area, buf);
 
     match region {
-        crate::regions::Region::Changes => render_changes(inner, buf, status, selected_change),
-        crate::regions::Region::ChangeViewer => {
-            render_change_viewer(inner, buf, status, selected_change)
+        crate::regions::Region::Changes => {
+            render_changes(inner, buf, content.status, content.selected_change)
         }
-        crate::regions::Region::CommitMessage => {
-            render_commit_message(inner, buf, commit_input, focused, commit_message_editing)
+        crate::regions::Region::ChangeViewer => {
+            DiffView::new(content.diff, focused).render(inner, buf)
         }
+        crate::regions::Region::CommitMessage => render_commit_message(
+            inner,
+            buf,
+            content.commit_input,
+            content.commit_message_editing,
+            content.amend,
+        ),
         _ => {}
     }
 }
@@ -186,35 +214,77 @@ fn render_changes(
     Paragraph::new(lines).render(area, buf);
 }
 
-fn render_change_viewer(
-    area: Rect,
-    buf: &mut Buffer,
-    status: &RepoStatus,
-    selected_change: Option<usize>,
-) {
-    if let Some(change) = selected_change.and_then(|idx| status.changes.get(idx)) {
-        let message = format!("Change view for {} is not yet implemented", change.path);
-        Paragraph::new(message).render(area, buf);
-    } else {
-        Paragraph::new("Select a change to view").render(area, buf);
-    }
-}
-
 fn render_commit_message(
     area: Rect,
     buf: &mut Buffer,
-    input: &crate::ui::input::TextInput,
-    focused: bool,
+    editor: &MessageEditor,
     editing: bool,
+    amend: Option<&AmendState>,
 ) {
     let mode = if editing { "INSERT" } else { "NAV" };
-    let mut spans = vec![Span::raw(format!("[{mode}] Commit message "))];
-    spans.extend(input.render_line("> "));
-    Paragraph::new(Line::from(spans)).render(area, buf);
+    let label = if amend.is_some() {
+        "Amend HEAD"
+    } else {
+        "Commit message"
+    };
+    let hint = Style::default().fg(Color::DarkGray);
+    let mut header = vec![
+        Span::raw(format!("[{mode}] {label}")),
+        Span::styled(
+            format!(
+                "  ·  subject {}/{SUBJECT_GUIDE}",
+                editor.subject().chars().count()
+            ),
+            hint,
+        ),
+    ];
+    if !editor.is_blank() && !editor.has_body() {
+        header.push(Span::styled(
+            "  ·  no body yet",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if editing {
+        header.push(Span::styled(
+            "  ·  [Enter] new line  [Ctrl+S] commit  [Esc] stop",
+            hint,
+        ));
+    }
+    let mut lines = vec![Line::from(header)];
+    if let Some(upstream) = amend.and_then(|amend| amend.pushed_to.as_deref()) {
+        lines.push(Line::styled(
+            format!("⚠ HEAD is already on {upstream}; amending rewrites pushed history"),
+            Style::default().fg(Color::Red),
+        ));
+    }
+
+    let header_height = (lines.len() as u16).min(area.height);
+    Paragraph::new(lines).render(
+        Rect {
+            height: header_height,
+            ..area
+        },
+        buf,
+    );
+    let editor_area = Rect {
+        y: area.y + header_height,
+        height: area.height - header_height,
+        ..area
+    };
+    if editor_area.height == 0 {
+        return;
+    }
+
+    let (rows, cursor_row) = editor.render_rows(editor_area.width as usize, editing);
+    let start = cursor_row.saturating_sub(editor_area.height as usize - 1);
+    Paragraph::new(rows.into_iter().skip(start).collect::<Vec<_>>()).render(editor_area, buf);
+    render_guides(editor_area, buf);
 }
 
 fn change_line(change: &FileChange) -> Line<'static> {
-    let (label, color) = if change.staged {
+    let (label, color) = if change.change == ChangeType::Unmerged {
+        ("conflict", Color::Magenta)
+    } else if change.staged {
         ("staged", Color::Green)
     } else {
         ("unstaged", Color::Red)
@@ -227,24 +297,10 @@ fn change_line(change: &FileChange) -> Line<'static> {
     ])
 }
 
-fn viewport(len: usize, focused: Option<usize>, height: u16) -> (usize, usize) {
-    if len == 0 || height == 0 {
-        return (0, 0);
-    }
-    let visible = height as usize;
-    let focus = focused.unwrap_or(0).min(len.saturating_sub(1));
-    if len <= visible {
-        return (0, len);
-    }
-    let max_start = len - visible;
-    let start = focus.saturating_sub(visible / 2).min(max_start);
-    let end = start + visible;
-    (start, end)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::{ChangeType, DiffHunk, DiffLine, DiffLineKind, FileDiff};
 
     #[test]
     fn layout_matches_requested_split() {
@@ -280,15 +336,20 @@ mod tests {
         let outer = Rect::new(0, 0, 100, 100);
         let mut buf = Buffer::empty(outer);
         let status = RepoStatus::default();
-        let input = crate::ui::input::TextInput::default();
+        let input = MessageEditor::default();
+        let diff = DiffState::default();
         CompartmentPopup::render(
             outer,
             &mut buf,
             crate::regions::Region::Changes,
-            &status,
-            None,
-            &input,
-            false,
+            &PopupContent {
+                status: &status,
+                selected_change: None,
+                diff: &diff,
+                commit_input: &input,
+                commit_message_editing: false,
+                amend: None,
+            },
         );
 
         let popup = centered_rect(80, 80, outer);
@@ -310,15 +371,20 @@ mod tests {
         let outer = Rect::new(0, 0, 50, 20);
         let mut buf = Buffer::empty(outer);
         let status = RepoStatus::default();
-        let input = crate::ui::input::TextInput::default();
+        let input = MessageEditor::default();
+        let diff = DiffState::default();
         CompartmentPopup::render(
             outer,
             &mut buf,
             crate::regions::Region::Changes,
-            &status,
-            None,
-            &input,
-            false,
+            &PopupContent {
+                status: &status,
+                selected_change: None,
+                diff: &diff,
+                commit_input: &input,
+                commit_message_editing: false,
+                amend: None,
+            },
         );
 
         let popup = centered_rect(80, 80, outer);
@@ -378,16 +444,21 @@ mod tests {
             ],
             ..RepoStatus::default()
         };
-        let input = crate::ui::input::TextInput::default();
+        let input = MessageEditor::default();
+        let diff = DiffState::default();
 
         CompartmentPopup::render(
             outer,
             &mut buf,
             crate::regions::Region::Changes,
-            &status,
-            Some(1),
-            &input,
-            false,
+            &PopupContent {
+                status: &status,
+                selected_change: Some(1),
+                diff: &diff,
+                commit_input: &input,
+                commit_message_editing: false,
+                amend: None,
+            },
         );
 
         let popup = centered_rect(80, 80, outer);
@@ -412,7 +483,7 @@ mod tests {
     }
 
     #[test]
-    fn change_viewer_shows_placeholder_with_selected_file() {
+    fn change_viewer_shows_diff_for_selected_file() {
         let outer = Rect::new(0, 0, 80, 20);
         let mut buf = Buffer::empty(outer);
         let status = RepoStatus {
@@ -423,16 +494,40 @@ mod tests {
             }],
             ..RepoStatus::default()
         };
-        let input = crate::ui::input::TextInput::default();
+        let input = MessageEditor::default();
+        let diff = DiffState {
+            change: Some(status.changes[0].clone()),
+            diff: Some(FileDiff {
+                header: vec!["diff --git a/dir/file.rs b/dir/file.rs".into()],
+                hunks: vec![DiffHunk {
+                    header: "@@ -1 +1 @@".into(),
+                    old_start: 1,
+                    old_lines: 1,
+                    new_start: 1,
+                    new_lines: 1,
+                    lines: vec![DiffLine {
+                        kind: DiffLineKind::Added,
+                        content: "added line".into(),
+                    }],
+                }],
+            }),
+            status: None,
+            cursor: 0,
+            anchor: None,
+        };
 
         CompartmentPopup::render(
             outer,
             &mut buf,
             crate::regions::Region::ChangeViewer,
-            &status,
-            Some(0),
-            &input,
-            false,
+            &PopupContent {
+                status: &status,
+                selected_change: Some(0),
+                diff: &diff,
+                commit_input: &input,
+                commit_message_editing: false,
+                amend: None,
+            },
         );
 
         let popup = centered_rect(80, 80, outer);
@@ -461,7 +556,7 @@ mod tests {
         }
 
         assert!(
-            text.contains("Change view for dir/file.rs"),
+            text.contains("diff --git a/dir/file.rs") && text.contains("+added line"),
             "text was: {text}"
         );
     }
@@ -0,0 +1,386 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::{RebaseAction, RebaseStep};
+use crate::ui::layout::{centered_rect, viewport};
+use crate::ui::message_editor::{MessageEditor, render_guides};
+
+/// An interactive rebase plan being edited, oldest commit first as in git's todo list.
+#[derive(Debug)]
+pub struct RebasePlan {
+    pub base: String,
+    pub steps: Vec<RebaseStep>,
+    pub hovered: usize,
+    /// The new message for the hovered step while it is being reworded.
+    pub reword_input: Option<MessageEditor>,
+}
+
+impl RebasePlan {
+    pub fn new(base: String, steps: Vec<RebaseStep>) -> Self {
+        Self {
+            base,
+            steps,
+            hovered: 0,
+            reword_input: None,
+        }
+    }
+
+    pub fn move_hover_up(&mut self) {
+        self.hovered = self.hovered.saturating_sub(1);
+    }
+
+    pub fn move_hover_down(&mut self) {
+        if self.hovered + 1 < self.steps.len() {
+            self.hovered += 1;
+        }
+    }
+
+    /// Moves the hovered commit one place earlier in the plan.
+    pub fn move_step_up(&mut self) {
+        if self.hovered > 0 {
+            self.steps.swap(self.hovered, self.hovered - 1);
+            self.hovered -= 1;
+        }
+    }
+
+    pub fn move_step_down(&mut self) {
+        if self.hovered + 1 < self.steps.len() {
+            self.steps.swap(self.hovered, self.hovered + 1);
+            self.hovered += 1;
+        }
+    }
+
+    pub fn set_action(&mut self, action: RebaseAction) {
+        if let Some(step) = self.steps.get_mut(self.hovered) {
+            step.action = action;
+        }
+    }
+
+    pub fn start_reword(&mut self) {
+        let Some(step) = self.steps.get(self.hovered) else {
+            return;
+        };
+        let value = step
+            .message
+            .clone()
+            .unwrap_or_else(|| step.original_message.clone());
+        self.reword_input = Some(MessageEditor::new(value));
+    }
+
+    pub fn finish_reword(&mut self) {
+        let Some(input) = self.reword_input.take() else {
+            return;
+        };
+        let message = input.value.trim_end();
+        if let Some(step) = self.steps.get_mut(self.hovered)
+            && !message.is_empty()
+        {
+            step.action = RebaseAction::Reword;
+            step.message = Some(message.to_string());
+        }
+    }
+
+    /// Catches plans git would reject before any commit is touched.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut kept = self
+            .steps
+            .iter()
+            .filter(|step| step.action != RebaseAction::Drop);
+        match kept.next() {
+            None => Err("Every commit is dropped; nothing to rebase".to_string()),
+            Some(first) if matches!(first.action, RebaseAction::Squash | RebaseAction::Fixup) => {
+                Err(format!(
+                    "Cannot {} {}: no earlier commit to fold it into",
+                    first.action.as_str(),
+                    first.short_id
+                ))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// Confirms rebasing the current branch `branch` onto `onto`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebaseOntoPrompt {
+    pub branch: String,
+    pub onto: String,
+    /// Commits of `branch` missing from `onto`, which the rebase replays.
+    pub replay: usize,
+    /// Commits of `onto` that `branch` is missing.
+    pub behind: usize,
+}
+
+impl RebaseOntoPrompt {
+    fn summary(&self) -> String {
+        let commits = |count: usize| {
+            if count == 1 {
+                "1 commit".to_string()
+            } else {
+                format!("{count} commits")
+            }
+        };
+        match (self.replay, self.behind) {
+            (_, 0) => format!("{} already contains {}", self.branch, self.onto),
+            (0, behind) => format!("Fast-forwards {} by {}", self.branch, commits(behind)),
+            (replay, behind) => format!(
+                "Replays {} of {} on top of {} new in {}",
+                commits(replay),
+                self.branch,
+                commits(behind),
+                self.onto
+            ),
+        }
+    }
+}
+
+pub fn render_rebase_onto_popup(area: Rect, buf: &mut Buffer, prompt: &RebaseOntoPrompt) {
+    let popup_area = centered_rect(50, 25, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Rebase "),
+            Span::styled(prompt.branch.clone(), Style::default().fg(Color::Cyan)),
+            Span::raw(" onto "),
+            Span::styled(prompt.onto.clone(), Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(""),
+        Line::from(prompt.summary()),
+    ];
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(Line::from(Span::styled("Rebase Branch", key)))
+                .title_bottom(Line::from(vec![
+                    Span::styled("[Enter] Rebase", key),
+                    Span::raw("   "),
+                    Span::styled("[Esc] Cancel", key),
+                ]))
+                .borders(Borders::ALL)
+                .border_set(border::THICK)
+                .style(Style::default().fg(Color::Green)),
+        )
+        .render(popup_area, buf);
+}
+
+pub fn render_rebase_popup(area: Rect, buf: &mut Buffer, plan: &RebasePlan) {
+    let popup_area = centered_rect(70, 60, area);
+    Clear.render(popup_area, buf);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!("Interactive rebase onto {}  ·  oldest first", plan.base),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .title_bottom(Line::from(
+            "[p]ick [r]eword [e]dit [s]quash [f]ixup [d]rop  [Shift+↑↓] reorder  [Enter] run  [Esc] cancel",
+        ))
+        .borders(Borders::ALL)
+        .border_set(border::THICK)
+        .style(Style::default().fg(Color::Green));
+    let inner = block.inner(popup_area);
+    block.render(popup_area, buf);
+
+    let mut list_area = inner;
+    if let Some(editor) = &plan.reword_input {
+        let editor_height = (inner.height / 2).max(inner.height.min(2));
+        list_area.height = inner.height - editor_height;
+        render_reword_editor(
+            Rect {
+                y: list_area.bottom(),
+                height: editor_height,
+                ..inner
+            },
+            buf,
+            editor,
+        );
+    }
+
+    let (start, end) = viewport(plan.steps.len(), Some(plan.hovered), list_area.height);
+    let lines: Vec<Line> = plan.steps[start..end]
+        .iter()
+        .enumerate()
+        .map(|(offset, step)| step_line(step, start + offset == plan.hovered))
+        .collect();
+    Paragraph::new(lines)
+        .alignment(Alignment::Left)
+        .render(list_area, buf);
+}
+
+fn render_reword_editor(area: Rect, buf: &mut Buffer, editor: &MessageEditor) {
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            "New message  [Ctrl+S] done  [Esc] cancel",
+            Style::default().fg(Color::Cyan),
+        )))
+        .borders(Borders::TOP)
+        .style(Style::default().fg(Color::Green));
+    let editor_area = block.inner(area);
+    block.render(area, buf);
+    if editor_area.height == 0 {
+        return;
+    }
+
+    let (rows, cursor_row) = editor.render_rows(editor_area.width as usize, true);
+    let start = cursor_row.saturating_sub(editor_area.height as usize - 1);
+    Paragraph::new(rows.into_iter().skip(start).collect::<Vec<_>>()).render(editor_area, buf);
+    render_guides(editor_area, buf);
+}
+
+fn step_line(step: &RebaseStep, hovered: bool) -> Line<'static> {
+    let color = match step.action {
+        RebaseAction::Pick => Color::Green,
+        RebaseAction::Reword | RebaseAction::Edit => Color::Cyan,
+        RebaseAction::Squash | RebaseAction::Fixup => Color::Yellow,
+        RebaseAction::Drop => Color::Red,
+    };
+    let mut text_style = Style::default().fg(Color::White);
+    if step.action == RebaseAction::Drop {
+        text_style = text_style.add_modifier(Modifier::CROSSED_OUT);
+    }
+    let text = match (&step.message, step.action) {
+        (Some(message), RebaseAction::Reword) => {
+            let subject = message.lines().next().unwrap_or_default();
+            format!("{subject}  (was: {})", step.summary)
+        }
+        _ => step.summary.clone(),
+    };
+    let mut line = Line::from(vec![
+        Span::styled(
+            format!("{:<7}", step.action.as_str()),
+            Style::default().fg(color),
+        ),
+        Span::styled(
+            format!("{} ", step.short_id),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(text, text_style),
+    ]);
+    if hovered {
+        line = line.style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str) -> RebaseStep {
+        RebaseStep {
+            action: RebaseAction::Pick,
+            id: id.to_string(),
+            short_id: id.to_string(),
+            summary: format!("commit {id}"),
+            original_message: format!(
+                "commit {id}\n\nBody of {id}.\n\nSigned-off-by: Dev <dev@example.com>"
+            ),
+            message: None,
+        }
+    }
+
+    fn plan(ids: &[&str]) -> RebasePlan {
+        RebasePlan::new("base".to_string(), ids.iter().map(|id| step(id)).collect())
+    }
+
+    fn ids(plan: &RebasePlan) -> Vec<&str> {
+        plan.steps.iter().map(|step| step.id.as_str()).collect()
+    }
+
+    #[test]
+    fn rebase_onto_summary_counts_replayed_commits() {
+        let mut prompt = RebaseOntoPrompt {
+            branch: "feature".to_string(),
+            onto: "main".to_string(),
+            replay: 3,
+            behind: 1,
+        };
+        assert_eq!(
+            prompt.summary(),
+            "Replays 3 commits of feature on top of 1 commit new in main"
+        );
+
+        prompt.replay = 0;
+        assert_eq!(prompt.summary(), "Fast-forwards feature by 1 commit");
+        prompt.behind = 0;
+        assert_eq!(prompt.summary(), "feature already contains main");
+    }
+
+    #[test]
+    fn reorders_steps_with_the_hover() {
+        let mut plan = plan(&["a", "b", "c"]);
+
+        plan.move_step_down();
+        assert_eq!(ids(&plan), vec!["b", "a", "c"]);
+        assert_eq!(plan.hovered, 1);
+        plan.move_step_down();
+        plan.move_step_down();
+        assert_eq!(ids(&plan), vec!["b", "c", "a"]);
+        assert_eq!(plan.hovered, 2);
+        plan.move_hover_up();
+        plan.move_step_up();
+        assert_eq!(ids(&plan), vec!["c", "b", "a"]);
+        assert_eq!(plan.hovered, 0);
+    }
+
+    #[test]
+    fn reword_keeps_the_new_message() {
+        let mut plan = plan(&["a", "b"]);
+        plan.move_hover_down();
+
+        plan.start_reword();
+        assert_eq!(
+            plan.reword_input.as_ref().unwrap().value,
+            "commit b\n\nBody of b.\n\nSigned-off-by: Dev <dev@example.com>"
+        );
+        let input = plan.reword_input.as_mut().unwrap();
+        input.value = input.value.replace("commit b", "better message");
+        plan.finish_reword();
+
+        assert_eq!(plan.steps[1].action, RebaseAction::Reword);
+        assert_eq!(
+            plan.steps[1].message.as_deref(),
+            Some("better message\n\nBody of b.\n\nSigned-off-by: Dev <dev@example.com>")
+        );
+        assert!(plan.reword_input.is_none());
+
+        plan.start_reword();
+        assert_eq!(
+            plan.reword_input.as_ref().unwrap().value,
+            plan.steps[1].message.clone().unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_plans_git_would_refuse() {
+        let mut plan = plan(&["a", "b"]);
+        assert!(plan.validate().is_ok());
+
+        plan.set_action(RebaseAction::Squash);
+        assert_eq!(
+            plan.validate().unwrap_err(),
+            "Cannot squash a: no earlier commit to fold it into"
+        );
+
+        plan.set_action(RebaseAction::Drop);
+        plan.move_hover_down();
+        plan.set_action(RebaseAction::Drop);
+        assert_eq!(
+            plan.validate().unwrap_err(),
+            "Every commit is dropped; nothing to rebase"
+        );
+    }
+}
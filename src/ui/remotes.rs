@@ -0,0 +1,235 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::{self, Remote};
+use crate::ui::input::TextInput;
+use crate::ui::layout::centered_rect;
+
+/// What the input line at the bottom of the remotes popup is asking for.
+#[derive(Debug, Clone)]
+pub enum RemotePrompt {
+    AddName(TextInput),
+    AddUrl {
+        name: String,
+        input: TextInput,
+    },
+    Rename {
+        remote: String,
+        input: TextInput,
+    },
+    SetUrl {
+        remote: String,
+        input: TextInput,
+        push: bool,
+    },
+    ConfirmRemove(String),
+}
+
+impl RemotePrompt {
+    pub fn label(&self) -> String {
+        match self {
+            RemotePrompt::AddName(_) => "New remote name: ".to_string(),
+            RemotePrompt::AddUrl { name, .. } => format!("URL for {name}: "),
+            RemotePrompt::Rename { remote, .. } => format!("Rename {remote} to: "),
+            RemotePrompt::SetUrl {
+                remote, push: true, ..
+            } => format!("Push URL for {remote}: "),
+            RemotePrompt::SetUrl { remote, .. } => format!("URL for {remote}: "),
+            RemotePrompt::ConfirmRemove(remote) => {
+                format!("Remove {remote} and its remote-tracking branches? [y] yes  [n] no")
+            }
+        }
+    }
+
+    pub fn input_mut(&mut self) -> Option<&mut TextInput> {
+        match self {
+            RemotePrompt::AddName(input)
+            | RemotePrompt::AddUrl { input, .. }
+            | RemotePrompt::Rename { input, .. }
+            | RemotePrompt::SetUrl { input, .. } => Some(input),
+            RemotePrompt::ConfirmRemove(_) => None,
+        }
+    }
+
+    fn input(&self) -> Option<&TextInput> {
+        match self {
+            RemotePrompt::AddName(input)
+            | RemotePrompt::AddUrl { input, .. }
+            | RemotePrompt::Rename { input, .. }
+            | RemotePrompt::SetUrl { input, .. } => Some(input),
+            RemotePrompt::ConfirmRemove(_) => None,
+        }
+    }
+}
+
+/// The remotes popup: configured remotes with their URLs, and an optional prompt.
+#[derive(Debug, Default)]
+pub struct RemotesState {
+    pub remotes: Vec<Remote>,
+    pub status: Option<String>,
+    pub hovered: usize,
+    pub prompt: Option<RemotePrompt>,
+}
+
+impl RemotesState {
+    pub fn load(previous_hovered: usize) -> Self {
+        match git::list_remotes() {
+            Ok(remotes) => Self {
+                hovered: previous_hovered.min(remotes.len().saturating_sub(1)),
+                remotes,
+                ..Self::default()
+            },
+            Err(err) => Self {
+                status: Some(err),
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn move_hover_up(&mut self) {
+        self.hovered = self.hovered.saturating_sub(1);
+    }
+
+    pub fn move_hover_down(&mut self) {
+        if self.hovered + 1 < self.remotes.len() {
+            self.hovered += 1;
+        }
+    }
+
+    pub fn hovered_remote(&self) -> Option<&Remote> {
+        self.remotes.get(self.hovered)
+    }
+}
+
+pub fn render_remotes_popup(area: Rect, buf: &mut Buffer, state: &RemotesState) {
+    let popup_area = centered_rect(70, 50, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let keys = if state.prompt.is_some() {
+        vec![
+            Span::styled("[Enter] Save", key),
+            Span::raw("   "),
+            Span::styled("[Esc] Cancel", key),
+        ]
+    } else {
+        [
+            "[f] fetch",
+            "[a] add",
+            "[r] rename",
+            "[e] edit URL",
+            "[u] edit push URL",
+            "[x] remove",
+            "[Esc] close",
+        ]
+        .into_iter()
+        .flat_map(|label| [Span::styled(label, key), Span::raw("  ")])
+        .collect()
+    };
+    let block = Block::default()
+        .title(Line::from(Span::styled("Remotes", key)))
+        .title_bottom(Line::from(keys))
+        .borders(Borders::ALL)
+        .border_set(border::THICK)
+        .style(Style::default().fg(Color::Green));
+    let inner = block.inner(popup_area);
+    block.render(popup_area, buf);
+
+    let mut list_area = inner;
+    if let Some(prompt) = &state.prompt {
+        list_area.height = list_area.height.saturating_sub(1);
+        let prompt_area = Rect {
+            y: list_area.bottom(),
+            height: inner.height.min(1),
+            ..inner
+        };
+        let label = prompt.label();
+        let line = match prompt.input() {
+            Some(input) => Line::from(input.render_line(&label)),
+            None => Line::styled(label.as_str(), Style::default().fg(Color::Red)),
+        };
+        Paragraph::new(line)
+            .style(Style::default().fg(Color::White))
+            .render(prompt_area, buf);
+    }
+
+    if let Some(status) = state.status.as_deref() {
+        Paragraph::new(status)
+            .style(Style::default().fg(Color::Red))
+            .render(list_area, buf);
+        return;
+    }
+    if state.remotes.is_empty() {
+        Paragraph::new("No remotes configured; [a] adds one").render(list_area, buf);
+        return;
+    }
+
+    let lines: Vec<Line> = state
+        .remotes
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, remote)| {
+            let lines = remote_lines(remote);
+            let hovered = idx == state.hovered;
+            lines.into_iter().map(move |line| {
+                if hovered {
+                    line.style(Style::default().add_modifier(Modifier::REVERSED))
+                } else {
+                    line
+                }
+            })
+        })
+        .collect();
+    Paragraph::new(lines).render(list_area, buf);
+}
+
+/// The remote's name and fetch URL, plus a second line when it pushes elsewhere.
+fn remote_lines(remote: &Remote) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            format!("{:<12} ", remote.name),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(remote.fetch_url.clone(), Style::default().fg(Color::White)),
+    ])];
+    if let Some(push_url) = &remote.push_url {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<12} ", "  push"),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(push_url.clone(), Style::default().fg(Color::White)),
+        ]));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separate_push_urls_get_their_own_line() {
+        let text =
+            |line: &Line| -> String { line.spans.iter().map(|s| s.content.as_ref()).collect() };
+        let mut remote = Remote {
+            name: "upstream".to_string(),
+            fetch_url: "https://host/them/repo.git".to_string(),
+            push_url: None,
+        };
+        assert_eq!(remote_lines(&remote).len(), 1);
+
+        remote.push_url = Some("no-push".to_string());
+        let lines = remote_lines(&remote);
+        assert_eq!(text(&lines[0]), "upstream     https://host/them/repo.git");
+        assert_eq!(text(&lines[1]), "  push       no-push");
+    }
+}
@@ -0,0 +1,173 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::{ChangeType, ResetPreview};
+use crate::ui::input::TextInput;
+use crate::ui::layout::centered_rect;
+
+/// Asks how to reset `branch` to the hovered commit, listing what it leaves behind.
+#[derive(Debug, Clone)]
+pub struct ResetPrompt {
+    pub target: String,
+    pub summary: String,
+    /// The current branch, or `HEAD` when detached; typed to confirm a hard reset.
+    pub branch: String,
+    pub preview: ResetPreview,
+    /// The typed confirmation, open once a hard reset is chosen.
+    pub confirm_input: Option<TextInput>,
+}
+
+impl ResetPrompt {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirm_input
+            .as_ref()
+            .is_some_and(|input| input.value.trim() == self.branch)
+    }
+}
+
+pub fn render_reset_popup(area: Rect, buf: &mut Buffer, prompt: &ResetPrompt) {
+    let popup_area = centered_rect(60, 60, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let keys = if prompt.confirm_input.is_some() {
+        vec![
+            Span::styled("[Enter] Hard reset", key),
+            Span::raw("   "),
+            Span::styled("[Esc] Back", key),
+        ]
+    } else {
+        vec![
+            Span::styled("[s] soft", key),
+            Span::raw("  "),
+            Span::styled("[m] mixed", key),
+            Span::raw("  "),
+            Span::styled("[h] hard", key),
+            Span::raw("   "),
+            Span::styled("[Esc] Cancel", key),
+        ]
+    };
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!("Reset {} to {}", prompt.branch, prompt.target),
+            key,
+        )))
+        .title_bottom(Line::from(keys))
+        .borders(Borders::ALL)
+        .border_set(border::THICK)
+        .style(Style::default().fg(Color::Green));
+    let inner = block.inner(popup_area);
+    block.render(popup_area, buf);
+
+    let mut list_area = inner;
+    if let Some(input) = &prompt.confirm_input {
+        list_area.height = list_area.height.saturating_sub(1);
+        let prompt_area = Rect {
+            y: list_area.bottom(),
+            height: inner.height.min(1),
+            ..inner
+        };
+        let label = format!("Type {} to confirm: ", prompt.branch);
+        Paragraph::new(Line::from(input.render_line(&label)))
+            .style(Style::default().fg(Color::Red))
+            .render(prompt_area, buf);
+    }
+
+    Paragraph::new(preview_lines(prompt)).render(list_area, buf);
+}
+
+fn preview_lines(prompt: &ResetPrompt) -> Vec<Line<'static>> {
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{} ", prompt.target),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(prompt.summary.clone(), Style::default().fg(Color::White)),
+        ]),
+        Line::from(""),
+    ];
+
+    let commits = &prompt.preview.commits;
+    if commits.is_empty() {
+        lines.push(Line::from(format!("No commits leave {}", prompt.branch)));
+    } else {
+        lines.push(Line::styled(
+            format!("Commits leaving {} ({}):", prompt.branch, commits.len()),
+            heading,
+        ));
+        lines.extend(commits.iter().map(|commit| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {} ", commit.id),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(commit.summary.clone(), Style::default().fg(Color::White)),
+            ])
+        }));
+    }
+
+    lines.push(Line::from(""));
+    let changes = &prompt.preview.changes;
+    if changes.is_empty() {
+        lines.push(Line::from("No uncommitted changes"));
+    } else {
+        lines.push(Line::styled(
+            format!(
+                "Uncommitted changes a hard reset discards ({}):",
+                changes.len()
+            ),
+            heading,
+        ));
+        lines.extend(changes.iter().map(|change| {
+            let marker = match change.change {
+                ChangeType::Added => "A",
+                ChangeType::Deleted => "D",
+                ChangeType::Renamed => "R",
+                ChangeType::Unmerged => "U",
+                _ => "M",
+            };
+            Line::styled(
+                format!("  {marker} {}", change.path),
+                Style::default().fg(Color::Red),
+            )
+        }));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hard_reset_needs_the_branch_name_typed() {
+        let mut prompt = ResetPrompt {
+            target: "abc1234".to_string(),
+            summary: "first".to_string(),
+            branch: "main".to_string(),
+            preview: ResetPreview::default(),
+            confirm_input: None,
+        };
+        assert!(!prompt.is_confirmed());
+
+        prompt.confirm_input = Some(TextInput {
+            value: "mai".to_string(),
+            cursor: 3,
+        });
+        assert!(!prompt.is_confirmed());
+        prompt.confirm_input.as_mut().unwrap().value = "main ".to_string();
+        assert!(prompt.is_confirmed());
+    }
+}
@@ -1,27 +1,182 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    widgets::{Paragraph, Widget},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, Paragraph, Widget},
 };
 
+use crate::git::{self, Stash};
 use crate::regions::Region;
+use crate::ui::layout::viewport;
 
 use super::panel::PanelBlock;
 
 pub type StashesPanel<W = super::panel::Empty> = PanelBlock<W>;
 
-pub fn panel(selected: bool) -> StashesPanel {
-    PanelBlock::new(Region::Stashes, selected)
-}
-
 pub fn panel_with_child<W: Widget>(selected: bool, child: W) -> StashesPanel<W> {
     PanelBlock::with_child(Region::Stashes, selected, child)
 }
 
-pub struct StashesView;
+#[derive(Debug, Default)]
+pub struct StashesState {
+    pub stashes: Vec<Stash>,
+    pub status: Option<String>,
+    pub hovered: Option<usize>,
+}
+
+impl StashesState {
+    pub fn refresh(previous_hovered: Option<usize>) -> Self {
+        Self::apply(git::fetch_stashes(), previous_hovered)
+    }
+
+    pub fn apply(fetched: Result<Vec<Stash>, String>, previous_hovered: Option<usize>) -> Self {
+        match fetched {
+            Ok(stashes) => {
+                let hovered = if stashes.is_empty() {
+                    None
+                } else {
+                    Some(previous_hovered.unwrap_or(0).min(stashes.len() - 1))
+                };
+                Self {
+                    stashes,
+                    status: None,
+                    hovered,
+                }
+            }
+            Err(err) => Self {
+                stashes: Vec::new(),
+                status: Some(err),
+                hovered: None,
+            },
+        }
+    }
+
+    pub fn move_hover_up(&mut self) {
+        self.update_hover(|idx, len| (idx + len - 1) % len);
+    }
+
+    pub fn move_hover_down(&mut self) {
+        self.update_hover(|idx, len| (idx + 1) % len);
+    }
+
+    fn update_hover<F: FnOnce(usize, usize) -> usize>(&mut self, next: F) {
+        let len = self.stashes.len();
+        if len == 0 {
+            self.hovered = None;
+            return;
+        }
+        self.hovered = Some(match self.hovered {
+            Some(idx) => next(idx, len),
+            None => 0,
+        });
+    }
+
+    pub fn hovered_stash(&self) -> Option<&Stash> {
+        self.hovered.and_then(|idx| self.stashes.get(idx))
+    }
+}
+
+pub struct StashesView<'a> {
+    state: &'a StashesState,
+}
+
+impl<'a> StashesView<'a> {
+    pub fn new(state: &'a StashesState) -> Self {
+        Self { state }
+    }
+}
 
-impl Widget for StashesView {
+impl Widget for StashesView<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        Paragraph::new("Not implemented yet").render(area, buf);
+        if let Some(status) = self.state.status.as_deref() {
+            Paragraph::new(status)
+                .style(Style::default().fg(Color::Red))
+                .render(area, buf);
+            return;
+        }
+
+        if self.state.stashes.is_empty() {
+            Paragraph::new("No stashes").render(area, buf);
+            return;
+        }
+
+        let (start, end) = viewport(self.state.stashes.len(), self.state.hovered, area.height);
+        let items: Vec<ListItem> = self.state.stashes[start..end]
+            .iter()
+            .enumerate()
+            .map(|(offset, stash)| {
+                let is_hovered = Some(start + offset) == self.state.hovered;
+                let style = if is_hovered {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(stash_line(stash)).style(style)
+            })
+            .collect();
+
+        List::new(items).render(area, buf);
+    }
+}
+
+fn stash_line(stash: &Stash) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        format!("{{{}}}", stash.index),
+        Style::default().fg(Color::Cyan),
+    )];
+    if let Some(branch) = &stash.branch {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            branch.clone(),
+            Style::default().fg(Color::Green),
+        ));
+    }
+    spans.push(Span::raw(" "));
+    spans.push(Span::raw(stash.message.clone()));
+    if !stash.age.is_empty() {
+        spans.push(Span::styled(
+            format!(" ({})", stash.age),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_stash(index: usize, branch: Option<&str>, message: &str) -> Stash {
+        Stash {
+            index,
+            branch: branch.map(str::to_string),
+            message: message.to_string(),
+            age: "2 hours ago".to_string(),
+        }
+    }
+
+    #[test]
+    fn stash_line_shows_index_branch_message_and_age() {
+        let line = stash_line(&make_stash(1, Some("main"), "wip"));
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+
+        assert_eq!(text, "{1} main wip (2 hours ago)");
+    }
+
+    #[test]
+    fn hover_moves_wrap() {
+        let mut state = StashesState {
+            stashes: vec![make_stash(0, None, "a"), make_stash(1, None, "b")],
+            status: None,
+            hovered: None,
+        };
+
+        state.move_hover_down();
+        assert_eq!(state.hovered, Some(0));
+        state.move_hover_up();
+        assert_eq!(state.hovered, Some(1));
+        state.move_hover_down();
+        assert_eq!(state.hovered, Some(0));
     }
 }
@@ -1,25 +1,35 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    prelude::Stylize,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
 use crate::{
-    git::{ChangeType, FileChange, RepoStatus},
+    git::{ChangeType, FileChange, RepoOperation, RepoStatus},
+    jobs::RunningJob,
     regions::Region,
 };
 
 pub struct StatusBox<'a> {
     status: &'a RepoStatus,
     region: Region,
+    jobs: Vec<&'a RunningJob>,
 }
 
 impl<'a> StatusBox<'a> {
     pub fn new(status: &'a RepoStatus, region: Region) -> Self {
-        Self { status, region }
+        Self {
+            status,
+            region,
+            jobs: Vec::new(),
+        }
+    }
+
+    pub fn with_jobs(mut self, jobs: Vec<&'a RunningJob>) -> Self {
+        self.jobs = jobs;
+        self
     }
 }
 
@@ -41,7 +51,10 @@ impl Widget for StatusBox<'_> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let lines = render_lines(self.status);
+        let mut lines = render_lines(self.status);
+        if let Some(first) = lines.first_mut() {
+            first.spans.extend(jobs_spans(&self.jobs));
+        }
         Paragraph::new(lines).render(inner, buf);
     }
 }
@@ -63,11 +76,69 @@ fn overview_line(status: &RepoStatus) -> Line<'static> {
     let summary_style = Style::default().fg(Color::Yellow);
     spans.push(Span::styled(summary_text(status), summary_style));
 
+    if let Some(operation) = &status.operation {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            format!("⚠ {}", operation.label()),
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            format!("  {}", operation_keys(operation)),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+
     Line::from(spans)
 }
 
+/// The keys that move an in-progress operation along, e.g. "[C] continue  [A] abort".
+pub fn operation_keys(operation: &RepoOperation) -> String {
+    let mut keys = Vec::new();
+    if operation.can_continue() {
+        keys.push("[C] continue");
+    }
+    if operation.can_skip() {
+        keys.push("[S] skip");
+    }
+    keys.push(if operation.can_continue() {
+        "[A] abort"
+    } else {
+        "[A] reset"
+    });
+    keys.join("  ")
+}
+
+fn jobs_spans(jobs: &[&RunningJob]) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for job in jobs {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            format!(
+                "{} {}… {}s",
+                job.spinner(),
+                job.label,
+                job.started.elapsed().as_secs()
+            ),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    if jobs.iter().any(|job| job.cancellable()) {
+        spans.push(Span::styled(
+            "  [Ctrl+C] cancel",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    spans
+}
+
 fn keys_hint_line(region: Region) -> Line<'static> {
-    let mut text = vec!["[q] quit".to_string(), "[l] local changes".to_string()];
+    let mut text = vec![
+        "[q] quit".to_string(),
+        "[l] local changes".to_string(),
+        "[R] refresh".to_string(),
+    ];
     let specific = region.instructions();
     if !specific.is_empty() {
         text.push("│".to_string());
@@ -139,6 +210,8 @@ fn change_label(change: ChangeType) -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::OperationKind;
+    use ratatui::prelude::Stylize;
 
     #[test]
     fn summarizes_counts_and_order() {
@@ -207,6 +280,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn shows_the_operation_in_progress_with_its_keys() {
+        let status = RepoStatus {
+            operation: Some(RepoOperation {
+                kind: OperationKind::Rebase,
+                progress: Some((3, 7)),
+            }),
+            ..RepoStatus::default()
+        };
+        let content = overview_line(&status)
+            .spans
+            .iter()
+            .map(|s| s.content.clone())
+            .collect::<Vec<_>>()
+            .join("");
+        assert!(content.contains("rebase 3/7  [C] continue  [S] skip  [A] abort"));
+
+        let bisect = RepoOperation {
+            kind: OperationKind::Bisect,
+            progress: None,
+        };
+        assert_eq!(operation_keys(&bisect), "[A] reset");
+    }
+
     #[test]
     fn shows_branch_hints_without_footer_keys() {
         let line = keys_hint_line(Region::Branches);
@@ -218,6 +315,7 @@ mod tests {
             .join("");
         assert!(content.contains("[q] quit"));
         assert!(content.contains("[l] local changes"));
+        assert!(content.contains("[R] refresh"));
         assert!(content.contains("[↑↓] move"));
         assert!(content.contains("[Enter] checkout"));
         assert!(content.contains("[u] update"));
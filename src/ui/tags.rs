@@ -0,0 +1,345 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Widget, Wrap},
+};
+
+use crate::git::{self, Tag};
+use crate::regions::Region;
+use crate::ui::input::TextInput;
+use crate::ui::layout::{centered_rect, viewport};
+
+use super::panel::PanelBlock;
+
+pub type TagsPanel<W = super::panel::Empty> = PanelBlock<W>;
+
+pub fn panel_with_child<W: Widget>(selected: bool, child: W) -> TagsPanel<W> {
+    PanelBlock::with_child(Region::Tags, selected, child)
+}
+
+#[derive(Debug, Default)]
+pub struct TagsState {
+    pub tags: Vec<Tag>,
+    pub status: Option<String>,
+    pub hovered: Option<usize>,
+}
+
+impl TagsState {
+    pub fn refresh(previous_hovered: Option<usize>) -> Self {
+        Self::apply(git::fetch_tags(), previous_hovered)
+    }
+
+    pub fn apply(fetched: Result<Vec<Tag>, String>, previous_hovered: Option<usize>) -> Self {
+        match fetched {
+            Ok(tags) => {
+                let hovered = if tags.is_empty() {
+                    None
+                } else {
+                    Some(previous_hovered.unwrap_or(0).min(tags.len() - 1))
+                };
+                Self {
+                    tags,
+                    status: None,
+                    hovered,
+                }
+            }
+            Err(err) => Self {
+                tags: Vec::new(),
+                status: Some(err),
+                hovered: None,
+            },
+        }
+    }
+
+    pub fn move_hover_up(&mut self) {
+        self.update_hover(|idx, len| (idx + len - 1) % len);
+    }
+
+    pub fn move_hover_down(&mut self) {
+        self.update_hover(|idx, len| (idx + 1) % len);
+    }
+
+    fn update_hover<F: FnOnce(usize, usize) -> usize>(&mut self, next: F) {
+        let len = self.tags.len();
+        if len == 0 {
+            self.hovered = None;
+            return;
+        }
+        self.hovered = Some(match self.hovered {
+            Some(idx) => next(idx, len),
+            None => 0,
+        });
+    }
+
+    pub fn hovered_tag(&self) -> Option<&Tag> {
+        self.hovered.and_then(|idx| self.tags.get(idx))
+    }
+}
+
+pub struct TagsView<'a> {
+    state: &'a TagsState,
+}
+
+impl<'a> TagsView<'a> {
+    pub fn new(state: &'a TagsState) -> Self {
+        Self { state }
+    }
+}
+
+impl Widget for TagsView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(status) = self.state.status.as_deref() {
+            Paragraph::new(status)
+                .style(Style::default().fg(Color::Red))
+                .render(area, buf);
+            return;
+        }
+
+        if self.state.tags.is_empty() {
+            Paragraph::new("No tags").render(area, buf);
+            return;
+        }
+
+        let (start, end) = viewport(self.state.tags.len(), self.state.hovered, area.height);
+        let items: Vec<ListItem> = self.state.tags[start..end]
+            .iter()
+            .enumerate()
+            .map(|(offset, tag)| {
+                let is_hovered = Some(start + offset) == self.state.hovered;
+                let style = if is_hovered {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(tag_line(tag)).style(style)
+            })
+            .collect();
+
+        List::new(items).render(area, buf);
+    }
+}
+
+fn tag_line(tag: &Tag) -> Line<'static> {
+    let mut spans = vec![
+        Span::styled(tag.name.clone(), Style::default().fg(Color::Yellow)),
+        Span::raw(" "),
+        Span::styled(tag.target.clone(), Style::default().fg(Color::Cyan)),
+    ];
+    if let Some(annotation) = &tag.annotation {
+        spans.push(Span::raw(" "));
+        spans.push(Span::raw(annotation.clone()));
+    }
+    Line::from(spans)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TagField {
+    #[default]
+    Name,
+    Message,
+}
+
+/// Names a new tag on `target`; a non-empty message makes it annotated.
+#[derive(Debug, Default, Clone)]
+pub struct TagInput {
+    pub target: String,
+    pub summary: String,
+    pub name: TextInput,
+    pub message: TextInput,
+    pub focus: TagField,
+}
+
+impl TagInput {
+    pub fn new(target: String, summary: String) -> Self {
+        Self {
+            target,
+            summary,
+            ..Self::default()
+        }
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            TagField::Name => TagField::Message,
+            TagField::Message => TagField::Name,
+        };
+    }
+
+    pub fn focused_mut(&mut self) -> &mut TextInput {
+        match self.focus {
+            TagField::Name => &mut self.name,
+            TagField::Message => &mut self.message,
+        }
+    }
+
+    /// The annotation message, or `None` for a lightweight tag.
+    pub fn annotation(&self) -> Option<&str> {
+        let message = self.message.value.trim();
+        (!message.is_empty()).then_some(message)
+    }
+}
+
+pub fn render_tag_popup(area: Rect, buf: &mut Buffer, input: &TagInput) {
+    let popup_area = centered_rect(50, 25, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let kind = if input.annotation().is_some() {
+        "annotated"
+    } else {
+        "lightweight"
+    };
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Tag "),
+            Span::styled(input.target.clone(), Style::default().fg(Color::Yellow)),
+            Span::raw(" "),
+            Span::styled(input.summary.clone(), Style::default().fg(Color::White)),
+        ]),
+        Line::from(""),
+        field_line("Name:    ", &input.name, input.focus == TagField::Name),
+        field_line(
+            "Message: ",
+            &input.message,
+            input.focus == TagField::Message,
+        ),
+        Line::from(""),
+        Line::styled(
+            format!("Creates a {kind} tag; leave the message empty for a lightweight one"),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(Line::from(Span::styled("Create Tag", key)))
+                .title_bottom(Line::from(vec![
+                    Span::styled("[Enter] Create", key),
+                    Span::raw("   "),
+                    Span::styled("[Tab] Switch field", key),
+                    Span::raw("   "),
+                    Span::styled("[Esc] Cancel", key),
+                ]))
+                .borders(Borders::ALL)
+                .border_set(border::THICK)
+                .style(Style::default().fg(Color::Green)),
+        )
+        .render(popup_area, buf);
+}
+
+/// Confirms deleting a tag from the remote as well, which other clones lose
+/// on their next fetch with pruning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTagDeletePrompt {
+    pub name: String,
+}
+
+pub fn render_remote_tag_delete_popup(
+    area: Rect,
+    buf: &mut Buffer,
+    prompt: &RemoteTagDeletePrompt,
+) {
+    let popup_area = centered_rect(50, 30, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Delete tag "),
+            Span::styled(prompt.name.clone(), Style::default().fg(Color::Yellow)),
+            Span::raw(" locally and from the remote?"),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "The tag is removed for everyone who fetches from the remote.",
+            Style::default().fg(Color::Red),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[y] ", key),
+            Span::raw("delete   "),
+            Span::styled("[n] ", key),
+            Span::raw("keep it"),
+        ]),
+    ];
+
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(Line::from(Span::styled("Delete Remote Tag", key)))
+                .title_bottom(Line::from(Span::styled("[Esc] Cancel", key)))
+                .borders(Borders::ALL)
+                .border_set(border::THICK)
+                .style(Style::default().fg(Color::Green)),
+        )
+        .render(popup_area, buf);
+}
+
+fn field_line<'a>(label: &'a str, input: &'a TextInput, focused: bool) -> Line<'a> {
+    if focused {
+        Line::from(input.render_line(label))
+    } else {
+        Line::from(vec![
+            Span::raw(label),
+            Span::styled(input.value.as_str(), Style::default().fg(Color::White)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyCode;
+
+    fn make_tag(name: &str, annotation: Option<&str>) -> Tag {
+        Tag {
+            name: name.to_string(),
+            target: "abc1234".to_string(),
+            annotation: annotation.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn tag_line_shows_name_target_and_annotation() {
+        let text = |tag: &Tag| -> String {
+            tag_line(tag)
+                .spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect()
+        };
+
+        assert_eq!(text(&make_tag("v1.0", Some("First"))), "v1.0 abc1234 First");
+        assert_eq!(text(&make_tag("nightly", None)), "nightly abc1234");
+    }
+
+    #[test]
+    fn refresh_keeps_the_hover_within_the_list() {
+        let tags = vec![make_tag("a", None), make_tag("b", None)];
+        let state = TagsState::apply(Ok(tags), Some(5));
+        assert_eq!(state.hovered, Some(1));
+
+        let empty = TagsState::apply(Ok(Vec::new()), Some(1));
+        assert_eq!(empty.hovered, None);
+    }
+
+    #[test]
+    fn a_blank_message_makes_a_lightweight_tag() {
+        let mut input = TagInput::new("abc1234".to_string(), "first".to_string());
+        input.toggle_focus();
+        input.focused_mut().handle_key(KeyCode::Char(' '));
+        assert_eq!(input.annotation(), None);
+
+        input.focused_mut().handle_key(KeyCode::Char('x'));
+        assert_eq!(input.annotation(), Some("x"));
+    }
+}
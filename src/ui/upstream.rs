@@ -0,0 +1,154 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Widget},
+};
+
+use crate::ui::layout::centered_rect;
+
+/// Picks the remote-tracking branch a local branch tracks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpstreamPicker {
+    pub branch: String,
+    pub current: Option<String>,
+    pub candidates: Vec<String>,
+    pub hovered: usize,
+}
+
+impl UpstreamPicker {
+    /// Starts on the current upstream, else on a same-named branch, preferring `origin`.
+    pub fn new(branch: String, current: Option<String>, candidates: Vec<String>) -> Self {
+        let same_name = |candidate: &String| {
+            candidate
+                .split_once('/')
+                .is_some_and(|(_, name)| name == branch)
+        };
+        let hovered = current
+            .as_ref()
+            .and_then(|current| candidates.iter().position(|c| c == current))
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .position(|c| same_name(c) && c.starts_with("origin/"))
+            })
+            .or_else(|| candidates.iter().position(same_name))
+            .unwrap_or(0);
+        Self {
+            branch,
+            current,
+            candidates,
+            hovered,
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        self.hovered = self.hovered.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.hovered + 1 < self.candidates.len() {
+            self.hovered += 1;
+        }
+    }
+
+    pub fn hovered_candidate(&self) -> Option<&str> {
+        self.candidates.get(self.hovered).map(String::as_str)
+    }
+}
+
+pub fn render_upstream_popup(area: Rect, buf: &mut Buffer, picker: &UpstreamPicker) {
+    let popup_area = centered_rect(50, 50, area);
+    Clear.render(popup_area, buf);
+
+    let key = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let current = picker.current.as_deref().unwrap_or("none");
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!("Upstream of {} (now: {current})", picker.branch),
+            key,
+        )))
+        .title_bottom(Line::from(vec![
+            Span::styled("[Enter] Track", key),
+            Span::raw("   "),
+            Span::styled("[x] Unset", key),
+            Span::raw("   "),
+            Span::styled("[Esc] Cancel", key),
+        ]))
+        .borders(Borders::ALL)
+        .border_set(border::THICK)
+        .style(Style::default().fg(Color::Green));
+    let inner = block.inner(popup_area);
+    block.render(popup_area, buf);
+
+    if picker.candidates.is_empty() {
+        Paragraph::new("No remote-tracking branches; fetch a remote first").render(inner, buf);
+        return;
+    }
+
+    let visible = inner.height as usize;
+    let start = picker
+        .hovered
+        .saturating_sub(visible / 2)
+        .min(picker.candidates.len().saturating_sub(visible));
+    let items: Vec<ListItem> = picker
+        .candidates
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible)
+        .map(|(idx, candidate)| {
+            let marker = if picker.current.as_ref() == Some(candidate) {
+                "* "
+            } else {
+                "  "
+            };
+            let style = if idx == picker.hovered {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(candidate.clone(), Style::default().fg(Color::Cyan)),
+            ]))
+            .style(style)
+        })
+        .collect();
+    List::new(items).render(inner, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<String> {
+        ["fork/feature", "origin/feature", "origin/main"]
+            .map(str::to_string)
+            .to_vec()
+    }
+
+    #[test]
+    fn starts_on_the_current_upstream_or_a_matching_branch() {
+        let picker = UpstreamPicker::new(
+            "feature".to_string(),
+            Some("origin/main".to_string()),
+            candidates(),
+        );
+        assert_eq!(picker.hovered_candidate(), Some("origin/main"));
+
+        let picker = UpstreamPicker::new("feature".to_string(), None, candidates());
+        assert_eq!(picker.hovered_candidate(), Some("origin/feature"));
+
+        let mut picker = UpstreamPicker::new("topic".to_string(), None, candidates());
+        assert_eq!(picker.hovered, 0);
+        picker.move_up();
+        assert_eq!(picker.hovered, 0);
+        picker.move_down();
+        assert_eq!(picker.hovered_candidate(), Some("origin/feature"));
+    }
+}
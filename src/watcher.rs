@@ -0,0 +1,287 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::git;
+
+/// Quiet time after the last change before a refresh fires.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+/// Upper bound on how long a steady stream of changes can hold a refresh back.
+const MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Entries of the git directory whose changes affect what easygit shows.
+const GIT_DIR_ENTRIES: [&str; 10] = [
+    "HEAD",
+    "index",
+    "refs",
+    "packed-refs",
+    "MERGE_HEAD",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "BISECT_LOG",
+    "rebase-merge",
+    "rebase-apply",
+];
+
+/// Watches the working tree and git directory, reporting debounced changes.
+pub struct RepoWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    work_dir: PathBuf,
+    git_dir: PathBuf,
+    ignore: Option<IgnoreRules>,
+    debounce: Debounce,
+}
+
+impl std::fmt::Debug for RepoWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RepoWatcher")
+            .field("git_dir", &self.git_dir)
+            .field("debounce", &self.debounce)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RepoWatcher {
+    pub fn start(path: impl AsRef<Path>) -> Result<Self, String> {
+        let (work_dir, git_dir) = git::repo_dirs_in(path)?;
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)
+            .map_err(|err| format!("Failed to start file watcher: {err}"))?;
+
+        watcher
+            .watch(&work_dir, RecursiveMode::Recursive)
+            .map_err(|err| format!("Failed to watch {}: {err}", work_dir.display()))?;
+        if !git_dir.starts_with(&work_dir) {
+            watcher
+                .watch(&git_dir, RecursiveMode::Recursive)
+                .map_err(|err| format!("Failed to watch {}: {err}", git_dir.display()))?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            ignore: IgnoreRules::load(&work_dir),
+            work_dir,
+            git_dir,
+            debounce: Debounce::default(),
+        })
+    }
+
+    /// Drains pending file events and returns true once a burst of relevant
+    /// changes has settled.
+    pub fn poll(&mut self) -> bool {
+        let now = Instant::now();
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else {
+                // The watcher lost track of something; refresh to be safe.
+                self.debounce.record(now);
+                continue;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            if event
+                .paths
+                .iter()
+                .any(|path| is_relevant(path, &self.git_dir) && !self.is_ignored(path))
+            {
+                self.debounce.record(now);
+            }
+        }
+        self.debounce.take_due(now)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.debounce.first.is_some()
+    }
+
+    /// Whether a work tree path is ignored by git, like build output under
+    /// `target/`. An edited ignore file reloads the rules and always counts.
+    fn is_ignored(&mut self, path: &Path) -> bool {
+        if path.starts_with(&self.git_dir) {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(&self.work_dir) else {
+            return false;
+        };
+        if path.file_name().is_some_and(|name| name == ".gitignore") {
+            self.ignore = IgnoreRules::load(&self.work_dir);
+            return false;
+        }
+        self.ignore
+            .as_mut()
+            .is_some_and(|rules| rules.is_ignored(relative, path.is_dir()))
+    }
+}
+
+/// The repository's ignore rules (`.gitignore` files, `info/exclude` and
+/// `core.excludesFile`), read with gix as paths are looked up.
+struct IgnoreRules {
+    repo: gix::Repository,
+    stack: gix::worktree::Stack,
+}
+
+impl IgnoreRules {
+    fn load(work_dir: &Path) -> Option<Self> {
+        let repo = gix::open(work_dir).ok()?;
+        // Ignore files are read from the work tree, so the index is not needed.
+        let index = gix::index::State::new(repo.object_hash());
+        let stack = repo
+            .excludes(
+                &index,
+                None,
+                gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+            )
+            .ok()?
+            .detach();
+        Some(Self { repo, stack })
+    }
+
+    /// Checks `relative` and each directory above it, since everything inside
+    /// an ignored directory is ignored too.
+    fn is_ignored(&mut self, relative: &Path, is_dir: bool) -> bool {
+        let mut ancestors: Vec<&Path> = relative
+            .ancestors()
+            .filter(|path| !path.as_os_str().is_empty())
+            .collect();
+        ancestors.reverse();
+        ancestors.iter().any(|path| {
+            let dir = *path != relative || is_dir;
+            let mode = dir.then_some(gix::index::entry::Mode::DIR);
+            self.stack
+                .at_path(*path, mode, &self.repo.objects)
+                .is_ok_and(|platform| platform.is_excluded())
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Debounce {
+    first: Option<Instant>,
+    last: Option<Instant>,
+}
+
+impl Debounce {
+    fn record(&mut self, now: Instant) {
+        self.first.get_or_insert(now);
+        self.last = Some(now);
+    }
+
+    fn take_due(&mut self, now: Instant) -> bool {
+        let (Some(first), Some(last)) = (self.first, self.last) else {
+            return false;
+        };
+        let due = now.duration_since(last) >= DEBOUNCE || now.duration_since(first) >= MAX_DELAY;
+        if due {
+            *self = Self::default();
+        }
+        due
+    }
+}
+
+/// Working tree changes always count; inside the git directory only HEAD, the
+/// index, refs and in-progress operation markers do, and never lock files.
+fn is_relevant(path: &Path, git_dir: &Path) -> bool {
+    let Ok(inner) = path.strip_prefix(git_dir) else {
+        return true;
+    };
+    if path.extension().is_some_and(|ext| ext == "lock") {
+        return false;
+    }
+    inner
+        .components()
+        .next()
+        .and_then(|first| first.as_os_str().to_str())
+        .is_some_and(|first| GIT_DIR_ENTRIES.contains(&first))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_git_dir_noise() {
+        let git_dir = Path::new("/repo/.git");
+
+        assert!(is_relevant(Path::new("/repo/src/main.rs"), git_dir));
+        assert!(is_relevant(Path::new("/repo/Cargo.lock"), git_dir));
+        assert!(is_relevant(Path::new("/repo/.git/HEAD"), git_dir));
+        assert!(is_relevant(Path::new("/repo/.git/index"), git_dir));
+        assert!(is_relevant(
+            Path::new("/repo/.git/refs/heads/main"),
+            git_dir
+        ));
+        assert!(!is_relevant(Path::new("/repo/.git/index.lock"), git_dir));
+        assert!(!is_relevant(
+            Path::new("/repo/.git/objects/ab/cdef"),
+            git_dir
+        ));
+        assert!(!is_relevant(Path::new("/repo/.git/logs/HEAD"), git_dir));
+    }
+
+    #[test]
+    fn ignore_rules_skip_ignored_files_and_directories() {
+        let root = std::env::temp_dir().join(format!(
+            "easygit-watcher-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        let status = std::process::Command::new("git")
+            .arg("init")
+            .arg("--quiet")
+            .current_dir(&root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n!keep.log\n").unwrap();
+
+        let mut rules = IgnoreRules::load(&root).unwrap();
+
+        assert!(rules.is_ignored(Path::new("target"), true));
+        assert!(rules.is_ignored(Path::new("target/debug/deps/easygit.d"), false));
+        assert!(rules.is_ignored(Path::new("logs/build.log"), false));
+        assert!(!rules.is_ignored(Path::new("logs/keep.log"), false));
+        assert!(!rules.is_ignored(Path::new("src/main.rs"), false));
+        assert!(!rules.is_ignored(Path::new("src/target.rs"), false));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn debounce_waits_for_quiet_period() {
+        let start = Instant::now();
+        let mut debounce = Debounce::default();
+        assert!(!debounce.take_due(start));
+
+        debounce.record(start);
+        assert!(!debounce.take_due(start + Duration::from_millis(50)));
+        debounce.record(start + Duration::from_millis(100));
+        assert!(!debounce.take_due(start + Duration::from_millis(250)));
+        assert!(debounce.take_due(start + Duration::from_millis(300)));
+        assert!(!debounce.take_due(start + Duration::from_millis(400)));
+    }
+
+    #[test]
+    fn debounce_fires_during_steady_changes() {
+        let start = Instant::now();
+        let mut debounce = Debounce::default();
+
+        for step in 0..10 {
+            let now = start + Duration::from_millis(step * 150);
+            debounce.record(now);
+            if debounce.take_due(now) {
+                assert!(step * 150 >= MAX_DELAY.as_millis() as u64);
+                return;
+            }
+        }
+        panic!("steady changes never triggered a refresh");
+    }
+}